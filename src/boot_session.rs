@@ -0,0 +1,286 @@
+// --- 必要なクレートの説明 ---
+// このファイルは発生日時順のイベントを起動（ブート）ごとの区間に分けるモジュールです
+// 起動を示すイベント（イベントカタログの category = "boot"、12 / 6005 / 6009 など）を区切りとし、
+// 1回の起動で続けて記録される複数の起動イベントは同じ起動として扱います
+// 各起動の終わり方（シャットダウンの記録による正常終了、次の起動時の 41 / 6008 による予期しない終了）、
+// シャットダウンの実行者（1074）・稼働時間を求め、各イベントに起動番号と起動からの経過時間を付与します
+// 起動の一覧（--format boots）と、起動の選択（--boot / --boots）もこのモジュールで扱います
+// chrono: 起動イベント間の間隔・稼働時間の計算用
+// serde: JSON出力用シリアライズ
+
+use std::io::Write; // 書き込み用
+use chrono::{DateTime, Duration, Utc}; // 日時
+use serde::Serialize; // シリアライズ
+use crate::event_types::{serialize_opt_utc, serialize_utc, EventRecord}; // イベントレコード型
+use crate::event_catalog::EventCategory; // 色分けカテゴリ（起動・終了の判定）
+use crate::event_util::{format_display, DisplayTz}; // 日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+use crate::time_window::format_duration; // 期間の表示
+
+/// 同じ起動とみなす起動イベントの間隔（この時間内に続く起動イベントは同じ起動の記録）
+const BOOT_CLUSTER_GAP_MINUTES: i64 = 5;
+/// 起動後、前回の予期しないシャットダウン（41 / 6008）が記録されうる時間（分）
+const UNEXPECTED_REPORT_MINUTES: i64 = 10;
+
+/// 起動の終わり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BootEnd {
+    Clean,   // シャットダウン・再起動の記録あり（正常終了）
+    Dirty,   // シャットダウンの記録がないまま次の起動を迎えた、または次の起動時に予期しないシャットダウンを記録
+    Running, // 監査範囲の終了時点で稼働中（最後の起動でシャットダウンの記録なし）
+}
+
+impl BootEnd {
+    /// 表示名
+    pub fn text(&self) -> &'static str {
+        match self {
+            BootEnd::Clean => t(Msg::BootEndClean),
+            BootEnd::Dirty => t(Msg::BootEndDirty),
+            BootEnd::Running => t(Msg::BootEndRunning),
+        }
+    }
+}
+
+/// 1回の起動（起動イベントから、シャットダウンまたは次の起動まで）
+#[derive(Debug, Clone, Serialize)]
+pub struct BootSession {
+    pub number: u32,                     // 起動番号（監査範囲内の最初の起動が1）
+    #[serde(rename = "start_utc", serialize_with = "serialize_utc")]
+    pub start: DateTime<Utc>,            // 起動日時（最初の起動イベントの日時）
+    #[serde(rename = "end_utc", serialize_with = "serialize_opt_utc")]
+    pub end: Option<DateTime<Utc>>,      // 終了日時（正常終了はシャットダウンの記録、予期しない終了は最後の記録、稼働中はNone）
+    pub end_state: BootEnd,              // 終わり方
+    pub shutdown_initiator: Option<String>, // シャットダウン・再起動の実行者（1074）
+    pub uptime_ms: i64,                  // 稼働時間（ミリ秒、稼働中は最後の記録まで）
+    pub event_count: usize,              // この起動のイベント数
+}
+
+/// イベントの起動番号と起動からの経過時間（各イベントに付与）
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct BootMark {
+    pub number: u32,                     // 起動番号（0は最初の起動イベントより前）
+    #[serde(rename = "start_utc", serialize_with = "serialize_opt_utc")]
+    pub start: Option<DateTime<Utc>>,    // 起動日時（起動番号0はNone）
+    pub since_boot_ms: Option<i64>,      // 起動からの経過時間（ミリ秒）
+}
+
+impl BootMark {
+    /// 表示（例: "boot #2 T+5423s"、最初の起動イベントより前は "boot #0"）
+    pub fn label(&self) -> String {
+        match self.since_boot_ms {
+            Some(ms) => tr(Msg::BootMarkSince, &[&self.number, &(ms / 1000)]),
+            None => tr(Msg::BootMark, &[&self.number]),
+        }
+    }
+}
+
+/// 起動ごとの区間
+#[derive(Debug, Clone, Default)]
+pub struct BootSessions {
+    sessions: Vec<BootSession>,         // 各起動（起動番号1から順、起動番号の付与済みのイベントから求めた場合は起動日時のみ）
+    of_event: Vec<u32>,                 // イベントごとの起動番号（0は最初の起動イベントより前）
+}
+
+impl BootSessions {
+    /// 発生日時順のイベントから起動の区間を求める
+    /// 起動イベントは、最初のもの・前の起動から一定時間以上経過したもの・前の起動の後に終了イベントがあるものを新しい起動とする
+    /// 全イベントに起動番号が付与済み（annotate 後）なら、その起動番号を使う（絞り込み後のイベントでも番号が変わらない）
+    pub fn detect(events: &[EventRecord]) -> BootSessions {
+        if !events.is_empty() && events.iter().all(|e| e.boot.is_some()) {
+            return BootSessions::from_marks(events);
+        }
+        let mut boots = BootSessions::default();
+        let mut last_boot_event: Option<DateTime<Utc>> = None; // 直前の起動イベントの日時
+        let mut shutdown_since_boot = false; // 直前の起動の後に終了イベントがあったか
+        for event in events {
+            match event.category {
+                EventCategory::Boot => {
+                    let new_boot = match last_boot_event {
+                        None => true,
+                        Some(last) => shutdown_since_boot || event.timestamp - last > Duration::minutes(BOOT_CLUSTER_GAP_MINUTES),
+                    };
+                    if new_boot {
+                        boots.sessions.push(BootSession::new(boots.sessions.len() as u32 + 1, event.timestamp));
+                        shutdown_since_boot = false;
+                    }
+                    last_boot_event = Some(event.timestamp);
+                }
+                EventCategory::Shutdown if last_boot_event.is_some() => shutdown_since_boot = true,
+                _ => {}
+            }
+            boots.of_event.push(boots.sessions.len() as u32);
+        }
+        boots.finish(events);
+        boots
+    }
+
+    /// 起動番号の付与済みのイベントから、起動番号と起動日時を復元する
+    fn from_marks(events: &[EventRecord]) -> BootSessions {
+        let mut boots = BootSessions::default();
+        for mark in events.iter().filter_map(|e| e.boot) {
+            boots.of_event.push(mark.number);
+            if let Some(start) = mark.start.filter(|_| !boots.sessions.iter().any(|s| s.number == mark.number)) {
+                boots.sessions.push(BootSession::new(mark.number, start));
+            }
+        }
+        boots
+    }
+
+    /// 各起動の終わり方・終了日時・シャットダウンの実行者・稼働時間を求める
+    fn finish(&mut self, events: &[EventRecord]) {
+        let count = self.sessions.len();
+        let mut last_seen: Vec<DateTime<Utc>> = self.sessions.iter().map(|s| s.start).collect(); // 最後の記録
+        let mut shutdown_at: Vec<Option<DateTime<Utc>>> = vec![None; count]; // 最後のシャットダウンの記録
+        let mut unexpected = vec![false; count]; // 次の起動時に予期しないシャットダウンを記録
+        for (event, &boot) in events.iter().zip(&self.of_event) {
+            let Some(i) = (boot as usize).checked_sub(1) else {
+                continue; // 最初の起動イベントより前
+            };
+            self.sessions[i].event_count += 1;
+            last_seen[i] = event.timestamp;
+            match event.category {
+                EventCategory::Shutdown => {
+                    shutdown_at[i] = Some(event.timestamp);
+                    if event.event_id == 1074 {
+                        self.sessions[i].shutdown_initiator = Some(initiator(event));
+                    }
+                }
+                // 41 / 6008 は次の起動の直後に、前回の起動の終わり方として記録される
+                EventCategory::Unexpected if i > 0 && event.timestamp - self.sessions[i].start <= Duration::minutes(UNEXPECTED_REPORT_MINUTES) => {
+                    unexpected[i - 1] = true;
+                }
+                _ => {}
+            }
+        }
+        for (i, session) in self.sessions.iter_mut().enumerate() {
+            let (end_state, end) = match shutdown_at[i] {
+                _ if unexpected[i] => (BootEnd::Dirty, Some(last_seen[i])),
+                Some(at) => (BootEnd::Clean, Some(at)),
+                None if i + 1 == count => (BootEnd::Running, None),
+                None => (BootEnd::Dirty, Some(last_seen[i])),
+            };
+            session.end_state = end_state;
+            session.end = end;
+            session.uptime_ms = (end.unwrap_or(last_seen[i]) - session.start).num_milliseconds();
+        }
+    }
+
+    /// イベント（detect に渡した順の添字）の起動番号（0は最初の起動イベントより前）
+    pub fn boot_of(&self, index: usize) -> u32 {
+        self.of_event.get(index).copied().unwrap_or(0)
+    }
+
+    /// 起動の数（最後の起動の起動番号）
+    pub fn count(&self) -> u32 {
+        self.sessions.iter().map(|s| s.number).max().unwrap_or(0)
+    }
+
+    /// 起動番号の起動日時（最初の起動イベントの日時、0や範囲外はNone）
+    pub fn start_of(&self, boot: u32) -> Option<DateTime<Utc>> {
+        self.sessions.iter().find(|s| s.number == boot).map(|s| s.start)
+    }
+
+    /// 各起動（起動番号順）
+    pub fn sessions(&self) -> &[BootSession] {
+        &self.sessions
+    }
+}
+
+impl BootSession {
+    /// 起動イベントから起動を生成する（終わり方は finish で決定）
+    fn new(number: u32, start: DateTime<Utc>) -> BootSession {
+        BootSession {
+            number,
+            start,
+            end: None,
+            end_state: BootEnd::Running,
+            shutdown_initiator: None,
+            uptime_ms: 0,
+            event_count: 0,
+        }
+    }
+}
+
+/// 起動番号と起動からの経過時間を各イベントに付与し、起動ごとの区間を返す関数（eventsは発生日時順）
+pub fn annotate(events: &mut [EventRecord]) -> BootSessions {
+    let boots = BootSessions::detect(events);
+    for (index, event) in events.iter_mut().enumerate() {
+        let number = boots.boot_of(index);
+        let start = boots.start_of(number);
+        let since_boot_ms = start.map(|start| (event.timestamp - start).num_milliseconds());
+        event.boot = Some(BootMark { number, start, since_boot_ms });
+    }
+    boots
+}
+
+/// シャットダウン・再起動の実行者（1074 の param7 ユーザー・param1 プロセス・param5 種類）
+fn initiator(event: &EventRecord) -> String {
+    let field = |name: &str| Some(event.data.get(name).unwrap_or("").trim()).filter(|v| !v.is_empty()).unwrap_or("-").to_string();
+    tr(Msg::BootInitiator, &[&field("param7"), &field("param1"), &field("param5")])
+}
+
+/// 起動の選択（--boot N / --boots 指定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BootSelection {
+    Range(u32, u32), // 起動番号の範囲（--boot N は N-N）
+    First(u32),      // 最初のN回の起動（first:N）
+    Last(u32),       // 直近N回の起動（last:N）
+}
+
+impl BootSelection {
+    /// --boots の値を解釈する（"last:5" / "first:3" / "2-4" / "3"、不正な値はNone）
+    pub fn parse(s: &str) -> Option<BootSelection> {
+        let s = s.trim().to_ascii_lowercase();
+        let count = |n: &str| n.trim().parse::<u32>().ok().filter(|&n| n >= 1);
+        if let Some(n) = s.strip_prefix("last:") {
+            return count(n).map(BootSelection::Last);
+        }
+        if let Some(n) = s.strip_prefix("first:") {
+            return count(n).map(BootSelection::First);
+        }
+        if let Some((from, to)) = s.split_once('-') {
+            let (from, to) = (from.trim().parse::<u32>().ok()?, to.trim().parse::<u32>().ok()?);
+            return (from <= to).then_some(BootSelection::Range(from, to));
+        }
+        s.parse::<u32>().ok().map(|n| BootSelection::Range(n, n))
+    }
+
+    /// 起動番号が選択に含まれるか（count は最後の起動の起動番号）
+    pub fn contains(&self, boot: u32, count: u32) -> bool {
+        match *self {
+            BootSelection::Range(from, to) => from <= boot && boot <= to,
+            BootSelection::First(n) => boot >= 1 && boot <= n,
+            BootSelection::Last(n) => boot >= 1 && boot + n > count,
+        }
+    }
+
+    /// 表示（ヘッダの「対象の起動」）
+    pub fn describe(&self) -> String {
+        match *self {
+            BootSelection::Range(from, to) if from == to => tr(Msg::BootSelectionOne, &[&from]),
+            BootSelection::Range(from, to) => tr(Msg::BootSelectionRange, &[&from, &to]),
+            BootSelection::First(n) => tr(Msg::BootSelectionFirst, &[&n]),
+            BootSelection::Last(n) => tr(Msg::BootSelectionLast, &[&n]),
+        }
+    }
+}
+
+/// 起動の一覧をタブ区切りで書き込む関数（--format boots）
+pub fn write_boots(sessions: &[BootSession], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", t(Msg::BootColumns))?; // 列名
+    for session in sessions {
+        writeln!(
+            out,
+            "#{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            session.number,
+            format_display(session.start, tz),
+            session.end.map(|end| format_display(end, tz)).unwrap_or_else(|| t(Msg::BootNoEnd).to_string()),
+            session.end_state.text(),
+            format_duration(Duration::milliseconds(session.uptime_ms)),
+            session.shutdown_initiator.as_deref().unwrap_or("-"),
+            session.event_count
+        )?;
+    }
+    Ok(())
+}
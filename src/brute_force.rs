@@ -0,0 +1,258 @@
+// --- 必要なクレートの説明 ---
+// このファイルはログオン失敗（4625 / 4771 / 4776）からブルートフォース・パスワードスプレーを検出するモジュールです
+// 接続元ごとの検出は、複数のアカウントへの失敗（パスワードスプレー）のみを対象とします
+// 失敗を対象アカウントごと・接続元IPアドレスごとにまとめ、スライディングウィンドウ（--brute-force-window）内の
+// 失敗回数が閾値（--brute-force-threshold）以上になった一連の失敗を検出します
+// 検出結果は閾値を超えた失敗イベントと、その後に続いたログオン成功（4624）に検出事項（Finding）として付与します
+// chrono: ウィンドウ内の失敗回数の計算用
+
+use std::collections::HashMap; // 種別・キー→失敗イベント
+use chrono::Duration; // 期間
+use crate::event_types::{EventRecord, Finding, FindingKind}; // イベントレコード型・検出事項
+use crate::event_util::{format_display, DisplayTz}; // 日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+use crate::time_window::format_duration; // 期間の表示
+
+/// 既定の閾値（ウィンドウ内の失敗回数）
+const DEFAULT_THRESHOLD: usize = 5;
+/// 既定のウィンドウ（分）
+const DEFAULT_WINDOW_MINS: i64 = 5;
+/// 検出事項に列挙するアカウント・接続元の最大数（超えた分は件数のみ）
+const MAX_LISTED: usize = 3;
+
+/// ブルートフォース検出の条件
+#[derive(Debug, Clone, Copy)]
+pub struct BruteForceOptions {
+    pub threshold: usize, // --brute-force-threshold: ウィンドウ内の失敗回数の閾値
+    pub window: Duration, // --brute-force-window: ウィンドウの長さ
+}
+
+impl Default for BruteForceOptions {
+    fn default() -> Self {
+        BruteForceOptions {
+            threshold: DEFAULT_THRESHOLD,                    // 5回
+            window: Duration::minutes(DEFAULT_WINDOW_MINS), // 5分以内
+        }
+    }
+}
+
+/// 失敗をまとめる単位
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum GroupBy {
+    Account, // 対象アカウント（ユーザー名、大文字小文字を区別しない）
+    Source,  // 接続元IPアドレス（複数アカウントへの失敗のみ）
+}
+
+/// ログオン失敗をまとめてブルートフォースを検出し、検出事項を付与する関数（eventsは発生日時順）
+pub fn detect(events: &mut [EventRecord], options: &BruteForceOptions, tz: DisplayTz) {
+    let mut groups: Vec<(GroupBy, String, Vec<usize>)> = Vec::new(); // (単位, 表示名, 失敗イベント)
+    let mut of_key: HashMap<(GroupBy, String), usize> = HashMap::new(); // (単位, キー)→グループ
+    for (index, event) in events.iter().enumerate() {
+        let Some(failure) = &event.failure else {
+            continue; // ログオン失敗以外
+        };
+        let keys = [
+            (GroupBy::Account, failure.target_user.to_lowercase(), failure.account()),
+            (GroupBy::Source, failure.ip_address.clone(), failure.ip_address.clone()),
+        ];
+        for (by, key, label) in keys.into_iter().filter(|(_, key, _)| !key.is_empty()) {
+            let slot = *of_key.entry((by, key)).or_insert_with(|| {
+                groups.push((by, label, Vec::new()));
+                groups.len() - 1
+            });
+            groups[slot].2.push(index);
+        }
+    }
+    let mut findings: Vec<(usize, Finding)> = Vec::new(); // (付与するイベント, 検出事項)
+    for (by, label, members) in &groups {
+        for burst in bursts(events, members, options) {
+            let failures = &members[burst.first..=burst.last];
+            let start = events[failures[0]].timestamp;
+            let end = events[members[burst.last]].timestamp;
+            // 相手側（アカウントごとなら接続元、接続元ごとならアカウント）の一覧
+            let mut others: Vec<String> = Vec::new();
+            for &i in failures {
+                let Some(f) = &events[i].failure else { continue };
+                let other = if *by == GroupBy::Account { f.ip_address.clone() } else { f.account() };
+                if !other.is_empty() && !others.iter().any(|o| o.eq_ignore_ascii_case(&other)) {
+                    others.push(other);
+                }
+            }
+            if *by == GroupBy::Source && others.len() < 2 {
+                continue; // 1つのアカウントへの失敗はアカウントごとの検出で扱う（接続元ごとは複数アカウントへのスプレーのみ）
+            }
+            // その後のログオン成功（最初の失敗以降、最後の失敗からウィンドウの長さ以内）
+            let success = (failures[0] + 1..events.len())
+                .take_while(|&i| events[i].timestamp <= end + options.window)
+                .find(|&i| {
+                    let event = &events[i];
+                    match (event.event_id, &event.logon) {
+                        (4624, Some(logon)) if *by == GroupBy::Account => logon.target_user.eq_ignore_ascii_case(label.rsplit('\\').next().unwrap_or(label)),
+                        (4624, Some(logon)) => logon.ip_address == *label,
+                        _ => false,
+                    }
+                });
+            let outcome = match success {
+                Some(i) => tr(Msg::BruteForceThenSuccess, &[&format_display(events[i].timestamp, tz)]),
+                None => t(Msg::BruteForceNoSuccess).to_string(),
+            };
+            let args: [&dyn std::fmt::Display; 9] = [
+                label,
+                &failures.len(),
+                &format_display(start, tz),
+                &format_display(end, tz),
+                &format_duration(options.window),
+                &burst.peak,
+                &options.threshold,
+                &list_names(&others),
+                &outcome,
+            ];
+            let (kind, message) = match by {
+                GroupBy::Account => (FindingKind::BruteForceAccount, tr(Msg::BruteForceAccount, &args)),
+                GroupBy::Source => (FindingKind::BruteForceSource, tr(Msg::BruteForceSource, &args)),
+            };
+            findings.push((members[burst.crossed], Finding { kind, message }));
+            if let Some(i) = success {
+                let message = match by {
+                    GroupBy::Account => tr(Msg::BruteForceSuccessAccount, &[label, &failures.len()]),
+                    GroupBy::Source => tr(Msg::BruteForceSuccessSource, &[label, &failures.len()]),
+                };
+                findings.push((i, Finding { kind: FindingKind::BruteForceSuccess, message }));
+            }
+        }
+    }
+    for (index, finding) in findings {
+        events[index].findings.push(finding);
+    }
+}
+
+/// 閾値を超えた一連の失敗（グループ内の添字）
+struct Burst {
+    first: usize,   // 最初の失敗
+    last: usize,    // 最後の失敗
+    crossed: usize, // 閾値に達した失敗
+    peak: usize,    // ウィンドウ内の最大失敗回数
+}
+
+/// グループ内の失敗から、ウィンドウ内の失敗回数が閾値以上になった区間を求める関数
+/// 閾値に達したウィンドウに含まれる失敗を印付けし、印の付いた失敗のうち間隔がウィンドウ以内で連続するものを1つの区間とする
+fn bursts(events: &[EventRecord], members: &[usize], options: &BruteForceOptions) -> Vec<Burst> {
+    let time = |k: usize| events[members[k]].timestamp;
+    let mut counts = vec![0usize; members.len()]; // 各失敗で終わるウィンドウ内の失敗回数
+    let mut marked = vec![false; members.len()]; // 閾値に達したウィンドウに含まれるか
+    let mut start = 0; // ウィンドウの先頭
+    for end in 0..members.len() {
+        while time(end) - time(start) > options.window {
+            start += 1;
+        }
+        counts[end] = end - start + 1;
+        if counts[end] >= options.threshold {
+            marked[start..=end].iter_mut().for_each(|m| *m = true);
+        }
+    }
+    let mut bursts: Vec<Burst> = Vec::new();
+    for k in (0..members.len()).filter(|&k| marked[k]) {
+        match bursts.last_mut() {
+            Some(burst) if burst.last + 1 == k && time(k) - time(burst.last) <= options.window => {
+                burst.last = k; // 直前の失敗と続いている
+                burst.peak = burst.peak.max(counts[k]);
+                if counts[burst.crossed] < options.threshold && counts[k] >= options.threshold {
+                    burst.crossed = k;
+                }
+            }
+            _ => bursts.push(Burst { first: k, last: k, crossed: k, peak: counts[k] }),
+        }
+    }
+    bursts
+}
+
+/// アカウント・接続元の一覧の表示（多い場合は先頭のみ列挙して残りは件数、空なら "-"）
+fn list_names(names: &[String]) -> String {
+    if names.is_empty() {
+        return "-".to_string();
+    }
+    let listed = names.iter().take(MAX_LISTED).cloned().collect::<Vec<_>>().join(", ");
+    match names.len().checked_sub(MAX_LISTED).filter(|&rest| rest > 0) {
+        Some(rest) => tr(Msg::BruteForceMore, &[&listed, &rest]),
+        None => listed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_parse::parse_events;
+
+    /// testdata/brute_force/Security.xml のログオン・ログオン失敗（発生日時順）
+    fn fixture() -> Vec<EventRecord> {
+        let xml = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/testdata/brute_force/Security.xml")).unwrap();
+        let mut events = parse_events(&xml, &[4624, 4625, 4771, 4776]).events;
+        events.sort_by(EventRecord::cmp_chronological);
+        events
+    }
+
+    fn detected(threshold: usize, window: Duration) -> Vec<EventRecord> {
+        let mut events = fixture();
+        detect(&mut events, &BruteForceOptions { threshold, window }, DisplayTz::Utc);
+        events
+    }
+
+    /// 指定種別の検出事項を付与されたイベント（UTCの時:分:秒, 対象ユーザー）
+    fn flagged(events: &[EventRecord], kind: FindingKind) -> Vec<(String, String)> {
+        events
+            .iter()
+            .filter(|e| e.findings.iter().any(|f| f.kind == kind))
+            .map(|e| {
+                let user = e.failure.as_ref().map(|f| f.target_user.clone()).or(e.logon.as_ref().map(|l| l.target_user.clone()));
+                (e.timestamp.format("%H:%M:%S").to_string(), user.unwrap_or_default())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn default_options_flag_account_bursts_at_the_threshold() {
+        let events = detected(DEFAULT_THRESHOLD, Duration::minutes(DEFAULT_WINDOW_MINS));
+        let times: Vec<String> = flagged(&events, FindingKind::BruteForceAccount).into_iter().map(|(t, _)| t).collect();
+        // alice は5回目の失敗、admin は 4771 と 4776 をあわせた5回目の失敗で閾値に達する（4分間隔の失敗・2回だけの失敗は対象外）
+        assert_eq!(times, ["06:11:30", "06:32:15"]);
+        let alice = events.iter().find(|e| e.findings.iter().any(|f| f.kind == FindingKind::BruteForceAccount)).unwrap();
+        assert!(alice.findings[0].message.contains("10.0.0.5"), "{}", alice.findings[0].message); // 接続元を列挙
+    }
+
+    #[test]
+    fn threshold_edge() {
+        let events = detected(6, Duration::minutes(5));
+        assert_eq!(flagged(&events, FindingKind::BruteForceAccount), [("06:11:58".to_string(), "alice".to_string())]); // 6回目で到達
+        let events = detected(7, Duration::minutes(5));
+        assert!(flagged(&events, FindingKind::BruteForceAccount).is_empty());
+    }
+
+    #[test]
+    fn window_edge_is_inclusive() {
+        // 07:00 ～ 07:16 の4分間隔の5回の失敗は、ウィンドウがちょうど16分なら閾値に達する
+        let events = detected(5, Duration::minutes(16));
+        assert!(flagged(&events, FindingKind::BruteForceAccount).contains(&("07:16:00".to_string(), "alice".to_string())));
+        let events = detected(5, Duration::minutes(16) - Duration::seconds(1));
+        assert!(!flagged(&events, FindingKind::BruteForceAccount).contains(&("07:16:00".to_string(), "alice".to_string())));
+    }
+
+    #[test]
+    fn password_spraying_from_one_source() {
+        let events = detected(DEFAULT_THRESHOLD, Duration::minutes(DEFAULT_WINDOW_MINS));
+        // 10.0.0.9 から6アカウントへ各1回（5秒間隔）: 接続元ごとの検出は5回目、アカウントごとの検出はなし
+        assert_eq!(flagged(&events, FindingKind::BruteForceSource), [("06:20:20".to_string(), "frank".to_string())]);
+        let spray = events.iter().flat_map(|e| &e.findings).find(|f| f.kind == FindingKind::BruteForceSource).unwrap();
+        assert!(spray.message.contains("10.0.0.9"), "{}", spray.message);
+        // 1つのアカウントだけへの失敗（10.0.0.5 → alice）は接続元ごとには検出しない
+        assert!(!spray.message.contains("10.0.0.5"));
+    }
+
+    #[test]
+    fn success_after_failures() {
+        let events = detected(DEFAULT_THRESHOLD, Duration::minutes(DEFAULT_WINDOW_MINS));
+        assert_eq!(flagged(&events, FindingKind::BruteForceSuccess), [("06:12:30".to_string(), "alice".to_string())]);
+        // 成功が最後の失敗からウィンドウより後なら対象外
+        let events = detected(DEFAULT_THRESHOLD, Duration::seconds(31));
+        assert!(flagged(&events, FindingKind::BruteForceSuccess).is_empty());
+    }
+}
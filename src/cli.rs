@@ -0,0 +1,388 @@
+// --- 必要なクレートの説明 ---
+// このファイルはコマンドライン引数の解析・ヘルプ表示・入力検証をまとめるモジュールです
+// crate::event_output::OutputFormat: --format の値の解釈
+// crate::event_query::list_event_dir: --xml-dir / --evtx-dir のファイル列挙
+// crate::time_window: --since / --until / --last / --boot-offset / --boot-window の値の解釈
+// crate::event_util::DisplayTz: --tz の値の解釈
+// crate::brute_force::BruteForceOptions: --brute-force-threshold / --brute-force-window の値の格納
+// crate::boot_session::BootSelection: --boot / --boots の値の解釈
+// crate::messages: ヘルプ・エラーメッセージ（日本語・英語）
+// ※--event-id がイベントカタログに定義されているかは、カタログ読み込み後に EventCatalog::check_event_ids で検証します
+// ※--profile のプロファイルが定義されているかは、除外フィルタ設定の読み込み後に FilterConfig::profile で検証します
+
+use std::path::{Path, PathBuf}; // ファイルパス
+use crate::event_output::OutputFormat; // 出力形式
+use crate::event_query::list_event_dir; // 保存済みファイルの列挙
+use crate::time_window::{parse_duration, parse_timestamp, WindowOptions}; // 監査範囲の指定
+use crate::event_util::DisplayTz; // 表示タイムゾーン
+use crate::brute_force::BruteForceOptions; // ブルートフォース検出の条件
+use crate::boot_session::BootSelection; // 起動の選択
+use crate::messages::{tr, t, Lang, Msg}; // メッセージカタログ
+
+/// 解析済みのコマンドラインオプション
+#[derive(Debug, Clone)]
+pub struct CliOptions {
+    pub profile: Option<String>,                          // 除外プロファイル名（Noneなら設定の default_profile）
+    pub filters: Vec<PathBuf>,                            // 追加・上書きする除外フィルタ設定（指定順に適用）
+    pub show_excluded: bool,                              // 除外されたイベントも除外理由付きで出力するか
+    pub hide_subtrees: Vec<String>,                       // 子孫ごと除外するプロセス（--hide-subtree）
+    pub only_subtrees: Vec<String>,                       // 子孫のみ出力するプロセス（--only-subtree）
+    pub format: OutputFormat,                             // 出力形式
+    pub csv_bom: bool,                                    // CSVにUTF-8 BOMを付与するか
+    pub output: Option<String>,                           // 出力先（ファイル名テンプレートまたは "-"）
+    pub input_files: Vec<(String, PathBuf)>,              // オフライン解析用XML/EVTXファイル（ログ名, パス）
+    pub event_ids: Vec<u32>,                              // 出力対象のイベントID（空なら全対象）
+    pub catalogs: Vec<PathBuf>,                           // 追加・上書きするイベントカタログ（指定順に適用）
+    pub window: WindowOptions,                            // 監査範囲の指定
+    pub tz: DisplayTz,                                    // 表示タイムゾーン
+    pub brute_force: BruteForceOptions,                   // ブルートフォース検出の条件
+    pub boot_selection: Option<BootSelection>,            // 出力する起動（--boot / --boots、Noneなら全て）
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        CliOptions {
+            profile: None,             // 設定の default_profile
+            filters: Vec::new(),       // 既定の除外フィルタ設定のみ
+            show_excluded: false,      // 除外されたイベントは出力しない
+            hide_subtrees: Vec::new(), // プロセスツリーでの絞り込みなし
+            only_subtrees: Vec::new(),
+            format: OutputFormat::Text, // テキスト
+            csv_bom: false,
+            output: None,              // output.<拡張子>
+            input_files: Vec::new(),   // ライブ取得
+            event_ids: Vec::new(),     // 全対象
+            catalogs: Vec::new(),      // 既定のカタログのみ
+            window: WindowOptions::default(), // 最終起動時刻の前後
+            tz: DisplayTz::default(),  // 日本標準時（Asia/Tokyo）
+            brute_force: BruteForceOptions::default(), // 5分以内に5回
+            boot_selection: None,      // 全ての起動
+        }
+    }
+}
+
+/// コマンドライン解析の結果
+#[derive(Debug, Clone)]
+pub enum CliCommand {
+    Run(Box<CliOptions>), // 解析を実行（オプションが大きいためBox化）
+    Help,                 // --help: 使い方を表示して終了
+    Version,              // --version: バージョンを表示して終了
+}
+
+/// 使い方（--help の表示内容、表示言語はメッセージカタログに従う）
+pub fn help_text() -> String {
+    tr(Msg::HelpText, &[&env!("CARGO_PKG_NAME"), &env!("CARGO_PKG_VERSION"), &env!("CARGO_PKG_NAME")])
+}
+
+/// バージョン表示文字列
+pub fn version_text() -> String {
+    format!("{} {}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"))
+}
+
+/// コマンドライン引数（プログラム名を除く）を解析する関数
+/// 不明なオプション・値の不足・矛盾する指定はErr（エラーメッセージ）を返す
+pub fn parse_args(args: &[String]) -> Result<CliCommand, String> {
+    let mut opts = CliOptions::default(); // 解析結果
+    let mut profile_flag: Option<String> = None; // 指定済みの除外プロファイル（矛盾検出用）
+    let mut format_flag: Option<String> = None;  // 指定済みの出力形式（重複検出用）
+    let mut selection_flag: Option<(String, BootSelection)> = None; // 指定済みの起動の選択（重複検出用）
+    let mut boot_flags = false; // --boot-offset / --boot-window の指定有無（矛盾検出用）
+    let mut since_raw: Option<String> = None; // --since の指定値
+    let mut until_raw: Option<String> = None; // --until の指定値
+    let mut iter = args.iter().peekable(); // 引数を走査
+    while let Some(raw) = iter.next() {
+        // --name=value 形式は名前と値に分割
+        let (name, inline_value) = match raw.split_once('=') {
+            Some((n, v)) if n.starts_with("--") => (n, Some(v.to_string())),
+            _ => (raw.as_str(), None),
+        };
+        // 値を取るオプションの値を取得するクロージャ（次の引数が "--" で始まるオプションなら値の不足とする）
+        let mut value = |flag: &str| -> Result<String, String> {
+            match inline_value.clone().or_else(|| iter.next_if(|v| !v.starts_with("--")).cloned()) {
+                Some(v) if !v.is_empty() => Ok(v),
+                _ => Err(tr(Msg::ValueRequired, &[&flag])),
+            }
+        };
+        match name {
+            "-h" | "--help" if inline_value.is_none() => return Ok(CliCommand::Help), // 使い方を表示
+            "-V" | "--version" if inline_value.is_none() => return Ok(CliCommand::Version), // バージョンを表示
+            "-s0" | "-s1" | "-s2" | "--svchost-level" | "--profile" => {
+                // 除外プロファイル（-s0 / -s1 / -s2 / --svchost-level は既定のプロファイルの別名、異なるプロファイルの同時指定は矛盾）
+                let (flag, profile) = match name {
+                    "--profile" => {
+                        let v = value(name)?;
+                        (format!("--profile {}", v), v)
+                    }
+                    _ => {
+                        let level = if name == "--svchost-level" { value(name)? } else { name[2..].to_string() };
+                        let profile = match level.as_str() {
+                            "0" => "all",
+                            "1" => "services",
+                            "2" => "no-svchost",
+                            _ => return Err(tr(Msg::SvchostLevelInvalid, &[&level])),
+                        };
+                        (format!("-s{}", level), profile.to_string())
+                    }
+                };
+                if let (Some(prev), Some(prev_profile)) = (&profile_flag, &opts.profile) {
+                    if !prev_profile.eq_ignore_ascii_case(&profile) {
+                        return Err(tr(Msg::ProfileConflict, &[prev, &flag]));
+                    }
+                }
+                profile_flag = Some(flag);
+                opts.profile = Some(profile);
+            }
+            "--format" => {
+                // 出力形式（text / json / jsonl / csv / html）
+                let v = value(name)?;
+                let Some(format) = OutputFormat::parse(&v) else {
+                    return Err(tr(Msg::FormatInvalid, &[&v]));
+                };
+                if let Some(prev) = &format_flag {
+                    if OutputFormat::parse(prev) != Some(format) {
+                        return Err(tr(Msg::FormatConflict, &[prev, &v]));
+                    }
+                }
+                format_flag = Some(v);
+                opts.format = format;
+            }
+            "--bom" if inline_value.is_none() => opts.csv_bom = true, // CSVにUTF-8 BOMを付与（Excel向け）
+            "--show-excluded" if inline_value.is_none() => opts.show_excluded = true, // 除外されたイベントも除外理由付きで出力
+            "--bom" | "--show-excluded" | "--help" | "--version" => {
+                return Err(tr(Msg::FlagTakesNoValue, &[&name, raw])); // 値を取らないオプションに値が指定された
+            }
+            "-o" | "--output" => {
+                // 出力先（"-" は値として扱う）
+                let v = value(name).map_err(|_| tr(Msg::OutputRequired, &[&name]))?;
+                if let Some(prev) = &opts.output {
+                    if *prev != v {
+                        return Err(tr(Msg::OutputConflict, &[prev, &v]));
+                    }
+                }
+                opts.output = Some(v);
+            }
+            "--since" | "--until" => {
+                // 監査範囲の開始・終了時刻（--tz 確定後に解釈する）
+                let v = value(name)?;
+                if name == "--since" { since_raw = Some(v) } else { until_raw = Some(v) }
+            }
+            "--tz" => {
+                // 表示タイムゾーン（IANA名 / UTC / local / ±HH:MM）
+                let v = value(name)?;
+                opts.tz = DisplayTz::parse(&v).ok_or_else(|| tr(Msg::TzInvalid, &[&v]))?;
+            }
+            "--lang" => {
+                // 表示言語（ja / en、設定は解析前に peek_lang で反映済みのため値の検証のみ）
+                let v = value(name)?;
+                Lang::parse(&v).ok_or_else(|| tr(Msg::LangInvalid, &[&v]))?;
+            }
+            "--last" | "--boot-offset" | "--boot-window" => {
+                // 期間指定（90m, 1h30m, 2d など）
+                let v = value(name)?;
+                let d = parse_duration(&v).ok_or_else(|| tr(Msg::DurationInvalid, &[&name, &v]))?;
+                match name {
+                    "--last" => opts.window.last = Some(d),
+                    "--boot-offset" => opts.window.boot_offset = d,
+                    _ => opts.window.boot_window = d,
+                }
+                boot_flags |= name != "--last"; // 起動基準の範囲指定あり
+            }
+            "--brute-force-threshold" => {
+                // ブルートフォースとみなすウィンドウ内の失敗回数
+                let v = value(name)?;
+                opts.brute_force.threshold = v.parse::<usize>().ok().filter(|&n| n >= 1).ok_or_else(|| tr(Msg::BruteForceThresholdInvalid, &[&v]))?;
+            }
+            "--brute-force-window" => {
+                // ブルートフォースの判定に使うウィンドウの長さ（5m, 1h など）
+                let v = value(name)?;
+                opts.brute_force.window = parse_duration(&v).filter(|d| *d > chrono::Duration::zero()).ok_or_else(|| tr(Msg::DurationInvalid, &[&name, &v]))?;
+            }
+            "--boot" | "--boots" => {
+                // 出力する起動（--boot N、--boots last:N / first:N / N-M）
+                let v = value(name)?;
+                let selection = match name {
+                    "--boot" => v.trim().parse::<u32>().ok().map(|n| BootSelection::Range(n, n)),
+                    _ => BootSelection::parse(&v),
+                }
+                .ok_or_else(|| tr(Msg::BootSelectionInvalid, &[&name, &v]))?;
+                if let Some((prev, prev_selection)) = &selection_flag {
+                    if *prev_selection != selection {
+                        return Err(tr(Msg::BootSelectionConflict, &[prev, &format!("{} {}", name, v)]));
+                    }
+                }
+                selection_flag = Some((format!("{} {}", name, v), selection));
+                opts.boot_selection = Some(selection);
+            }
+            "--event-id" => {
+                // 出力対象のイベントID（カンマ区切り・複数指定可）
+                let v = value(name)?;
+                for raw_id in v.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                    let id = raw_id.parse::<u32>().map_err(|_| tr(Msg::EventIdInvalid, &[&raw_id]))?;
+                    if !opts.event_ids.contains(&id) {
+                        opts.event_ids.push(id);
+                    }
+                }
+            }
+            "--catalog" => {
+                // イベントカタログ（TOML、複数指定時は指定順に追加・上書き）
+                opts.catalogs.push(PathBuf::from(value(name)?));
+            }
+            "--filter" => {
+                // 除外フィルタ設定（TOML、複数指定時は指定順に追加・上書き）
+                opts.filters.push(PathBuf::from(value(name)?));
+            }
+            "--hide-subtree" => {
+                // 指定プロセスとその子孫のプロセス作成を除外（複数指定可）
+                opts.hide_subtrees.push(value(name)?);
+            }
+            "--only-subtree" => {
+                // 指定プロセスとその子孫のプロセス作成のみ出力（複数指定可）
+                opts.only_subtrees.push(value(name)?);
+            }
+            "--xml-dir" | "--evtx-dir" => {
+                // 保存済みXML/EVTXファイルのディレクトリ（ファイル名=ログ名）
+                let ext = if name == "--xml-dir" { "xml" } else { "evtx" }; // 対象拡張子
+                let dir = value(name)?;
+                let mut files = list_event_dir(Path::new(&dir), ext)?; // ディレクトリ内のファイルを列挙
+                if files.is_empty() {
+                    return Err(tr(Msg::InputDirEmpty, &[&dir, &ext, &name])); // ライブ取得に切り替わらないようエラーとする
+                }
+                opts.input_files.append(&mut files);
+            }
+            "--xml-file" | "--evtx-file" => {
+                // ログ名=ファイルパス 形式で保存済みXML/EVTXファイルを指定
+                let v = value(name)?;
+                let Some((log, path)) = v.split_once('=').filter(|(l, p)| !l.is_empty() && !p.is_empty()) else {
+                    return Err(tr(Msg::InputFileSpecInvalid, &[&name]));
+                };
+                opts.input_files.push((log.to_string(), PathBuf::from(path)));
+            }
+            _ if name.starts_with('-') => {
+                return Err(tr(Msg::UnknownOption, &[raw]));
+            }
+            _ => {
+                return Err(tr(Msg::UnknownArgument, &[raw]));
+            }
+        }
+    }
+    // --- 監査範囲の日時を表示タイムゾーンで解釈 ---
+    for (flag, raw, slot) in [("--since", since_raw, &mut opts.window.since), ("--until", until_raw, &mut opts.window.until)] {
+        if let Some(v) = raw {
+            let dt = parse_timestamp(&v, opts.tz).ok_or_else(|| tr(Msg::TimestampInvalid, &[&flag, &v]))?;
+            *slot = Some(dt);
+        }
+    }
+    // --- オプション間の矛盾チェック ---
+    if opts.csv_bom && opts.format != OutputFormat::Csv {
+        return Err(t(Msg::BomRequiresCsv).to_string());
+    }
+    if opts.window.since.is_some() && opts.window.last.is_some() {
+        return Err(t(Msg::SinceLastConflict).to_string());
+    }
+    if boot_flags && opts.window.is_explicit() {
+        return Err(t(Msg::BootFlagsConflict).to_string());
+    }
+    if opts.boot_selection.is_some() && opts.input_files.is_empty() && !opts.window.is_explicit() {
+        return Err(t(Msg::BootSelectionLiveRange).to_string()); // ライブ取得の既定の範囲は最終起動の前後のみ
+    }
+    if boot_flags && !opts.input_files.is_empty() {
+        return Err(t(Msg::BootFlagsOffline).to_string());
+    }
+    if let (Some(since), Some(until)) = (opts.window.since, opts.window.until) {
+        if since > until {
+            return Err(t(Msg::SinceAfterUntil).to_string());
+        }
+    }
+    Ok(CliCommand::Run(Box::new(opts)))
+}
+
+/// 引数から --lang の指定値を先読みする関数（引数エラーのメッセージも指定言語で表示するため、解析前に使用）
+pub fn peek_lang(args: &[String]) -> Option<Lang> {
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--lang" {
+            return iter.next().and_then(|v| Lang::parse(v));
+        }
+        if let Some(v) = arg.strip_prefix("--lang=") {
+            return Lang::parse(v);
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliCommand, String> {
+        parse_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    fn run(args: &[&str]) -> CliOptions {
+        match parse(args) {
+            Ok(CliCommand::Run(opts)) => *opts,
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_flags_and_arguments_are_rejected() {
+        assert_eq!(parse(&["--frobnicate"]).unwrap_err(), tr(Msg::UnknownOption, &[&"--frobnicate"]));
+        assert_eq!(parse(&["-x"]).unwrap_err(), tr(Msg::UnknownOption, &[&"-x"]));
+        assert_eq!(parse(&["stray"]).unwrap_err(), tr(Msg::UnknownArgument, &[&"stray"]));
+        assert_eq!(parse(&["--bom=yes"]).unwrap_err(), tr(Msg::FlagTakesNoValue, &[&"--bom", &"--bom=yes"]));
+    }
+
+    #[test]
+    fn missing_values_are_rejected() {
+        assert_eq!(parse(&["--format"]).unwrap_err(), tr(Msg::ValueRequired, &[&"--format"]));
+        assert_eq!(parse(&["--tz="]).unwrap_err(), tr(Msg::ValueRequired, &[&"--tz"]));
+        assert_eq!(parse(&["-o"]).unwrap_err(), tr(Msg::OutputRequired, &[&"-o"]));
+    }
+
+    #[test]
+    fn next_flag_is_not_taken_as_a_value() {
+        assert_eq!(parse(&["--output", "--format", "json"]).unwrap_err(), tr(Msg::OutputRequired, &[&"--output"]));
+        assert_eq!(parse(&["--last", "--since", "2025-01-01"]).unwrap_err(), tr(Msg::ValueRequired, &[&"--last"]));
+        let opts = run(&["--output", "-", "--format", "json"]); // "-"（標準出力）は値として扱う
+        assert_eq!(opts.output.as_deref(), Some("-"));
+        assert_eq!(opts.format, OutputFormat::Json);
+    }
+
+    #[test]
+    fn name_equals_value_form() {
+        let opts = run(&["--format=csv", "--bom", "--output=out/{date}.csv", "--event-id=4688,4624", "--last=6h", "--tz=UTC"]);
+        assert_eq!(opts.format, OutputFormat::Csv);
+        assert!(opts.csv_bom);
+        assert_eq!(opts.output.as_deref(), Some("out/{date}.csv"));
+        assert_eq!(opts.event_ids, [4688, 4624]);
+        assert_eq!(opts.window.last, Some(chrono::Duration::hours(6)));
+        assert_eq!(opts.tz, DisplayTz::Utc);
+    }
+
+    #[test]
+    fn conflicting_options_are_rejected() {
+        assert_eq!(parse(&["-s0", "-s2"]).unwrap_err(), tr(Msg::ProfileConflict, &[&"-s0", &"-s2"]));
+        assert!(matches!(parse(&["-s1", "--profile", "services"]), Ok(CliCommand::Run(_)))); // 同じプロファイルの別名は矛盾しない
+        assert_eq!(parse(&["--format", "json", "--format", "csv"]).unwrap_err(), tr(Msg::FormatConflict, &[&"json", &"csv"]));
+        assert_eq!(parse(&["--bom"]).unwrap_err(), t(Msg::BomRequiresCsv));
+        assert_eq!(parse(&["--since", "2025-01-01", "--last", "1h"]).unwrap_err(), t(Msg::SinceLastConflict));
+        assert_eq!(parse(&["--last", "1h", "--boot-window", "2h"]).unwrap_err(), t(Msg::BootFlagsConflict));
+        assert_eq!(parse(&["--since", "2025-01-02", "--until", "2025-01-01"]).unwrap_err(), t(Msg::SinceAfterUntil));
+        assert_eq!(parse(&["--boot", "1", "--boots", "last:2", "--last", "1d"]).unwrap_err(), tr(Msg::BootSelectionConflict, &[&"--boot 1", &"--boots last:2"]));
+        assert_eq!(parse(&["--boots", "last:2"]).unwrap_err(), t(Msg::BootSelectionLiveRange));
+    }
+
+    #[test]
+    fn input_dir_without_matching_files_is_an_error() {
+        let evtx_dir = format!("{}/testdata/evtx", env!("CARGO_MANIFEST_DIR")); // *.evtx のみ
+        assert_eq!(parse(&["--xml-dir", &evtx_dir]).unwrap_err(), tr(Msg::InputDirEmpty, &[&evtx_dir, &"xml", &"--xml-dir"]));
+        assert_eq!(run(&["--evtx-dir", &evtx_dir]).input_files.len(), 2);
+    }
+
+    #[test]
+    fn help_and_version() {
+        assert!(matches!(parse(&["--help"]), Ok(CliCommand::Help)));
+        assert!(matches!(parse(&["-V"]), Ok(CliCommand::Version)));
+    }
+}
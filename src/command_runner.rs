@@ -0,0 +1,96 @@
+// --- 必要なクレートの説明 ---
+// このファイルは外部コマンド（cmd, wevtutil, powershell, AuditPol等）の実行を抽象化するモジュールです
+// std::process::Command: 実コマンド実行用
+// std::cell::RefCell: 記録済み出力の再生順管理用（テスト用の ScriptedRunner）
+
+use std::process::Command; // 外部コマンド実行
+#[cfg(test)]
+use std::cell::RefCell;    // 内部可変性（再生済み出力の管理）
+use crate::messages::{tr, Msg}; // メッセージカタログ
+
+/// 外部コマンドの実行結果
+#[derive(Debug, Clone)]
+pub struct CommandOutput {
+    pub success: bool,  // 終了コードが0か
+    pub stdout: String, // 標準出力（UTF-8に変換済み）
+    pub stderr: String, // 標準エラー出力（UTF-8に変換済み、異常終了の理由の表示用）
+}
+
+/// 外部コマンドを実行するトレイト
+/// init.rs のシステムチェックと wevtutil によるイベントログ取得はすべてこのトレイト経由でコマンドを実行する
+pub trait CommandRunner {
+    /// コマンドを実行し、終了状態と標準出力を返す（起動自体に失敗した場合はErr）
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String>;
+}
+
+/// 実際にプロセスを起動する実装
+pub struct SystemRunner;
+
+impl CommandRunner for SystemRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+        let output = Command::new(program)
+            .args(args)
+            .output()
+            .map_err(|e| tr(Msg::CommandSpawnFailed, &[&program, &e]))?; // 起動失敗
+        Ok(CommandOutput {
+            success: output.status.success(), // 終了状態
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(), // 標準出力
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(), // 標準エラー出力
+        })
+    }
+}
+
+/// 再生用エントリ（プログラム名, 全引数, 結果）
+#[cfg(test)]
+type ScriptEntry = (String, Vec<String>, Result<CommandOutput, String>);
+
+/// 記録済みの標準出力・終了状態を再生する実装（テスト用）
+/// プログラム名と全引数が一致した最初の未使用エントリを返す
+#[cfg(test)]
+#[derive(Default)]
+pub struct ScriptedRunner {
+    entries: RefCell<Vec<ScriptEntry>>, // 未使用の再生用エントリ
+}
+
+#[cfg(test)]
+impl ScriptedRunner {
+    /// 空の再生リストを生成する
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 指定コマンドの実行結果（終了状態・標準出力）を登録する
+    pub fn on(self, program: &str, args: &[&str], success: bool, stdout: &str) -> Self {
+        let output = CommandOutput { success, stdout: stdout.to_string(), stderr: String::new() };
+        self.push(program, args, Ok(output))
+    }
+
+    /// 指定コマンドの異常終了（標準エラー出力付き）を登録する
+    pub fn on_failure(self, program: &str, args: &[&str], stderr: &str) -> Self {
+        let output = CommandOutput { success: false, stdout: String::new(), stderr: stderr.to_string() };
+        self.push(program, args, Ok(output))
+    }
+
+    /// 指定コマンドの起動失敗を登録する
+    pub fn on_error(self, program: &str, args: &[&str], message: &str) -> Self {
+        self.push(program, args, Err(message.to_string()))
+    }
+
+    fn push(self, program: &str, args: &[&str], result: Result<CommandOutput, String>) -> Self {
+        let args = args.iter().map(|a| a.to_string()).collect();
+        self.entries.borrow_mut().push((program.to_string(), args, result));
+        self
+    }
+}
+
+#[cfg(test)]
+impl CommandRunner for ScriptedRunner {
+    fn run(&self, program: &str, args: &[&str]) -> Result<CommandOutput, String> {
+        let mut entries = self.entries.borrow_mut();
+        let index = entries
+            .iter()
+            .position(|(p, a, _)| p.eq_ignore_ascii_case(program) && a.iter().map(String::as_str).eq(args.iter().copied()))
+            .ok_or_else(|| format!("command not registered: {} {}", program, args.join(" ")))?; // 未登録
+        entries.remove(index).2 // 使用済みエントリは取り除く
+    }
+}
@@ -0,0 +1,391 @@
+// --- 必要なクレートの説明 ---
+// このファイルは対象イベントと表示内容（アクション・説明・色分けカテゴリ）を定義するイベントカタログをまとめるモジュールです
+// 既定のカタログ（event_catalog.toml）は実行ファイルに埋め込み、--catalog で指定したファイルで追加・上書きします
+// toml / serde: カタログファイルの読み込み用
+// crate::event_types: イベントのチャネル・EventData（テンプレートの値）
+// crate::messages: 表示言語・エラーメッセージ
+
+use std::path::Path; // ファイルパス
+use serde::{Deserialize, Serialize}; // カタログの読み込み・カテゴリの出力
+use crate::event_types::{Channel, EventData, EventRecord}; // イベントレコード型
+use crate::messages::{lang, t, tr, Lang, Msg}; // メッセージカタログ
+
+/// 既定のイベントカタログ（TOML）
+const DEFAULT_CATALOG: &str = include_str!("event_catalog.toml");
+
+/// イベントの色分けカテゴリ（HTMLレポートの行の色・凡例）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventCategory {
+    Boot,       // 起動
+    Shutdown,   // シャットダウン
+    Unexpected, // 予期しないシャットダウン
+    Logon,      // ログオン
+    LogonFailure, // ログオン失敗
+    Process,    // プロセス作成
+    Cleared,    // ログ消去
+    AuditChange, // 監査ポリシー・システム時刻の変更
+    #[default]
+    Other,      // その他
+}
+
+impl EventCategory {
+    /// 凡例に表示するカテゴリ（表示順）
+    pub const LEGEND: &'static [EventCategory] = &[
+        EventCategory::Boot,
+        EventCategory::Shutdown,
+        EventCategory::Unexpected,
+        EventCategory::Logon,
+        EventCategory::LogonFailure,
+        EventCategory::Process,
+        EventCategory::Cleared,
+        EventCategory::AuditChange,
+    ];
+
+    /// カタログの category の値から決定する
+    pub fn parse(s: &str) -> Option<EventCategory> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "boot" => Some(EventCategory::Boot),
+            "shutdown" => Some(EventCategory::Shutdown),
+            "unexpected" => Some(EventCategory::Unexpected),
+            "logon" => Some(EventCategory::Logon),
+            "logon_failure" => Some(EventCategory::LogonFailure),
+            "process" => Some(EventCategory::Process),
+            "cleared" => Some(EventCategory::Cleared),
+            "audit_change" => Some(EventCategory::AuditChange),
+            "other" => Some(EventCategory::Other),
+            _ => None,
+        }
+    }
+
+    /// HTMLのCSSクラス名
+    pub fn css_class(&self) -> &'static str {
+        match self {
+            EventCategory::Boot => "boot",
+            EventCategory::Shutdown => "shutdown",
+            EventCategory::Unexpected => "unexpected",
+            EventCategory::Logon => "logon",
+            EventCategory::LogonFailure => "logon_failure",
+            EventCategory::Process => "process",
+            EventCategory::Cleared => "cleared",
+            EventCategory::AuditChange => "audit_change",
+            EventCategory::Other => "other",
+        }
+    }
+
+    /// 凡例の表示名
+    pub fn label(&self) -> Msg {
+        match self {
+            EventCategory::Boot => Msg::CatBoot,
+            EventCategory::Shutdown => Msg::CatShutdown,
+            EventCategory::Unexpected => Msg::CatUnexpected,
+            EventCategory::Logon => Msg::CatLogon,
+            EventCategory::LogonFailure => Msg::CatLogonFailure,
+            EventCategory::Process => Msg::CatProcess,
+            EventCategory::Cleared => Msg::CatCleared,
+            EventCategory::AuditChange => Msg::CatAuditChange,
+            EventCategory::Other => Msg::CatOther,
+        }
+    }
+}
+
+/// 表示テンプレートの構成要素
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Text(String),  // そのまま表示する文字列
+    Field(String), // EventData / UserData の項目名（{名前}）
+}
+
+/// 表示テンプレート（{名前} を EventData / UserData の値で置き換える）
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>, // 構成要素
+}
+
+impl Template {
+    /// テンプレート文字列を解釈する（{{ / }} は波括弧そのもの、閉じていない {・対応のない }・空の {} はErr）
+    pub fn parse(s: &str) -> Result<Template, String> {
+        let mut segments = Vec::new();
+        let mut text = String::new(); // 解釈中の文字列部分
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new(); // 項目名
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => return Err(tr(Msg::CatalogTemplateUnclosed, &[&s])),
+                            Some(ch) => name.push(ch),
+                        }
+                    }
+                    let name = name.trim().to_string();
+                    if name.is_empty() {
+                        return Err(tr(Msg::CatalogTemplateEmptyField, &[&s]));
+                    }
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Field(name));
+                }
+                '}' => return Err(tr(Msg::CatalogTemplateStrayBrace, &[&s])),
+                _ => text.push(c),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Template { segments })
+    }
+
+    /// EventData / UserData の値を埋め込んだ文字列を返す（値のない項目は空文字列）
+    pub fn render(&self, data: &EventData) -> String {
+        self.segments
+            .iter()
+            .map(|seg| match seg {
+                Segment::Text(s) => s.as_str(),
+                Segment::Field(name) => data.get(name).unwrap_or_default(),
+            })
+            .collect()
+    }
+}
+
+/// 言語別の表示テンプレート
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LocalizedTemplate {
+    ja: Template, // 日本語
+    en: Template, // 英語
+}
+
+impl LocalizedTemplate {
+    /// 現在の表示言語のテンプレートで文字列を作る
+    pub fn render(&self, data: &EventData) -> String {
+        match lang() {
+            Lang::Ja => self.ja.render(data),
+            Lang::En => self.en.render(data),
+        }
+    }
+}
+
+/// カタログのエントリ（1イベント分の定義）
+#[derive(Debug, Clone)]
+pub struct CatalogEntry {
+    pub id: u32,                         // イベントID
+    pub channel: Channel,                // ログ名
+    pub provider: Option<String>,        // プロバイダ名（Noneなら全プロバイダ）
+    pub category: EventCategory,         // 色分けカテゴリ
+    pub action: LocalizedTemplate,       // アクション
+    pub description: LocalizedTemplate,  // 説明
+}
+
+impl CatalogEntry {
+    /// 同じエントリとみなすキー（ログ名・プロバイダ名・イベントID）が等しいか
+    fn same_key(&self, other: &CatalogEntry) -> bool {
+        self.id == other.id
+            && self.channel.name().eq_ignore_ascii_case(other.channel.name())
+            && match (&self.provider, &other.provider) {
+                (Some(a), Some(b)) => a.eq_ignore_ascii_case(b),
+                (None, None) => true,
+                _ => false,
+            }
+    }
+
+    /// キーの表示用文字列
+    fn key_str(&self) -> String {
+        format!("channel={}, provider={}, id={}", self.channel.name(), self.provider.as_deref().unwrap_or("*"), self.id)
+    }
+}
+
+/// イベントカタログ
+#[derive(Debug, Clone, Default)]
+pub struct EventCatalog {
+    entries: Vec<CatalogEntry>, // エントリ（定義順）
+}
+
+/// カタログファイルの形式
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CatalogFile {
+    #[serde(default)]
+    event: Vec<EntrySpec>, // [[event]] の並び
+}
+
+/// カタログファイルの [[event]] の形式
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EntrySpec {
+    id: u32,                       // イベントID
+    channel: String,               // ログ名
+    provider: Option<String>,      // プロバイダ名
+    category: Option<String>,      // 色分けカテゴリ
+    action: Option<LocalizedText>,      // アクション
+    description: Option<LocalizedText>, // 説明
+    #[serde(default = "default_enabled")]
+    enabled: bool,                 // falseなら既存のエントリを無効化
+}
+
+/// enabled の既定値
+fn default_enabled() -> bool {
+    true
+}
+
+/// 設定ファイル上の表示文字列（両言語共通の文字列、または言語別）
+/// イベントカタログ・除外フィルタ設定で共通に使用
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum LocalizedText {
+    Plain(String),                                    // 両言語共通
+    ByLang { ja: Option<String>, en: Option<String> }, // 言語別（片方のみなら両言語に使用）
+}
+
+impl LocalizedText {
+    /// (日本語, 英語) の組を返す（ja / en の両方がない場合はNone）
+    pub fn pair(&self) -> Option<(&str, &str)> {
+        match self {
+            LocalizedText::Plain(s) => Some((s.as_str(), s.as_str())),
+            LocalizedText::ByLang { ja, en } => match (ja.as_deref(), en.as_deref()) {
+                (Some(ja), Some(en)) => Some((ja, en)),
+                (Some(s), None) | (None, Some(s)) => Some((s, s)),
+                (None, None) => None,
+            },
+        }
+    }
+
+    /// 現在の表示言語の文字列を返す
+    pub fn current(&self) -> &str {
+        match (self.pair(), lang()) {
+            (Some((ja, _)), Lang::Ja) => ja,
+            (Some((_, en)), Lang::En) => en,
+            (None, _) => "",
+        }
+    }
+
+    /// 言語別のテンプレートに変換する
+    fn to_template(&self, field: &str) -> Result<LocalizedTemplate, String> {
+        let (ja, en) = self.pair().ok_or_else(|| tr(Msg::CatalogTextMissing, &[&field]))?;
+        Ok(LocalizedTemplate { ja: Template::parse(ja)?, en: Template::parse(en)? })
+    }
+}
+
+impl EventCatalog {
+    /// 既定のカタログを読み込み、指定されたファイルを順に追加・上書きする
+    pub fn load(paths: &[impl AsRef<Path>]) -> Result<EventCatalog, String> {
+        let mut catalog = EventCatalog::default();
+        catalog.merge_str(DEFAULT_CATALOG, t(Msg::CatalogBuiltin))?; // 既定のカタログ
+        for path in paths {
+            let path = path.as_ref();
+            let text = std::fs::read_to_string(path).map_err(|e| tr(Msg::CatalogReadFailed, &[&path.display(), &e]))?;
+            catalog.merge_str(&text, &path.display().to_string())?; // 利用者のカタログ
+        }
+        if catalog.entries.is_empty() {
+            return Err(t(Msg::CatalogEmpty).to_string());
+        }
+        Ok(catalog)
+    }
+
+    /// TOML文字列のエントリを追加する（同じキーのエントリは置き換え、enabled = false は削除）
+    fn merge_str(&mut self, text: &str, source: &str) -> Result<(), String> {
+        let file: CatalogFile = toml::from_str(text)
+            .map_err(|e| tr(Msg::CatalogInvalid, &[&source, &format!("{}{}", e.message().trim_end().replace('\n', " "), error_position(text, e.span()))]))?;
+        let mut seen: Vec<CatalogEntry> = Vec::new(); // このファイル内のエントリ（重複検出用）
+        for (i, spec) in file.event.iter().enumerate() {
+            let entry_err = |e: String| tr(Msg::CatalogEntryInvalid, &[&source, &(i + 1), &spec.id, &e]); // エントリ単位のエラー
+            let entry = to_entry(spec).map_err(entry_err)?;
+            if seen.iter().any(|s| s.same_key(&entry)) {
+                return Err(entry_err(tr(Msg::CatalogDuplicate, &[&entry.key_str()])));
+            }
+            seen.push(entry.clone());
+            let existing = self.entries.iter().position(|e| e.same_key(&entry)); // 既存の同じキーのエントリ
+            match (existing, spec.enabled) {
+                (Some(pos), true) => self.entries[pos] = entry,   // 上書き
+                (Some(pos), false) => { self.entries.remove(pos); } // 無効化
+                (None, true) => self.entries.push(entry),          // 追加
+                (None, false) => {}                                // 対象なし
+            }
+        }
+        Ok(())
+    }
+
+    /// 取得対象のログ名と、ログごとのイベントID（カタログの定義順、重複なし）
+    pub fn channels(&self) -> Vec<(String, Vec<u32>)> {
+        let mut channels: Vec<(String, Vec<u32>)> = Vec::new();
+        for entry in &self.entries {
+            let name = entry.channel.name();
+            let pos = match channels.iter().position(|(c, _)| c.eq_ignore_ascii_case(name)) {
+                Some(pos) => pos,
+                None => {
+                    channels.push((name.to_string(), Vec::new()));
+                    channels.len() - 1
+                }
+            };
+            if !channels[pos].1.contains(&entry.id) {
+                channels[pos].1.push(entry.id);
+            }
+        }
+        channels
+    }
+
+    /// --event-id の指定値がすべてカタログに定義されているか検証する
+    pub fn check_event_ids(&self, ids: &[u32]) -> Result<(), String> {
+        match ids.iter().find(|id| !self.entries.iter().any(|e| e.id == **id)) {
+            Some(id) => Err(tr(Msg::EventIdUnsupported, &[id])),
+            None => Ok(()),
+        }
+    }
+
+    /// イベントに対応するエントリを返す（プロバイダ指定のエントリを優先、なければプロバイダ省略のエントリ）
+    pub fn lookup(&self, event: &EventRecord) -> Option<&CatalogEntry> {
+        let candidates = || {
+            self.entries
+                .iter()
+                .filter(move |e| e.id == event.event_id && e.channel.name().eq_ignore_ascii_case(event.channel.name()))
+        };
+        candidates()
+            .find(|e| e.provider.as_deref().is_some_and(|p| p.eq_ignore_ascii_case(&event.provider)))
+            .or_else(|| candidates().find(|e| e.provider.is_none()))
+    }
+}
+
+/// [[event]] の内容を検証してエントリに変換する関数
+fn to_entry(spec: &EntrySpec) -> Result<CatalogEntry, String> {
+    let channel = spec.channel.trim();
+    if channel.is_empty() {
+        return Err(t(Msg::CatalogChannelEmpty).to_string());
+    }
+    let provider = spec.provider.as_deref().map(str::trim).filter(|p| !p.is_empty()).map(str::to_string); // 空ならプロバイダ指定なし
+    let category = match &spec.category {
+        Some(c) => EventCategory::parse(c).ok_or_else(|| tr(Msg::CatalogCategoryInvalid, &[c]))?,
+        None => EventCategory::Other,
+    };
+    // 無効化のみのエントリ（enabled = false）は表示内容を省略可
+    let text = |text: &Option<LocalizedText>, field: &str| match text {
+        Some(t) => t.to_template(field),
+        None if !spec.enabled => Ok(LocalizedTemplate::default()),
+        None => Err(tr(Msg::CatalogTextMissing, &[&field])),
+    };
+    Ok(CatalogEntry {
+        id: spec.id,
+        channel: Channel::from_name(channel),
+        provider,
+        category,
+        action: text(&spec.action, "action")?,
+        description: text(&spec.description, "description")?,
+    })
+}
+
+/// TOMLエラーの位置（行・列）を表示用の文字列にする関数
+pub(crate) fn error_position(text: &str, span: Option<std::ops::Range<usize>>) -> String {
+    let Some(span) = span else { return String::new() };
+    let before = &text[..span.start.min(text.len())];
+    let line = before.matches('\n').count() + 1; // 行番号
+    let col = before.chars().rev().take_while(|c| *c != '\n').count() + 1; // 列番号
+    format!(" (line {}, column {})", line, col)
+}
@@ -0,0 +1,209 @@
+// --- 必要なクレートの説明 ---
+// このファイルはイベントの項目値のうち、コードで記録されるもの（LogonType、%%nnnn 形式のメッセージID、
+// 整合性レベルのSID、ログオン失敗の Status / SubStatus）を人が読める表記に変換するモジュールです
+// 元の値は EventData / UserData（data）にそのまま残し、変換結果は項目名→表記の別のマップ（decoded）に格納します
+// crate::messages::lang: 表記の言語の選択用
+
+use crate::event_types::{EventData, LogonType}; // 項目のマップ・ログオンタイプ
+use crate::messages::{lang, Lang}; // 表示言語
+
+/// 代表的なパラメータメッセージ（%%nnnn、msobjs.dll）の表記（ID, 日本語, 英語）
+const PARAMETER_MESSAGES: &[(u32, &str, &str)] = &[
+    // 偽装レベル（ImpersonationLevel）
+    (1832, "識別", "Identification"),
+    (1833, "偽装", "Impersonation"),
+    (1840, "委任", "Delegation"),
+    // はい / いいえ（ElevatedToken、VirtualAccount など）
+    (1842, "はい", "Yes"),
+    (1843, "いいえ", "No"),
+    // トークンの昇格の種類（TokenElevationType）
+    (1936, "既定（UAC無効または組み込みAdministrator、昇格なし）", "TokenElevationTypeDefault (UAC disabled or built-in account)"),
+    (1937, "完全（昇格済みの管理者トークン）", "TokenElevationTypeFull (elevated)"),
+    (1938, "制限付き（昇格していない管理者トークン）", "TokenElevationTypeLimited (not elevated)"),
+    // ログオン失敗の理由（FailureReason）
+    (2304, "ログオン中にエラーが発生しました", "An error occurred during logon"),
+    (2305, "アカウントの有効期限が切れています", "The specified user account has expired"),
+    (2306, "NetLogon コンポーネントが無効です", "The NetLogon component is not active"),
+    (2307, "アカウントがロックアウトされています", "Account locked out"),
+    (2308, "このコンピュータで要求されたログオンの種類が許可されていません", "The user has not been granted the requested logon type at this machine"),
+    (2309, "パスワードの有効期限が切れています", "The specified account's password has expired"),
+    (2310, "アカウントが無効です", "Account currently disabled"),
+    (2311, "ログオン時間の制限に違反しています", "Account logon time restriction violation"),
+    (2312, "このコンピュータへのログオンが許可されていません", "User not allowed to logon at this computer"),
+    (2313, "ユーザー名が不明か、パスワードが正しくありません", "Unknown user name or bad password"),
+    // 監査ポリシーの変更（AuditPolicyChanges）
+    (8448, "成功の監査を削除", "Success removed"),
+    (8449, "成功の監査を追加", "Success added"),
+    (8450, "失敗の監査を削除", "Failure removed"),
+    (8451, "失敗の監査を追加", "Failure added"),
+    // 監査ポリシーのカテゴリ（CategoryId）
+    (8272, "システム", "System"),
+    (8273, "ログオン/ログオフ", "Logon/Logoff"),
+    (8274, "オブジェクトアクセス", "Object Access"),
+    (8275, "特権の使用", "Privilege Use"),
+    (8276, "詳細追跡", "Detailed Tracking"),
+    (8277, "ポリシーの変更", "Policy Change"),
+    (8278, "アカウントの管理", "Account Management"),
+    (8279, "DS アクセス", "DS Access"),
+    (8280, "アカウント ログオン", "Account Logon"),
+    // 監査ポリシーのサブカテゴリ（SubcategoryId、代表的なもの）
+    (12288, "セキュリティ状態の変更", "Security State Change"),
+    (12289, "セキュリティ システムの拡張", "Security System Extension"),
+    (12290, "システムの整合性", "System Integrity"),
+    (12292, "その他のシステム イベント", "Other System Events"),
+    (12544, "ログオン", "Logon"),
+    (12545, "ログオフ", "Logoff"),
+    (12546, "アカウント ロックアウト", "Account Lockout"),
+    (12548, "特殊なログオン", "Special Logon"),
+    (12551, "その他のログオン/ログオフ イベント", "Other Logon/Logoff Events"),
+    (12800, "ファイル システム", "File System"),
+    (12801, "レジストリ", "Registry"),
+    (12802, "カーネル オブジェクト", "Kernel Object"),
+    (12803, "SAM", "SAM"),
+    (12804, "その他のオブジェクト アクセス イベント", "Other Object Access Events"),
+    (12808, "ファイル共有", "File Share"),
+    (13056, "重要な特権の使用", "Sensitive Privilege Use"),
+    (13312, "プロセス作成", "Process Creation"),
+    (13313, "プロセス終了", "Process Termination"),
+    (13568, "監査ポリシーの変更", "Audit Policy Change"),
+    (13569, "認証ポリシーの変更", "Authentication Policy Change"),
+    (13570, "承認ポリシーの変更", "Authorization Policy Change"),
+    (13824, "ユーザー アカウントの管理", "User Account Management"),
+    (13826, "セキュリティ グループの管理", "Security Group Management"),
+    (14336, "資格情報の確認", "Credential Validation"),
+    (14337, "Kerberos サービス チケット操作", "Kerberos Service Ticket Operations"),
+    (14339, "Kerberos 認証サービス", "Kerberos Authentication Service"),
+];
+
+/// 整合性レベル（MandatoryLabel）のSIDの表記（SID, 日本語, 英語）
+const INTEGRITY_LEVELS: &[(&str, &str, &str)] = &[
+    ("S-1-16-0", "信頼されていない", "Untrusted"),
+    ("S-1-16-4096", "低", "Low"),
+    ("S-1-16-8192", "中", "Medium"),
+    ("S-1-16-8448", "中（プラス）", "Medium Plus"),
+    ("S-1-16-12288", "高", "High"),
+    ("S-1-16-16384", "システム", "System"),
+    ("S-1-16-20480", "保護されたプロセス", "Protected Process"),
+];
+
+/// ログオン失敗の理由のNTSTATUS（4625 / 4776 の Status / SubStatus）の表記（コード, 日本語, 英語）
+const NTSTATUS_CODES: &[(u32, &str, &str)] = &[
+    (0xc000005e, "ログオン要求を処理できるログオンサーバーがありません", "No logon servers available"),
+    (0xc0000064, "ユーザー名が存在しません", "User name does not exist"),
+    (0xc000006a, "パスワードが正しくありません", "Wrong password"),
+    (0xc000006d, "ユーザー名または認証情報が正しくありません", "Bad user name or authentication information"),
+    (0xc000006e, "アカウントの制限によりログオンできません", "Account restriction"),
+    (0xc000006f, "ログオン時間の制限外です", "Logon outside authorized hours"),
+    (0xc0000070, "このワークステーションからのログオンは許可されていません", "Logon from unauthorized workstation"),
+    (0xc0000071, "パスワードの有効期限が切れています", "Password expired"),
+    (0xc0000072, "アカウントが無効です", "Account disabled"),
+    (0xc00000dc, "サーバーの状態が正しくありません", "Server in wrong state"),
+    (0xc0000133, "ドメインコントローラーとの時刻のずれが大きすぎます", "Clock skew with the domain controller is too great"),
+    (0xc000015b, "要求されたログオンの種類が許可されていません", "Logon type not granted"),
+    (0xc000018c, "信頼関係に失敗しました", "Trust relationship failed"),
+    (0xc0000192, "NetLogon サービスが開始されていません", "NetLogon service not started"),
+    (0xc0000193, "アカウントの有効期限が切れています", "Account expired"),
+    (0xc0000224, "次回ログオン時にパスワードの変更が必要です", "Password must change at next logon"),
+    (0xc0000225, "Windows のバグ（通常は無害）", "Windows bug (usually benign)"),
+    (0xc0000234, "アカウントがロックアウトされています", "Account locked out"),
+    (0xc00002ee, "ログオン中にエラーが発生しました", "An error occurred during logon"),
+    (0xc0000413, "認証ファイアウォールによりログオンが拒否されました", "Authentication firewall prohibits logon"),
+];
+
+/// Kerberos 事前認証の失敗（4771 の Status）のエラーコードの表記（コード, 日本語, 英語）
+const KERBEROS_CODES: &[(u32, &str, &str)] = &[
+    (0x6, "ユーザー名が存在しません", "Client not found in Kerberos database"),
+    (0x7, "サーバーが存在しません", "Server not found in Kerberos database"),
+    (0xc, "ポリシーによりログオンが拒否されました（ワークステーション・ログオン時間の制限）", "Policy rejects request (workstation or time restriction)"),
+    (0x12, "アカウントが無効・期限切れ・ロックアウトされています", "Client credentials revoked (disabled, expired or locked out)"),
+    (0x17, "パスワードの有効期限が切れています", "Password expired"),
+    (0x18, "事前認証に失敗しました（パスワードが正しくありません）", "Pre-authentication failed (wrong password)"),
+    (0x25, "時刻のずれが大きすぎます", "Clock skew too great"),
+];
+
+/// ログオン失敗の状態コードの表記（4771 は Kerberos のエラーコード、それ以外はNTSTATUS。未知のコードはNone）
+pub fn status_text(event_id: u32, code: u32) -> Option<&'static str> {
+    let table = if event_id == 4771 { KERBEROS_CODES } else { NTSTATUS_CODES };
+    table.iter().find(|(known, _, _)| *known == code).map(|(_, ja, en)| localized(ja, en))
+}
+
+/// EventData / UserData の全項目のうち、変換できる項目の表記を求める関数（変換できない項目は含めない）
+pub fn decode_data(event_id: u32, data: &EventData) -> EventData {
+    let mut decoded = EventData::default();
+    for (name, value) in data.iter() {
+        if let Some(text) = decode_value(event_id, name, value) {
+            decoded.insert(name, &text);
+        }
+    }
+    decoded
+}
+
+/// イベントの詳細に付ける表示（例: "TokenElevationType=%%1937 (完全…), MandatoryLabel=S-1-16-12288 (高)"）
+/// LogonType は [LogonType=...] として別に表示するため含めない（変換できる項目がなければNone）
+pub fn decoded_marker(data: &EventData, decoded: &EventData) -> Option<String> {
+    let items: Vec<String> = decoded
+        .iter()
+        .filter(|(name, _)| *name != "LogonType")
+        .map(|(name, text)| format!("{}={} ({})", name, data.get(name).unwrap_or("").trim(), text))
+        .collect();
+    (!items.is_empty()).then(|| items.join(", "))
+}
+
+/// 1つの項目の値を変換する関数（変換できなければNone）
+fn decode_value(event_id: u32, name: &str, value: &str) -> Option<String> {
+    let value = value.trim();
+    if matches!(event_id, 4625 | 4771 | 4776) && (name == "Status" || name == "SubStatus") {
+        let code = value.strip_prefix("0x").or_else(|| value.strip_prefix("0X")).and_then(|hex| u32::from_str_radix(hex, 16).ok())?;
+        return status_text(event_id, code).map(str::to_string);
+    }
+    if name == "LogonType" {
+        return value.parse::<u32>().ok().and_then(|code| LogonType::from_code(code).name()).map(str::to_string);
+    }
+    if name == "MandatoryLabel" {
+        return INTEGRITY_LEVELS
+            .iter()
+            .find(|(sid, _, _)| sid.eq_ignore_ascii_case(value))
+            .map(|(_, ja, en)| localized(ja, en).to_string());
+    }
+    decode_parameter_messages(value)
+}
+
+/// %%nnnn 形式のメッセージIDを表記に置き換える関数（複数のIDを含む値にも対応、既知のIDを含まなければNone）
+fn decode_parameter_messages(value: &str) -> Option<String> {
+    if !value.contains("%%") {
+        return None;
+    }
+    let mut out = String::new();
+    let mut rest = value;
+    let mut replaced = false; // 既知のIDを1つ以上置き換えたか
+    while let Some(pos) = rest.find("%%") {
+        out.push_str(&rest[..pos]);
+        let after = &rest[pos + 2..];
+        let digits = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        let text = after[..digits]
+            .parse::<u32>()
+            .ok()
+            .and_then(|id| PARAMETER_MESSAGES.iter().find(|(known, _, _)| *known == id))
+            .map(|(_, ja, en)| localized(ja, en));
+        match text {
+            Some(text) => {
+                out.push_str(text);
+                replaced = true;
+            }
+            None => out.push_str(&rest[pos..pos + 2 + digits]), // 未知のIDはそのまま
+        }
+        rest = &after[digits..];
+    }
+    out.push_str(rest);
+    // 複数の値は改行・タブ区切りで記録されるため、1行にまとめる
+    let out = out.split_whitespace().collect::<Vec<_>>().join(" ");
+    replaced.then_some(out)
+}
+
+/// 表示言語の表記を選ぶ関数
+fn localized<'a>(ja: &'a str, en: &'a str) -> &'a str {
+    match lang() {
+        Lang::Ja => ja,
+        Lang::En => en,
+    }
+}
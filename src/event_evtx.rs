@@ -0,0 +1,657 @@
+// --- 必要なクレートの説明 ---
+// このファイルはEVTX（Windows XML Event Log）バイナリファイルを読み込むモジュールです
+// 外部クレートに依存せず、ファイルヘッダ・チャンク・BinXML（テンプレート展開含む）を解析し、
+// wevtutil qe /f:xml と同じ形式のXML文字列に変換します。
+// 変換後のXMLを event_parse::parse_events に渡すことで、ライブ取得時と同じEventRecordが得られます。
+
+use std::cell::Cell; // レコードごとの展開数（入れ子のカーソルで共有）
+use std::path::Path; // ファイルパス
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+const FILE_SIGNATURE: &[u8] = b"ElfFile\0";  // ファイルヘッダのシグネチャ
+const CHUNK_SIGNATURE: &[u8] = b"ElfChnk\0"; // チャンクヘッダのシグネチャ
+const RECORD_SIGNATURE: &[u8] = b"\x2a\x2a\x00\x00"; // イベントレコードのシグネチャ
+const FILE_HEADER_BLOCK_SIZE: usize = 4096; // ファイルヘッダ領域のサイズ
+const CHUNK_SIZE: usize = 65536;            // チャンクのサイズ
+const CHUNK_HEADER_SIZE: usize = 512;       // チャンクヘッダ（文字列・テンプレート表含む）のサイズ
+const RECORD_HEADER_SIZE: usize = 24;       // レコードヘッダのサイズ
+const MAX_NESTING_DEPTH: usize = 64;        // 要素・テンプレート・埋め込みBinXMLの入れ子の上限（自己参照するテンプレート対策）
+const MAX_RECORD_EXPANSIONS: usize = 100_000; // 1レコードの要素・テンプレート・埋め込みBinXMLの展開数の上限（同じテンプレートを多数参照する定義の連鎖対策）
+
+/// EVTXファイルを読み込み、全レコードをwevtutil形式のXML文字列として連結して返す関数
+pub fn read_evtx_file(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| tr(Msg::FileReadFailed, &[&path.display(), &e]))?; // ファイル読み込み
+    let records = evtx_to_xml_records(&bytes).map_err(|e| tr(Msg::EvtxParseFailed, &[&path.display(), &e]))?; // レコード単位のXML
+    Ok(records.concat()) // 連結したXML文字列を返す
+}
+
+/// EVTXのバイト列を解析し、レコードごとのXML文字列を返す関数
+/// 壊れたレコードはスキップし、解析できたものだけを返す
+pub fn evtx_to_xml_records(bytes: &[u8]) -> Result<Vec<String>, String> {
+    // --- ファイルヘッダの検証 ---
+    if bytes.len() < FILE_HEADER_BLOCK_SIZE || !bytes.starts_with(FILE_SIGNATURE) {
+        return Err(t(Msg::EvtxBadHeader).to_string());
+    }
+    let major_version = u16::from_le_bytes([bytes[38], bytes[39]]); // メジャーバージョン
+    if major_version != 3 {
+        return Err(tr(Msg::EvtxUnsupportedVersion, &[&major_version]));
+    }
+    // --- チャンクを順に解析（ヘッダのチャンク数ではなく実際のシグネチャで判定） ---
+    let mut records = Vec::new(); // 結果格納用
+    let mut offset = FILE_HEADER_BLOCK_SIZE; // 最初のチャンク位置
+    while offset + CHUNK_SIZE <= bytes.len() {
+        let chunk = &bytes[offset..offset + CHUNK_SIZE]; // チャンク切り出し
+        offset += CHUNK_SIZE;
+        if !chunk.starts_with(CHUNK_SIGNATURE) {
+            continue; // 未使用・破損チャンクはスキップ
+        }
+        records.extend(parse_chunk_records(chunk)); // チャンク内レコードを解析
+    }
+    Ok(records)
+}
+
+/// 1チャンク内のイベントレコードを解析し、XML文字列のリストを返す関数
+fn parse_chunk_records(chunk: &[u8]) -> Vec<String> {
+    let free_space_offset = u32::from_le_bytes([chunk[48], chunk[49], chunk[50], chunk[51]]) as usize; // 空き領域の開始位置
+    let end = free_space_offset.clamp(CHUNK_HEADER_SIZE, chunk.len()); // レコード領域の終端
+    let mut records = Vec::new(); // 結果格納用
+    let mut pos = CHUNK_HEADER_SIZE; // 最初のレコード位置
+    while pos + RECORD_HEADER_SIZE <= end && &chunk[pos..pos + 4] == RECORD_SIGNATURE {
+        let size = u32::from_le_bytes([chunk[pos + 4], chunk[pos + 5], chunk[pos + 6], chunk[pos + 7]]) as usize; // レコードサイズ
+        if size < RECORD_HEADER_SIZE + 4 || pos + size > end {
+            break; // サイズ不正なら以降は解析しない
+        }
+        // レコードヘッダ（24バイト）の後ろから、末尾のサイズ複製（4バイト）の手前までがBinXML
+        let data_end = pos + size - 4;
+        let expansions = Cell::new(0); // このレコードの展開数
+        let mut cursor = Cursor { data: chunk, pos: pos + RECORD_HEADER_SIZE, end: data_end, depth: 0, expansions: &expansions };
+        if let Ok(nodes) = parse_fragment(&mut cursor, &[], false) {
+            let mut xml = String::new();
+            for node in &nodes {
+                render_node(node, &mut xml); // XML文字列に変換
+            }
+            xml.push('\n');
+            records.push(xml);
+        }
+        pos += size; // 次のレコードへ
+    }
+    records
+}
+
+// --- BinXML解析 ---
+
+/// チャンク内のバイト列を読み進めるカーソル（posはチャンク先頭からのオフセット）
+struct Cursor<'a> {
+    data: &'a [u8], // チャンク全体
+    pos: usize,     // 現在位置
+    end: usize,     // 読み込み可能な終端
+    depth: usize,   // 要素・テンプレート・埋め込みBinXMLの入れ子の深さ
+    expansions: &'a Cell<usize>, // レコード全体での要素・テンプレート・埋め込みBinXMLの展開数
+}
+
+impl Cursor<'_> {
+    fn bytes(&mut self, len: usize) -> Result<&[u8], String> {
+        if self.pos + len > self.end {
+            return Err(tr(Msg::EvtxOutOfBounds, &[&self.pos]));
+        }
+        let s = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(s)
+    }
+    fn u8(&mut self) -> Result<u8, String> {
+        Ok(self.bytes(1)?[0])
+    }
+    fn u16(&mut self) -> Result<u16, String> {
+        let b = self.bytes(2)?;
+        Ok(u16::from_le_bytes([b[0], b[1]]))
+    }
+    fn u32(&mut self) -> Result<u32, String> {
+        let b = self.bytes(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+    fn peek(&self) -> Result<u8, String> {
+        self.data.get(self.pos).copied().filter(|_| self.pos < self.end).ok_or_else(|| t(Msg::EvtxEndOfData).to_string())
+    }
+    fn utf16(&mut self, chars: usize) -> Result<String, String> {
+        let b = self.bytes(chars * 2)?;
+        Ok(utf16_to_string(b))
+    }
+    /// 入れ子を1段深くする（深さ・展開数の上限を超えたら壊れた・悪意のあるファイルとしてErr）
+    fn descend(&mut self) -> Result<(), String> {
+        if self.depth >= MAX_NESTING_DEPTH {
+            return Err(tr(Msg::EvtxTooDeep, &[&MAX_NESTING_DEPTH, &self.pos]));
+        }
+        if self.expansions.get() >= MAX_RECORD_EXPANSIONS {
+            return Err(tr(Msg::EvtxTooLarge, &[&MAX_RECORD_EXPANSIONS, &self.pos]));
+        }
+        self.depth += 1;
+        self.expansions.set(self.expansions.get() + 1);
+        Ok(())
+    }
+    /// 指定範囲を読むカーソル（入れ子の深さ・展開数は引き継ぐ）
+    fn at(&self, pos: usize, end: usize) -> Cursor<'_> {
+        Cursor { data: self.data, pos, end, depth: self.depth, expansions: self.expansions }
+    }
+    /// テンプレート定義・埋め込みBinXMLを読むための1段深いカーソル
+    fn nested(&self, pos: usize, end: usize) -> Result<Cursor<'_>, String> {
+        let mut cur = self.at(pos, end);
+        cur.descend()?;
+        Ok(cur)
+    }
+}
+
+/// 解析結果のXMLノード
+#[derive(Debug, Clone)]
+enum Node {
+    Element { name: String, attrs: Vec<(String, String)>, children: Vec<Node> }, // 要素
+    Text(String),   // テキスト（エスケープ前）
+    Raw(String),    // エスケープ済みの出力（実体参照・文字参照）
+}
+
+/// テンプレートに渡される置換値
+#[derive(Debug, Clone)]
+struct Value {
+    value_type: u8, // 値の型
+    start: usize,   // チャンク内の開始位置
+    size: usize,    // バイト数
+}
+
+/// BinXMLフラグメント（フラグメントヘッダ＋要素/テンプレートインスタンス＋EOF）を解析する関数
+/// in_substitution: 置換値として埋め込まれたBinXMLの場合true（要素の依存IDが省略される）
+fn parse_fragment(cur: &mut Cursor, values: &[Value], in_substitution: bool) -> Result<Vec<Node>, String> {
+    let mut nodes = Vec::new(); // 結果格納用
+    while cur.pos < cur.end {
+        let token = cur.u8()?;
+        match token {
+            0x00 => break, // EOF
+            0x0f => {
+                cur.bytes(3)?; // フラグメントヘッダ（メジャー・マイナー・フラグ）
+            }
+            0x0c => nodes.extend(parse_template_instance(cur)?), // テンプレートインスタンス
+            0x01 | 0x41 => nodes.extend(parse_element(cur, token == 0x41, values, in_substitution)?), // 要素
+            _ => return Err(tr(Msg::EvtxUnexpectedToken, &[&format!("0x{:02x}", token), &(cur.pos - 1)])),
+        }
+    }
+    Ok(nodes)
+}
+
+/// 要素名（チャンク内の名前構造）を読み取る関数
+/// 名前がトークンの直後にインライン配置されている場合は、カーソルをその後ろまで進める
+fn read_name(cur: &mut Cursor) -> Result<String, String> {
+    let name_offset = cur.u32()? as usize; // 名前構造のオフセット
+    let mut name_cur = cur.at(name_offset, cur.data.len());
+    name_cur.u32()?; // 次の文字列オフセット（未使用）
+    name_cur.u16()?; // ハッシュ（未使用）
+    let count = name_cur.u16()? as usize; // 文字数
+    let name = name_cur.utf16(count)?; // 名前本体
+    name_cur.u16()?; // NUL終端
+    if name_offset == cur.pos {
+        cur.pos = name_cur.pos; // インライン配置ならスキップ
+    }
+    Ok(name)
+}
+
+/// 要素（開始タグ～終了タグ）を解析する関数
+/// 省略可能置換で値がない場合など、要素が出力されない場合は空ベクタを返す
+fn parse_element(cur: &mut Cursor, has_attributes: bool, values: &[Value], in_substitution: bool) -> Result<Vec<Node>, String> {
+    if !in_substitution {
+        cur.u16()?; // 依存ID（未使用）
+    }
+    cur.u32()?; // データサイズ（未使用）
+    let name = read_name(cur)?; // 要素名
+    cur.descend()?; // 子要素の入れ子
+    if has_attributes {
+        cur.u32()?; // 属性リストのサイズ（未使用）
+    }
+    // --- 属性 ---
+    let mut attrs = Vec::new(); // 属性リスト
+    loop {
+        let token = cur.peek()?;
+        match token & 0x0f {
+            0x06 => {
+                cur.u8()?;
+                let attr_name = read_name(cur)?; // 属性名
+                let mut parts = Vec::new(); // 属性値を構成するノード
+                while matches!(cur.peek()? & 0x0f, 0x05 | 0x08 | 0x09 | 0x0d | 0x0e) {
+                    let t = cur.u8()?;
+                    parts.extend(parse_content_token(cur, t, values)?);
+                }
+                if !parts.is_empty() {
+                    let mut value = String::new();
+                    for part in &parts {
+                        render_attr_part(part, &mut value); // 属性値を文字列化
+                    }
+                    attrs.push((attr_name, value)); // 値がない（NULL置換）属性は出力しない
+                }
+            }
+            _ => break,
+        }
+    }
+    // --- 開始タグの終了 ---
+    let mut children = Vec::new(); // 子ノード
+    match cur.u8()? {
+        0x02 => {
+            // 子要素・テキストを終了タグまで解析
+            loop {
+                let token = cur.u8()?;
+                match token {
+                    0x04 => break, // 終了タグ
+                    0x01 | 0x41 => children.extend(parse_element(cur, token == 0x41, values, in_substitution)?),
+                    0x0c => children.extend(parse_template_instance(cur)?),
+                    _ => children.extend(parse_content_token(cur, token, values)?),
+                }
+            }
+        }
+        0x03 => {} // 空要素
+        t => return Err(tr(Msg::EvtxBadElementEnd, &[&format!("0x{:02x}", t)])),
+    }
+    cur.depth -= 1;
+    Ok(vec![Node::Element { name, attrs, children }])
+}
+
+/// 値・置換・参照などの内容トークンを解析する関数（トークン種別は読み取り済み）
+fn parse_content_token(cur: &mut Cursor, token: u8, values: &[Value]) -> Result<Vec<Node>, String> {
+    match token {
+        0x05 | 0x45 => {
+            // 値（文字列型のみ出現する）
+            let value_type = cur.u8()?;
+            if value_type != 0x01 {
+                return Err(tr(Msg::EvtxUnsupportedValueType, &[&format!("0x{:02x}", value_type)]));
+            }
+            let count = cur.u16()? as usize;
+            Ok(vec![Node::Text(cur.utf16(count)?)])
+        }
+        0x07 | 0x47 => {
+            // CDATA
+            let count = cur.u16()? as usize;
+            Ok(vec![Node::Text(cur.utf16(count)?)])
+        }
+        0x08 | 0x48 => Ok(vec![Node::Raw(format!("&#{};", cur.u16()?))]), // 文字参照
+        0x09 | 0x49 => Ok(vec![Node::Raw(format!("&{};", read_name(cur)?))]), // 実体参照
+        0x0a => {
+            // 処理命令ターゲット（データは後続の0x0b）
+            let target = read_name(cur)?;
+            let data = if cur.peek()? == 0x0b {
+                cur.u8()?;
+                let count = cur.u16()? as usize;
+                cur.utf16(count)?
+            } else {
+                String::new()
+            };
+            Ok(vec![Node::Raw(format!("<?{} {}?>", target, data))])
+        }
+        0x0d | 0x0e => {
+            // 通常置換・省略可能置換
+            let index = cur.u16()? as usize; // 置換値のインデックス
+            cur.u8()?; // 型（置換値側の型を優先）
+            match values.get(index) {
+                Some(v) => substitute(cur, v),
+                None => Ok(Vec::new()),
+            }
+        }
+        _ => Err(tr(Msg::EvtxUnexpectedContent, &[&format!("0x{:02x}", token), &(cur.pos - 1)])),
+    }
+}
+
+/// テンプレートインスタンスを解析し、テンプレート定義を置換値で展開する関数
+fn parse_template_instance(cur: &mut Cursor) -> Result<Vec<Node>, String> {
+    cur.u8()?; // 不明（常に1）
+    cur.u32()?; // テンプレートID（未使用）
+    let def_offset = cur.u32()? as usize; // テンプレート定義のオフセット
+    let def_size;
+    if def_offset == cur.pos {
+        // 定義がインライン配置されている場合はスキップ
+        cur.u32()?; // 次のテンプレート定義オフセット
+        cur.bytes(16)?; // テンプレートGUID
+        def_size = cur.u32()? as usize; // 定義データサイズ
+        cur.bytes(def_size)?;
+    } else {
+        let mut def_cur = cur.at(def_offset + 20, cur.data.len());
+        def_size = def_cur.u32()? as usize;
+    }
+    // --- 置換値の記述子と値本体 ---
+    let count = cur.u32()? as usize; // 置換値の数
+    let mut descriptors = Vec::with_capacity(count);
+    for _ in 0..count {
+        let size = cur.u16()? as usize; // 値のサイズ
+        let value_type = cur.u8()?; // 値の型
+        cur.u8()?; // 予約
+        descriptors.push((size, value_type));
+    }
+    let mut values = Vec::with_capacity(count);
+    for (size, value_type) in descriptors {
+        values.push(Value { value_type, start: cur.pos, size });
+        cur.bytes(size)?;
+    }
+    // --- テンプレート定義を展開 ---
+    let def_start = def_offset + 24; // 定義ヘッダ（24バイト）の直後がBinXML
+    let mut def_cur = cur.nested(def_start, (def_start + def_size).min(cur.data.len()))?;
+    parse_fragment(&mut def_cur, &values, false)
+}
+
+/// 置換値をノードに変換する関数（埋め込みBinXMLは再帰的に展開）
+fn substitute(cur: &Cursor, value: &Value) -> Result<Vec<Node>, String> {
+    let data = cur.data;
+    if value.size == 0 || value.value_type == 0x00 {
+        return Ok(Vec::new()); // NULL値
+    }
+    if value.value_type == 0x21 {
+        // BinXML（EventData/UserDataなど）
+        let mut cur = cur.nested(value.start, value.start + value.size)?;
+        return parse_fragment(&mut cur, &[], true);
+    }
+    let bytes = &data[value.start..value.start + value.size];
+    Ok(vec![Node::Text(format_value(value.value_type, bytes))])
+}
+
+/// 置換値（BinXML以外）を文字列に変換する関数
+fn format_value(value_type: u8, b: &[u8]) -> String {
+    if value_type & 0x80 != 0 {
+        // 配列型: 文字列配列はNUL区切り、それ以外は固定長要素
+        let base = value_type & 0x7f;
+        if base == 0x01 {
+            let s = utf16_to_string(b);
+            return s.split('\0').filter(|p| !p.is_empty()).collect::<Vec<_>>().join(",");
+        }
+        let elem = match base {
+            0x03 | 0x04 => 1,
+            0x05 | 0x06 => 2,
+            0x07 | 0x08 | 0x0b | 0x0d | 0x14 => 4,
+            0x09 | 0x0a | 0x0c | 0x11 | 0x15 => 8,
+            0x0f | 0x12 => 16,
+            _ => return hex_upper(b),
+        };
+        return b.chunks_exact(elem).map(|c| format_value(base, c)).collect::<Vec<_>>().join(",");
+    }
+    match value_type {
+        0x01 => utf16_to_string(b).trim_end_matches('\0').to_string(), // UTF-16文字列
+        0x02 => String::from_utf8_lossy(b).trim_end_matches('\0').to_string(), // ANSI文字列
+        0x03 => (b[0] as i8).to_string(),
+        0x04 => b[0].to_string(),
+        0x05 => le_u64(b).map(|v| (v as i16).to_string()).unwrap_or_default(),
+        0x06 => le_u64(b).map(|v| (v as u16).to_string()).unwrap_or_default(),
+        0x07 => le_u64(b).map(|v| (v as i32).to_string()).unwrap_or_default(),
+        0x08 => le_u64(b).map(|v| (v as u32).to_string()).unwrap_or_default(),
+        0x09 => le_u64(b).map(|v| (v as i64).to_string()).unwrap_or_default(),
+        0x0a => le_u64(b).map(|v| v.to_string()).unwrap_or_default(),
+        0x0b if b.len() == 4 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]).to_string(),
+        0x0c if b.len() == 8 => f64::from_bits(le_u64(b).unwrap_or_default()).to_string(),
+        0x0d => if le_u64(b).unwrap_or_default() != 0 { "true" } else { "false" }.to_string(), // 真偽値
+        0x0e => hex_upper(b), // バイナリ
+        0x0f if b.len() == 16 => format_guid(b),
+        0x10 | 0x14 | 0x15 => le_u64(b).map(|v| format!("0x{:x}", v)).unwrap_or_default(), // ポインタ・16進数
+        0x11 => le_u64(b).map(format_filetime).unwrap_or_default(), // FILETIME
+        0x12 if b.len() == 16 => format_systemtime(b),
+        0x13 => format_sid(b),
+        _ => hex_upper(b),
+    }
+}
+
+/// リトルエンディアンの整数（最大8バイト）を読み取る関数
+fn le_u64(b: &[u8]) -> Option<u64> {
+    if b.is_empty() || b.len() > 8 {
+        return None;
+    }
+    let mut buf = [0u8; 8];
+    buf[..b.len()].copy_from_slice(b);
+    Some(u64::from_le_bytes(buf))
+}
+
+/// UTF-16LEバイト列を文字列に変換する関数
+fn utf16_to_string(b: &[u8]) -> String {
+    let units: Vec<u16> = b.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// バイト列を大文字16進文字列に変換する関数
+fn hex_upper(b: &[u8]) -> String {
+    b.iter().map(|x| format!("{:02X}", x)).collect()
+}
+
+/// GUIDを {XXXXXXXX-XXXX-XXXX-XXXX-XXXXXXXXXXXX} 形式に変換する関数
+fn format_guid(b: &[u8]) -> String {
+    format!(
+        "{{{:08X}-{:04X}-{:04X}-{}-{}}}",
+        u32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+        u16::from_le_bytes([b[4], b[5]]),
+        u16::from_le_bytes([b[6], b[7]]),
+        hex_upper(&b[8..10]),
+        hex_upper(&b[10..16])
+    )
+}
+
+/// FILETIME（1601年からの100ナノ秒単位）をwevtutilと同じ7桁小数のUTC文字列に変換する関数
+fn format_filetime(ft: u64) -> String {
+    const EPOCH_DIFF_SECS: i64 = 11_644_473_600; // 1601-01-01 と 1970-01-01 の差（秒）
+    let secs = (ft / 10_000_000) as i64 - EPOCH_DIFF_SECS; // UNIX秒
+    let frac = ft % 10_000_000; // 100ナノ秒単位の端数
+    match chrono::DateTime::from_timestamp(secs, 0) {
+        Some(dt) => format!("{}.{:07}Z", dt.format("%Y-%m-%dT%H:%M:%S"), frac),
+        None => ft.to_string(),
+    }
+}
+
+/// SYSTEMTIME構造体をUTC文字列に変換する関数
+fn format_systemtime(b: &[u8]) -> String {
+    let f = |i: usize| u16::from_le_bytes([b[i * 2], b[i * 2 + 1]]);
+    // 年, 月, 曜日, 日, 時, 分, 秒, ミリ秒
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z", f(0), f(1), f(3), f(4), f(5), f(6), f(7))
+}
+
+/// SIDバイナリを S-1-5-21-... 形式に変換する関数
+fn format_sid(b: &[u8]) -> String {
+    if b.len() < 8 {
+        return hex_upper(b);
+    }
+    let revision = b[0]; // リビジョン
+    let count = b[1] as usize; // サブ権限数
+    let authority = b[2..8].iter().fold(0u64, |acc, x| (acc << 8) | *x as u64); // 識別子権限（ビッグエンディアン）
+    let mut sid = format!("S-{}-{}", revision, authority);
+    for i in 0..count {
+        let p = 8 + i * 4;
+        if p + 4 > b.len() {
+            break;
+        }
+        sid.push_str(&format!("-{}", u32::from_le_bytes([b[p], b[p + 1], b[p + 2], b[p + 3]])));
+    }
+    sid
+}
+
+// --- XML出力 ---
+
+/// XML特殊文字をエスケープする関数
+fn escape_xml(s: &str, out: &mut String) {
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '\'' => out.push_str("&apos;"),
+            '"' => out.push_str("&quot;"),
+            _ => out.push(c),
+        }
+    }
+}
+
+/// 属性値の構成ノードを文字列化する関数（要素は出現しない前提）
+fn render_attr_part(node: &Node, out: &mut String) {
+    match node {
+        Node::Text(t) => escape_xml(t, out),
+        Node::Raw(r) => out.push_str(r),
+        Node::Element { .. } => {}
+    }
+}
+
+/// ノードをwevtutil形式（属性は単一引用符、子なし要素は空要素タグ）のXMLに変換する関数
+fn render_node(node: &Node, out: &mut String) {
+    match node {
+        Node::Text(t) => escape_xml(t, out),
+        Node::Raw(r) => out.push_str(r),
+        Node::Element { name, attrs, children } => {
+            out.push('<');
+            out.push_str(name);
+            for (k, v) in attrs {
+                out.push(' ');
+                out.push_str(k);
+                out.push_str("='");
+                out.push_str(v);
+                out.push('\'');
+            }
+            if children.is_empty() {
+                out.push_str("/>");
+            } else {
+                out.push('>');
+                for child in children {
+                    render_node(child, out);
+                }
+                out.push_str("</");
+                out.push_str(name);
+                out.push('>');
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_parse::parse_events;
+    use chrono::{DateTime, TimeZone, Utc};
+
+    /// testdata/evtx の合成EVTX（make_evtx.py で生成）を読み込む
+    fn sample(name: &str) -> Vec<u8> {
+        std::fs::read(Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/evtx").join(name)).unwrap()
+    }
+
+    #[test]
+    fn system_sample_expands_inline_and_shared_templates() {
+        let records = evtx_to_xml_records(&sample("System.evtx")).unwrap();
+        assert_eq!(records.len(), 3);
+        let parsed = parse_events(&records.concat(), &[6005, 6006, 7036]);
+        let ids: Vec<u32> = parsed.events.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids, [6005, 7036, 6006]);
+        let scm = &parsed.events[1];
+        assert_eq!(scm.provider, "Service Control Manager");
+        assert_eq!(scm.record_id, 7002);
+        assert_eq!(scm.timestamp, Utc.with_ymd_and_hms(2025, 7, 10, 6, 3, 50).unwrap());
+        assert_eq!(scm.data.get("param1"), Some("Windows Update"));
+        assert_eq!(scm.data.get("param2"), Some("running"));
+        assert_eq!(scm.computer, "HOST1");
+        assert!(records[1].contains("<EventID Qualifiers='16384'>7036</EventID>"), "{}", records[1]);
+        assert_eq!(parsed.records.len(), 3); // 対象外のIDも含む全イベントの位置
+    }
+
+    #[test]
+    fn security_sample_keeps_event_data_and_escapes() {
+        let records = evtx_to_xml_records(&sample("Security.evtx")).unwrap();
+        let parsed = parse_events(&records.concat(), &[4688, 4624]);
+        assert_eq!(parsed.events.len(), 2);
+        let created = &parsed.events[0];
+        assert_eq!((created.event_id, created.record_id), (4688, 1001));
+        assert_eq!(created.timestamp, Utc.with_ymd_and_hms(2025, 7, 10, 6, 3, 46).unwrap());
+        assert_eq!(created.channel.name(), "Security");
+        assert_eq!(created.data.get("CommandLine"), Some("cmd.exe /c \"a & b\""));
+        assert_eq!(created.data.get("NewProcessName"), Some("C:\\Windows\\System32\\cmd.exe"));
+        let logon = &parsed.events[1];
+        assert_eq!((logon.event_id, logon.record_id), (4624, 1002));
+        assert_eq!(logon.data.get("TargetUserName"), Some("alice"));
+        assert_eq!(logon.data.get("LogonType"), Some("10"));
+        assert!(!records[0].contains("Qualifiers"), "{}", records[0]); // 省略可能置換で値がない属性は出力しない
+    }
+
+    /// testdata/evtx/real/<名前>.evtx（実機のログ）と、同じファイルを wevtutil qe <名前>.evtx /lf:true /f:xml でエクスポートした <名前>.xml を比較する
+    #[test]
+    fn real_samples_match_wevtutil_export() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/evtx/real");
+        let Ok(samples) = crate::event_query::list_event_dir(&dir, "evtx") else {
+            return; // 実機のサンプルを置いていない環境
+        };
+        for (_, evtx) in samples {
+            let exported = crate::event_query::read_event_file(&evtx.with_extension("xml")).unwrap();
+            let marks = |log: &crate::event_types::ParsedLog| -> Vec<(u64, u32, DateTime<Utc>)> {
+                log.records.iter().map(|m| (m.record_id, m.event_id, m.timestamp)).collect()
+            };
+            let expected = parse_events(&exported, &[]);
+            let ids: Vec<u32> = expected.records.iter().map(|m| m.event_id).collect();
+            let expected = parse_events(&exported, &ids);
+            let actual = parse_events(&read_evtx_file(&evtx).unwrap(), &ids);
+            assert!(!expected.records.is_empty(), "{}", evtx.display());
+            assert_eq!(marks(&actual), marks(&expected), "{}", evtx.display());
+            assert_eq!(serde_json::to_value(&actual.events).unwrap(), serde_json::to_value(&expected.events).unwrap(), "{}", evtx.display());
+        }
+    }
+
+    #[test]
+    fn rejects_bad_header_and_version() {
+        assert!(evtx_to_xml_records(b"not an evtx file").is_err());
+        let mut bytes = sample("System.evtx");
+        bytes[38] = 2; // メジャーバージョン
+        assert!(evtx_to_xml_records(&bytes).is_err());
+    }
+
+    #[test]
+    fn self_referencing_template_is_an_error_not_a_stack_overflow() {
+        // チャンク内オフセット512のレコードで、テンプレート定義のオフセットをレコード自身（定義の本体がこの0x0cトークン）に向ける
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        chunk[..8].copy_from_slice(CHUNK_SIGNATURE);
+        let rec = CHUNK_HEADER_SIZE;
+        chunk[rec..rec + 4].copy_from_slice(RECORD_SIGNATURE);
+        chunk[rec + 20..rec + 24].copy_from_slice(&16u32.to_le_bytes()); // 定義データサイズ（def_offset + 20）
+        let body = rec + RECORD_HEADER_SIZE;
+        chunk[body] = 0x0c; // テンプレートインスタンス
+        chunk[body + 1] = 0x01;
+        chunk[body + 6..body + 10].copy_from_slice(&(rec as u32).to_le_bytes()); // 定義のオフセット（本体 = rec + 24 = このトークン）
+        let expansions = Cell::new(0);
+        let mut cur = Cursor { data: &chunk, pos: body, end: body + 64, depth: 0, expansions: &expansions };
+        let err = parse_fragment(&mut cur, &[], false).unwrap_err();
+        assert_eq!(err, tr(Msg::EvtxTooDeep, &[&MAX_NESTING_DEPTH, &body]));
+        // ファイル全体の読み込みでは壊れたレコードとしてスキップする
+        let size = 64u32;
+        chunk[rec + 4..rec + 8].copy_from_slice(&size.to_le_bytes());
+        chunk[48..52].copy_from_slice(&((rec + size as usize) as u32).to_le_bytes());
+        let mut bytes = sample("System.evtx")[..FILE_HEADER_BLOCK_SIZE].to_vec();
+        bytes.extend_from_slice(&chunk);
+        assert_eq!(evtx_to_xml_records(&bytes).unwrap(), Vec::<String>::new());
+    }
+
+    #[test]
+    fn fan_out_of_templates_is_bounded_per_record() {
+        // テンプレート定義 T0..T20 を並べ、Ti の本体は T(i-1) のインスタンス4つ（T0 は空）: 入れ子は浅いが展開数は 4^20
+        const FAN_OUT: usize = 4;
+        const LEVELS: usize = 20;
+        let mut chunk = vec![0u8; CHUNK_SIZE];
+        let instance = |def: usize| {
+            let mut b = vec![0x0c, 0x01, 0, 0, 0, 0]; // テンプレートインスタンス・不明・テンプレートID
+            b.extend_from_slice(&(def as u32).to_le_bytes()); // 定義のオフセット
+            b.extend_from_slice(&0u32.to_le_bytes()); // 置換値の数
+            b
+        };
+        let mut defs = Vec::new(); // 各定義のオフセット
+        let mut pos = 1024;
+        for level in 0..=LEVELS {
+            let body: Vec<u8> = match defs.last() {
+                Some(&prev) if level > 0 => (0..FAN_OUT).flat_map(|_| instance(prev)).chain([0x00]).collect(),
+                _ => vec![0x00],
+            };
+            chunk[pos + 20..pos + 24].copy_from_slice(&(body.len() as u32).to_le_bytes()); // 定義データサイズ
+            chunk[pos + 24..pos + 24 + body.len()].copy_from_slice(&body);
+            defs.push(pos);
+            pos += 24 + body.len();
+        }
+        let record = instance(*defs.last().unwrap());
+        chunk[CHUNK_HEADER_SIZE..CHUNK_HEADER_SIZE + record.len()].copy_from_slice(&record);
+        let expansions = Cell::new(0);
+        let mut cur = Cursor { data: &chunk, pos: CHUNK_HEADER_SIZE, end: CHUNK_HEADER_SIZE + record.len(), depth: 0, expansions: &expansions };
+        let err = parse_fragment(&mut cur, &[], false).unwrap_err();
+        assert!(err.contains(&MAX_RECORD_EXPANSIONS.to_string()), "{}", err);
+        assert_eq!(expansions.get(), MAX_RECORD_EXPANSIONS);
+        // 自身のインスタンスを複数持つテンプレートも、展開せずにエラーとなる
+        let top = *defs.last().unwrap();
+        let body: Vec<u8> = (0..FAN_OUT).flat_map(|_| instance(top)).chain([0x00]).collect();
+        chunk[top + 24..top + 24 + body.len()].copy_from_slice(&body);
+        let expansions = Cell::new(0);
+        let mut cur = Cursor { data: &chunk, pos: CHUNK_HEADER_SIZE, end: CHUNK_HEADER_SIZE + record.len(), depth: 0, expansions: &expansions };
+        assert!(parse_fragment(&mut cur, &[], false).is_err());
+        assert!(expansions.get() <= MAX_NESTING_DEPTH);
+    }
+}
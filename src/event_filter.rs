@@ -1,100 +1,390 @@
 // --- 必要なクレートの説明 ---
-// このファイルは除外判定・svchostロジックをまとめるモジュールです
+// このファイルは除外判定をまとめるモジュールです
+// 除外ルールは名前付きのプロファイルとして除外フィルタ設定（TOML）に定義します。
+// --hide-subtree / --only-subtree によるプロセスツリー（祖先）での絞り込みもここで判定します
+// 既定の設定（event_filter.toml）は実行ファイルに埋め込み、--filter で指定したファイルで追加・上書きします
 // crate::event_types::EventRecord: イベントレコード型（判定対象）
+// crate::event_util::normalize_image_path_in: プロセスのパスの正規化（表記ゆれ・.. によるすり抜け防止）
+// パスのパターンの %SystemRoot%\... は、解析対象のシステムルート（C:\Windows 以外も可）配下のパスと照合します
+// regex: ワイルドカード・コマンドラインの照合用
+// toml / serde: 除外フィルタ設定の読み込み用
 
+use std::path::Path; // ファイルパス
+use regex::{Regex, RegexBuilder}; // 正規表現
+use serde::Deserialize; // 設定の読み込み
 use crate::event_types::EventRecord; // イベントレコード型をインポート
+use crate::event_util::{file_name_of, normalize_image_path_in, system_relative, SYSTEM_ROOT_TOKEN}; // プロセスのパスの正規化
+use crate::event_catalog::{error_position, LocalizedText}; // 言語別の表示文字列・設定ファイルのエラー位置
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
 
-// 除外プロセスリスト（1行1プロセス＋コメントで可読性向上）
-const EXCLUDE_PROCS: &[&str] = &[
-    "ctfmon.exe",                 // 入力関連
-    "explorer.exe",               // エクスプローラ
-    "OneDrive.exe",               // OneDrive
-    "runtimebroker.exe",          // ランタイムブローカー
-    "RuntimeBroker.exe",          // ランタイムブローカー（大文字）
-    "sihost.exe",                 // Shell Infrastructure Host
-    "startmenuexperiencehost.exe",// スタートメニュー
-    "StartMenuExperienceHost.exe",// スタートメニュー（大文字）
-    "taskhostw.exe",              // タスクホスト
-    "ProcessListEnd",             // プロセスリスト終端
-    "SearchIndexer.exe",          // 検索インデクサ
-    "SearchHost.exe",             // 検索ホスト
-    "ShellExperienceHost.exe",    // シェル体験ホスト
-    "smartscreen.exe",            // SmartScreen
-    "dwm.exe",                    // デスクトップウィンドウマネージャ
-    "lsass.exe",                  // ローカルセキュリティ
-    "lsm.exe",                    // ローカルセッションマネージャ
-    "services.exe",               // サービスコントローラ
-    "wininit.exe",                // Windows初期化
-    "winlogon.exe",               // ログオンプロセス
-    "csrss.exe",                  // クライアントサーバランタイム
-    "spoolsv.exe",                // プリントスプーラ
-    "audiodg.exe",                // オーディオデバイスグラフ
-    "fontdrvhost.exe",            // フォントドライバホスト
-    "SystemSettings.exe",         // システム設定
-    "SecurityHealthSystray.exe",  // セキュリティ通知
-    "conhost.exe",                // コンソールホスト
-    "msedge.exe",                 // Microsoft Edge
-    "chrome.exe",                 // Google Chrome
-    "firefox.exe"                 // Firefox
-];
-// svchost.exeで起動される代表的なサービス名リスト（1行1サービス＋コメントで可読性向上）
-const EXCLUDE_SVCHOST_SERVICES: &[&str] = &[
-    "wuauserv",           // Windows Update
-    "Dnscache",           // DNS Client
-    "Dhcp",               // DHCP Client
-    "EventLog",           // イベントログ
-    "lmhosts",            // LMHOSTS
-    "Themes",             // テーマ
-    "ProfSvc",            // ユーザープロファイル
-    "BITS",               // BITS
-    "Winmgmt",            // WMI
-    "Schedule",           // タスクスケジューラ
-    "CryptSvc",           // 暗号化サービス
-    "AudioSrv",           // オーディオ
-    "LanmanWorkstation",  // ワークステーション
-    "LanmanServer",       // サーバ
-    "wscsvc",             // セキュリティセンター
-    "w32time",            // 時刻同期
-    "EventSystem",        // イベントシステム
-    "PlugPlay",           // プラグアンドプレイ
-    "Power",              // 電源
-    "Spooler",            // プリントスプーラ
-    "Netman",             // ネットワーク接続
-    "WlanSvc",            // WLAN
-    "Wecsvc",             // イベントコレクタ
-    "RemoteRegistry",     // リモートレジストリ
-    "SessionEnv",         // リモートデスクトップ
-    "TermService",        // ターミナルサービス
-    "WinDefend"           // Windows Defender
-];
-
-/// 除外プロセス・サービス判定、svchost出力レベル判定など
-pub fn should_exclude(event: &EventRecord, svchost_level: u8, cmdline_audit_enabled: bool) -> bool {
-    let app_name_lower = event.proc_info.to_ascii_lowercase(); // プロセス名を小文字化
-    let is_svchost = app_name_lower.ends_with("svchost.exe"); // svchost.exeか判定
-    let should_exclude_proc = EXCLUDE_PROCS.iter().any(|p| app_name_lower.ends_with(&p.to_ascii_lowercase())); // 除外プロセス判定
-    // svchost.exeサービス名抽出（簡易）
-    let mut svchost_service_name = String::new(); // サービス名初期化
-    if is_svchost {
-        if let Some(idx) = event.proc_info.find("-s ") { // コマンドラインに-sが含まれるか
-            let rest = &event.proc_info[idx+3..]; // -s以降を取得
-            if let Some(end) = rest.find(' ') { // 次の空白まで
-                svchost_service_name = rest[..end].to_string(); // サービス名抽出
-            } else {
-                svchost_service_name = rest.to_string(); // 末尾まで
+/// 既定の除外フィルタ設定（TOML）
+const DEFAULT_FILTER: &str = include_str!("event_filter.toml");
+
+/// ルールの動作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleAction {
+    Exclude, // 除外
+    Include, // 除外せず出力（以降のルールを評価しない）
+}
+
+/// 除外ルール（指定された条件すべてに一致した場合に一致）
+#[derive(Debug, Clone)]
+pub struct FilterRule {
+    pub name: String,              // ルール名
+    pub action: RuleAction,        // 動作
+    image: Vec<Regex>,             // プロセスのフルパス（正規化済みのパスと照合）
+    file_name: Vec<Regex>,         // プロセスのファイル名
+    parent: ImagePattern,          // 親プロセス
+    ancestor: ImagePattern,        // 祖先のプロセス（プロセスツリー上の親・親の親…）
+    service: Vec<String>,          // svchost.exe のサービス名（-s）
+    service_group: Vec<String>,    // svchost.exe のサービスグループ（-k）
+    user: Vec<Regex>,              // ユーザー名
+    event_id: Vec<u32>,            // イベントID
+    command_line: Option<Regex>,   // コマンドラインの正規表現
+    cmdline_audit: Option<bool>,   // コマンドライン監査の状態
+}
+
+/// 照合対象のイベント情報
+struct Subject<'a> {
+    image: String,         // プロセスのフルパス（正規化済み）
+    parent: String,        // 親プロセスのフルパス（正規化済み）
+    ancestors: &'a [String], // 祖先のプロセスのフルパス（正規化済み、親から順）
+    system_root: &'a str,  // システムルート（正規化済み）
+    event: &'a EventRecord, // イベント本体
+}
+
+/// 正規化済みのパスの照合用の表記（フルパスと、システムルート配下なら %systemroot%\... の表記）
+fn path_forms(path: &str, system_root: &str) -> impl Iterator<Item = String> {
+    std::iter::once(path.to_string()).chain(system_relative(path, system_root))
+}
+
+/// パターンの正規化（%SystemRoot% / %windir% / \SystemRoot\ は %systemroot% のまま残し、照合時にシステムルートと対応付ける）
+fn normalize_pattern(pattern: &str) -> String {
+    normalize_image_path_in(pattern, SYSTEM_ROOT_TOKEN)
+}
+
+/// プロセスのパターン（\ を含むパターンはフルパス、含まないパターンはファイル名として照合）
+#[derive(Debug, Clone, Default)]
+struct ImagePattern {
+    paths: Vec<Regex>, // フルパスのパターン（正規化済み）
+    names: Vec<Regex>, // ファイル名のパターン
+}
+
+impl ImagePattern {
+    /// ワイルドカードのパターンを変換する
+    fn compile(patterns: Vec<String>) -> Result<ImagePattern, String> {
+        let (paths, names): (Vec<String>, Vec<String>) = patterns.into_iter().partition(|p| p.contains(['\\', '/']));
+        Ok(ImagePattern {
+            paths: paths.iter().map(|p| glob_regex(&normalize_pattern(p))).collect::<Result<_, _>>()?,
+            names: names.iter().map(|p| glob_regex(p)).collect::<Result<_, _>>()?,
+        })
+    }
+
+    /// パターンが指定されていないか
+    fn is_empty(&self) -> bool {
+        self.paths.is_empty() && self.names.is_empty()
+    }
+
+    /// 正規化済みのパスがいずれかのパターンに一致するか
+    fn matches(&self, path: &str, system_root: &str) -> bool {
+        path_forms(path, system_root).any(|form| self.paths.iter().any(|p| p.is_match(&form)))
+            || self.names.iter().any(|p| p.is_match(file_name_of(path)))
+    }
+}
+
+impl FilterRule {
+    /// イベントがこのルールに一致するか
+    fn matches(&self, s: &Subject, cmdline_audit_enabled: bool) -> bool {
+        let any = |patterns: &[Regex], value: &str| patterns.is_empty() || patterns.iter().any(|p| p.is_match(value));
+        let listed = |names: &[String], value: Option<&str>| {
+            names.is_empty() || value.is_some_and(|v| names.iter().any(|n| n.eq_ignore_ascii_case(v)))
+        };
+        (self.image.is_empty() || path_forms(&s.image, s.system_root).any(|form| self.image.iter().any(|p| p.is_match(&form))))
+            && any(&self.file_name, file_name_of(&s.image))
+            && (self.parent.is_empty() || self.parent.matches(&s.parent, s.system_root))
+            && (self.ancestor.is_empty() || s.ancestors.iter().any(|a| self.ancestor.matches(a, s.system_root)))
+            && listed(&self.service, s.event.svchost.as_ref().and_then(|c| c.service.as_deref()))
+            && listed(&self.service_group, s.event.svchost.as_ref().and_then(|c| c.group.as_deref()))
+            && any(&self.user, &s.event.user)
+            && (self.event_id.is_empty() || self.event_id.contains(&s.event.event_id))
+            && self.command_line.as_ref().map_or(true, |re| re.is_match(&s.event.command_line))
+            && self.cmdline_audit.map_or(true, |state| state == cmdline_audit_enabled)
+    }
+}
+
+/// 除外プロファイル（継承元のルールを含めた評価順のルール）
+#[derive(Debug, Clone)]
+pub struct FilterProfile {
+    pub name: String,           // プロファイル名
+    description: LocalizedText, // 説明
+    rules: Vec<FilterRule>,     // ルール（評価順）
+    hide_subtrees: Vec<(String, ImagePattern)>, // --hide-subtree（指定値, パターン）
+    only_subtrees: Vec<(String, ImagePattern)>, // --only-subtree（指定値, パターン）
+}
+
+impl FilterProfile {
+    /// 現在の表示言語での説明
+    pub fn description(&self) -> &str {
+        self.description.current()
+    }
+
+    /// プロセスツリーでの絞り込み（--hide-subtree / --only-subtree）を追加する
+    pub fn with_subtrees(mut self, hide: &[String], only: &[String]) -> Result<FilterProfile, String> {
+        let compile = |values: &[String]| -> Result<Vec<(String, ImagePattern)>, String> {
+            values.iter().map(|v| Ok((v.clone(), ImagePattern::compile(vec![v.clone()])?))).collect()
+        };
+        self.hide_subtrees = compile(hide)?;
+        self.only_subtrees = compile(only)?;
+        Ok(self)
+    }
+
+    /// イベントを除外する理由（ルール名または指定されたオプション）を返す（除外しない場合はNone）
+    /// ancestors はプロセスツリー上の祖先のプロセスのフルパス（system_root で正規化済み、親から順）
+    /// system_root はなりすまし検出と同じシステムルート（正規化済み）
+    pub fn excluded_by(&self, event: &EventRecord, ancestors: &[String], cmdline_audit_enabled: bool, system_root: &str) -> Option<String> {
+        let subject = subject_of(event, ancestors, system_root);
+        // プロセスツリーでの絞り込みはプロセス作成・終了イベントのみ対象（部分木は起点のプロセス自身を含む）
+        if event.new_process_id.is_some() || event.exit_process_id.is_some() {
+            let in_subtree = |pattern: &ImagePattern| {
+                pattern.matches(&subject.image, system_root) || ancestors.iter().any(|a| pattern.matches(a, system_root))
+            };
+            if let Some((value, _)) = self.hide_subtrees.iter().find(|(_, p)| in_subtree(p)) {
+                return Some(format!("--hide-subtree {}", value));
+            }
+            if !self.only_subtrees.is_empty() && !self.only_subtrees.iter().any(|(_, p)| in_subtree(p)) {
+                let values: Vec<&str> = self.only_subtrees.iter().map(|(v, _)| v.as_str()).collect();
+                return Some(format!("--only-subtree {}", values.join(", ")));
+            }
+        }
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(&subject, cmdline_audit_enabled))
+            .filter(|rule| rule.action == RuleAction::Exclude)
+            .map(|rule| rule.name.clone())
+    }
+}
+
+/// 除外フィルタ設定ファイルの形式
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FilterFile {
+    default_profile: Option<String>, // --profile 未指定時のプロファイル名
+    #[serde(default)]
+    profile: Vec<ProfileSpec>,       // [[profile]] の並び
+}
+
+/// [[profile]] の形式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ProfileSpec {
+    name: String,                      // プロファイル名
+    description: Option<LocalizedText>, // 説明
+    extends: Option<String>,           // 継承元のプロファイル名
+    #[serde(default)]
+    rule: Vec<RuleSpec>,               // [[profile.rule]] の並び
+}
+
+/// [[profile.rule]] の形式
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,                       // ルール名
+    action: String,                     // exclude / include
+    image: Option<OneOrMany<String>>,   // プロセスのフルパス
+    file_name: Option<OneOrMany<String>>, // プロセスのファイル名
+    parent: Option<OneOrMany<String>>,  // 親プロセス
+    ancestor: Option<OneOrMany<String>>, // 祖先のプロセス
+    service: Option<OneOrMany<String>>, // サービス名
+    service_group: Option<OneOrMany<String>>, // サービスグループ
+    user: Option<OneOrMany<String>>,    // ユーザー名
+    event_id: Option<OneOrMany<u32>>,   // イベントID
+    command_line: Option<String>,       // コマンドラインの正規表現
+    cmdline_audit: Option<bool>,        // コマンドライン監査の状態
+}
+
+/// 単一の値または値の配列
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum OneOrMany<T> {
+    One(T),       // 単一の値
+    Many(Vec<T>), // 値の配列
+}
+
+impl<T: Clone> OneOrMany<T> {
+    /// 配列に変換する
+    fn to_vec(&self) -> Vec<T> {
+        match self {
+            OneOrMany::One(v) => vec![v.clone()],
+            OneOrMany::Many(v) => v.clone(),
+        }
+    }
+}
+
+/// 除外フィルタ設定（既定の設定＋--filter で指定したファイル）
+#[derive(Debug, Clone, Default)]
+pub struct FilterConfig {
+    default_profile: String,      // --profile 未指定時のプロファイル名
+    profiles: Vec<ProfileSpec>,   // プロファイル（定義順）
+}
+
+impl FilterConfig {
+    /// 既定の設定を読み込み、指定されたファイルを順に追加・上書きする（同じ名前のプロファイルは置き換え）
+    pub fn load(paths: &[impl AsRef<Path>]) -> Result<FilterConfig, String> {
+        let mut config = FilterConfig::default();
+        config.merge_str(DEFAULT_FILTER, t(Msg::FilterBuiltin))?; // 既定の設定
+        for path in paths {
+            let path = path.as_ref();
+            let text = std::fs::read_to_string(path).map_err(|e| tr(Msg::FilterReadFailed, &[&path.display(), &e]))?;
+            config.merge_str(&text, &path.display().to_string())?; // 利用者の設定
+        }
+        // すべてのプロファイルを解決してルールの誤りを検出
+        for spec in &config.profiles {
+            config.resolve(&spec.name)?;
+        }
+        Ok(config)
+    }
+
+    /// TOML文字列のプロファイルを追加する
+    fn merge_str(&mut self, text: &str, source: &str) -> Result<(), String> {
+        let file: FilterFile = toml::from_str(text)
+            .map_err(|e| tr(Msg::FilterInvalid, &[&source, &format!("{}{}", e.message().trim_end().replace('\n', " "), error_position(text, e.span()))]))?;
+        let mut seen: Vec<&str> = Vec::new(); // このファイル内のプロファイル名（重複検出用）
+        for spec in &file.profile {
+            let name = spec.name.trim();
+            if name.is_empty() {
+                return Err(tr(Msg::FilterInvalid, &[&source, &t(Msg::FilterProfileNameEmpty)]));
+            }
+            if seen.iter().any(|s| s.eq_ignore_ascii_case(name)) {
+                return Err(tr(Msg::FilterInvalid, &[&source, &tr(Msg::FilterProfileDuplicate, &[&name])]));
             }
+            seen.push(name);
+            for (i, rule) in spec.rule.iter().enumerate() {
+                compile_rule(rule).map_err(|e| tr(Msg::FilterRuleInvalid, &[&source, &name, &(i + 1), &rule.name, &e]))?;
+            }
+            match self.profiles.iter().position(|p| p.name.eq_ignore_ascii_case(name)) {
+                Some(pos) => self.profiles[pos] = spec.clone(), // 上書き
+                None => self.profiles.push(spec.clone()),      // 追加
+            }
+        }
+        if let Some(default) = &file.default_profile {
+            self.default_profile = default.trim().to_string();
         }
+        Ok(())
+    }
+
+    /// プロファイルを選択する（Noneなら default_profile）
+    pub fn profile(&self, name: Option<&str>) -> Result<FilterProfile, String> {
+        self.resolve(name.unwrap_or(&self.default_profile))
     }
-    // svchost_levelによる分岐
-    let svchost_output = match svchost_level {
-        0 => true, // 全出力
-        1 => {
-            // 除外サービス名なら出力しない
-            !(is_svchost && !svchost_service_name.is_empty() && EXCLUDE_SVCHOST_SERVICES.iter().any(|s| svchost_service_name.eq_ignore_ascii_case(s)))
-        },
-        2 => !is_svchost, // svchost.exeは全て出力しない
-        _ => true, // デフォルトは全出力
+
+    /// 継承元を含めてプロファイルのルールを評価順に並べる
+    fn resolve(&self, name: &str) -> Result<FilterProfile, String> {
+        let mut rules = Vec::new(); // 評価順のルール
+        let mut chain: Vec<&str> = Vec::new(); // 継承の経路（循環検出用）
+        let mut current = Some(name);
+        let mut first: Option<&ProfileSpec> = None; // 選択したプロファイル
+        while let Some(n) = current {
+            let Some(spec) = self.profiles.iter().find(|p| p.name.eq_ignore_ascii_case(n)) else {
+                let names: Vec<&str> = self.profiles.iter().map(|p| p.name.as_str()).collect();
+                return Err(tr(Msg::FilterProfileUnknown, &[&n, &names.join(", ")]));
+            };
+            if chain.iter().any(|c| c.eq_ignore_ascii_case(n)) {
+                chain.push(n);
+                return Err(tr(Msg::FilterProfileCycle, &[&chain.join(" -> ")]));
+            }
+            chain.push(n);
+            first.get_or_insert(spec);
+            for rule in &spec.rule {
+                rules.push(compile_rule(rule)?); // 検証済みのためエラーにはならない
+            }
+            current = spec.extends.as_deref();
+        }
+        let spec = first.expect("少なくとも1つのプロファイルを解決済み");
+        Ok(FilterProfile {
+            name: spec.name.clone(),
+            description: spec.description.clone().unwrap_or_else(|| LocalizedText::Plain(spec.name.clone())),
+            rules,
+            hide_subtrees: Vec::new(),
+            only_subtrees: Vec::new(),
+        })
+    }
+}
+
+/// [[profile.rule]] の内容を検証してルールに変換する関数
+fn compile_rule(spec: &RuleSpec) -> Result<FilterRule, String> {
+    if spec.name.trim().is_empty() {
+        return Err(t(Msg::FilterRuleNameEmpty).to_string());
+    }
+    let action = match spec.action.trim().to_ascii_lowercase().as_str() {
+        "exclude" => RuleAction::Exclude,
+        "include" => RuleAction::Include,
+        other => return Err(tr(Msg::FilterActionInvalid, &[&other])),
+    };
+    let list = |v: &Option<OneOrMany<String>>| v.as_ref().map(|v| v.to_vec()).unwrap_or_default();
+    let globs = |v: Vec<String>| -> Result<Vec<Regex>, String> { v.iter().map(|p| glob_regex(p)).collect() };
+    let paths = |v: Vec<String>| -> Result<Vec<Regex>, String> { v.iter().map(|p| glob_regex(&normalize_pattern(p))).collect() };
+    let command_line = match &spec.command_line {
+        Some(re) => Some(Regex::new(re).map_err(|e| tr(Msg::FilterRegexInvalid, &[re, &e]))?),
+        None => None,
+    };
+    let rule = FilterRule {
+        name: spec.name.trim().to_string(),
+        action,
+        image: paths(list(&spec.image))?,
+        file_name: globs(list(&spec.file_name))?,
+        parent: ImagePattern::compile(list(&spec.parent))?,
+        ancestor: ImagePattern::compile(list(&spec.ancestor))?,
+        service: list(&spec.service),
+        service_group: list(&spec.service_group),
+        user: globs(list(&spec.user))?,
+        event_id: spec.event_id.as_ref().map(|v| v.to_vec()).unwrap_or_default(),
+        command_line,
+        cmdline_audit: spec.cmdline_audit,
     };
-    // 除外判定
-    should_exclude_proc || (is_svchost && (!cmdline_audit_enabled || !svchost_output)) // 除外条件
+    let has_condition = !(rule.image.is_empty()
+        && rule.file_name.is_empty()
+        && rule.parent.is_empty()
+        && rule.ancestor.is_empty()
+        && rule.service.is_empty()
+        && rule.service_group.is_empty()
+        && rule.user.is_empty()
+        && rule.event_id.is_empty()
+        && rule.command_line.is_none()
+        && rule.cmdline_audit.is_none());
+    if !has_condition {
+        return Err(t(Msg::FilterRuleNoCondition).to_string()); // 全イベントに一致するルールは誤りとみなす
+    }
+    Ok(rule)
+}
+
+/// ワイルドカードを正規表現に変換する関数（大文字小文字は区別しない）
+/// * は \ をまたがない任意の文字列、** は \ をまたぐ任意の文字列、? は \ 以外の1文字
+fn glob_regex(pattern: &str) -> Result<Regex, String> {
+    let mut re = String::from("^");
+    let mut chars = pattern.trim().chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                re.push_str(".*"); // 任意の深さのディレクトリ
+            }
+            '*' => re.push_str(r"[^\\]*"),
+            '?' => re.push_str(r"[^\\]"),
+            _ => re.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    re.push('$');
+    RegexBuilder::new(&re).case_insensitive(true).build().map_err(|e| tr(Msg::FilterRegexInvalid, &[&pattern, &e]))
+}
+
+/// イベントから照合対象の情報を取り出す関数
+fn subject_of<'a>(event: &'a EventRecord, ancestors: &'a [String], system_root: &'a str) -> Subject<'a> {
+    let image = normalize_image_path_in(event.image(), system_root); // プロセスのフルパス
+    Subject {
+        image,
+        parent: normalize_image_path_in(event.parent_image(), system_root),
+        ancestors,
+        system_root,
+        event,
+    }
 }
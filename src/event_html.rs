@@ -0,0 +1,250 @@
+// --- 必要なクレートの説明 ---
+// このファイルは解析結果を1ファイルで完結するHTMLタイムラインレポートとして出力するモジュールです
+// 外部のCSS/JavaScriptは使用せず、すべてインラインで埋め込みます（オフライン閲覧可）
+// std::io::Write: 出力先への書き込み用
+
+use std::io::Write; // 書き込み用
+use crate::event_types::EventRecord; // イベントレコード型
+use crate::event_decode::decoded_marker; // コードで記録された項目の表記
+use crate::event_output::ReportHeader; // レポートヘッダ
+use crate::integrity::Severity; // ログの整合性の重大度
+use crate::event_catalog::EventCategory; // 色分けカテゴリ
+use crate::event_util::{file_name_of, format_display, normalize_image_path, DisplayTz}; // 日時変換関数・表示タイムゾーン・パスの正規化
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+/// svchost.exeによるイベントか判定する関数（折りたたみグループ化用）
+/// なりすましの疑いがあるイベントは折りたたまない
+fn is_svchost(event: &EventRecord) -> bool {
+    event.findings.is_empty() && (event.svchost.is_some() || file_name_of(&normalize_image_path(event.image())) == "svchost.exe")
+}
+
+/// HTML特殊文字をエスケープする関数
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+/// インラインCSS
+const STYLE: &str = r#"
+body { font-family: "Segoe UI", "Meiryo", sans-serif; margin: 1.5em; color: #222; }
+h1 { font-size: 1.4em; }
+.summary { background: #f5f5f5; border: 1px solid #ddd; padding: 0.8em 1.2em; margin-bottom: 1em; }
+.summary .warn { color: #b00020; }
+.integrity { background: #ffebee; border: 2px solid #b00020; padding: 0.6em 1.2em; margin-bottom: 1em; }
+.integrity .high { color: #b00020; font-weight: bold; }
+.integrity .medium { color: #e65100; }
+.legend span { display: inline-block; padding: 0.1em 0.6em; margin-right: 0.4em; border: 1px solid #999; border-radius: 3px; }
+.filters { margin: 1em 0; }
+.filters input { margin-right: 1em; padding: 0.2em 0.4em; }
+table { border-collapse: collapse; width: 100%; font-size: 0.9em; }
+th, td { border: 1px solid #ccc; padding: 0.25em 0.5em; text-align: left; vertical-align: top; }
+th { background: #333; color: #fff; position: sticky; top: 0; }
+td.cmd { font-family: Consolas, monospace; word-break: break-all; }
+.boot { background: #e3f2fd; }
+.shutdown { background: #ede7f6; }
+.unexpected { background: #ffcdd2; }
+.logon { background: #e8f5e9; }
+.logon_failure { background: #ffe0b2; }
+.process { background: #fffde7; }
+.cleared { background: #ff8a80; font-weight: bold; }
+.audit_change { background: #ffab91; }
+.other { background: #fff; }
+tr.excluded td { color: #888; text-decoration: line-through; }
+tr.suspicious td:first-child { border-left: 4px solid #b00020; }
+.finding { color: #b00020; font-weight: bold; }
+tr.svc-head { background: #cfd8dc; cursor: pointer; font-style: italic; }
+tr.svc-member.collapsed { display: none; }
+tr.filtered { display: none; }
+"#;
+
+/// インラインJavaScript（svchostグループの折りたたみ・イベントID/ユーザーでの絞り込み）
+const SCRIPT: &str = r#"
+function toggleGroup(id) {
+  document.querySelectorAll('tr[data-group="' + id + '"]').forEach(function (r) { r.classList.toggle('collapsed'); });
+}
+function applyFilter() {
+  var ids = document.getElementById('f-id').value.split(',').map(function (s) { return s.trim(); }).filter(function (s) { return s; });
+  var user = document.getElementById('f-user').value.trim().toLowerCase();
+  document.querySelectorAll('tr.ev').forEach(function (r) {
+    var okId = ids.length === 0 || ids.indexOf(r.dataset.eventId) >= 0;
+    var okUser = user === '' || r.dataset.user.toLowerCase().indexOf(user) >= 0;
+    r.classList.toggle('filtered', !(okId && okUser));
+  });
+}
+"#;
+
+/// HTMLタイムラインレポートを書き込む関数
+pub fn write_html(header: &ReportHeader, events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "<!DOCTYPE html>")?;
+    writeln!(out, "<html lang=\"{}\"><head><meta charset=\"utf-8\"><title>{}</title>", header.lang.code(), t(Msg::HtmlTitle))?;
+    writeln!(out, "<style>{}</style><script>{}</script></head><body>", STYLE, SCRIPT)?;
+    writeln!(out, "<h1>{}</h1>", t(Msg::HtmlHeading))?;
+    // --- ログの整合性（消去・欠落・監査ポリシー・時刻の変更、最も目立つ位置に表示） ---
+    if !header.integrity.is_empty() {
+        writeln!(out, "<div class=\"integrity\"><ul>")?;
+        for finding in &header.integrity {
+            let class = match finding.severity {
+                Severity::High => "high",
+                Severity::Medium => "medium",
+            };
+            writeln!(out, "<li class=\"{}\">{}</li>", class, escape_html(&finding.headline()))?;
+        }
+        writeln!(out, "</ul></div>")?;
+    }
+    // --- サマリ（監査範囲・出力設定・環境チェック結果） ---
+    writeln!(out, "<div class=\"summary\">")?;
+    writeln!(out, "<p>{}</p>", escape_html(&tr(Msg::HtmlAuditRange, &[&header.audit_range])))?;
+    writeln!(out, "<p>{}</p>", escape_html(tr(Msg::HeaderFilterProfile, &[&header.filter_profile, &header.filter_profile_description]).trim_start_matches(">>> ")))?;
+    if header.show_excluded {
+        writeln!(out, "<p>{}</p>", escape_html(t(Msg::HeaderShowExcluded).trim_start_matches(">>> ")))?;
+    }
+    if let Some(selection) = &header.boot_selection {
+        writeln!(out, "<p>{}</p>", escape_html(tr(Msg::HeaderBootSelection, &[selection, &header.boots.len()]).trim_start_matches(">>> ")))?;
+    }
+    writeln!(out, "<p>{}</p>", escape_html(&tr(Msg::HtmlTzAndCount, &[&header.time_zone, &events.len()])))?;
+    writeln!(out, "<p>{}</p>", escape_html(tr(Msg::HeaderSystemRoot, &[&header.system_root]).trim_start_matches(">>> ")))?;
+    if !header.cmdline_audit_enabled {
+        writeln!(out, "<p class=\"warn\">{}</p>", t(Msg::HtmlCmdlineWarn))?;
+    }
+    if !header.environment.is_empty() {
+        writeln!(out, "<ul>")?;
+        for msg in &header.environment {
+            let class = if msg.starts_with('⚠') || msg.starts_with('🛑') { " class=\"warn\"" } else { "" }; // 警告は強調
+            writeln!(out, "<li{}>{}</li>", class, escape_html(msg))?;
+        }
+        writeln!(out, "</ul>")?;
+    }
+    writeln!(out, "</div>")?;
+    // --- 凡例・絞り込み ---
+    write!(out, "<div class=\"legend\">")?;
+    for category in EventCategory::LEGEND {
+        write!(out, "<span class=\"{}\">{}</span>", category.css_class(), escape_html(t(category.label())))?;
+    }
+    writeln!(out, "</div>")?;
+    writeln!(
+        out,
+        "<div class=\"filters\">{}: <input id=\"f-id\" oninput=\"applyFilter()\"> {}: <input id=\"f-user\" oninput=\"applyFilter()\"></div>",
+        escape_html(t(Msg::HtmlFilterIds)),
+        escape_html(t(Msg::HtmlFilterUser))
+    )?;
+    // --- タイムライン ---
+    write!(out, "<table><thead><tr>")?;
+    for col in [Msg::ColDateTime, Msg::ColBoot, Msg::ColLog, Msg::ColUser, Msg::ColEventId, Msg::ColAction, Msg::ColDescription, Msg::ColService, Msg::ColDetail] {
+        write!(out, "<th>{}</th>", escape_html(t(col)))?; // 列見出し
+    }
+    writeln!(out, "</tr></thead><tbody>")?;
+    let mut i = 0;
+    let mut group_no = 0; // svchostグループ番号
+    while i < events.len() {
+        if is_svchost(&events[i]) {
+            // 連続するsvchost.exeのイベントを1グループにまとめて折りたたむ
+            let end = events[i..].iter().position(|e| !is_svchost(e)).map(|p| i + p).unwrap_or(events.len());
+            group_no += 1;
+            writeln!(
+                out,
+                "<tr class=\"svc-head\" onclick=\"toggleGroup({})\"><td colspan=\"9\">{}</td></tr>",
+                group_no,
+                escape_html(&tr(Msg::HtmlSvchostGroup, &[&(end - i)]))
+            )?;
+            for event in &events[i..end] {
+                write_row(out, event, tz, Some(group_no))?;
+            }
+            i = end;
+        } else {
+            write_row(out, &events[i], tz, None)?;
+            i += 1;
+        }
+    }
+    writeln!(out, "</tbody></table></body></html>")
+}
+
+/// タイムラインの1行を書き込む関数
+fn write_row(out: &mut dyn Write, event: &EventRecord, tz: DisplayTz, group: Option<usize>) -> std::io::Result<()> {
+    let mut category = event.category.css_class().to_string(); // 色分けカテゴリ
+    if event.excluded_by.is_some() {
+        category.push_str(" excluded"); // 除外対象（--show-excluded）は取り消し線
+    }
+    if !event.findings.is_empty() {
+        category.push_str(" suspicious"); // 検出事項ありは強調
+    }
+    let (class, group_attr) = match group {
+        Some(g) => (format!("ev {} svc-member collapsed", category), format!(" data-group=\"{}\"", g)), // 折りたたみ対象
+        None => (format!("ev {}", category), String::new()),
+    };
+    let detail = if !event.command_line.is_empty() {
+        format!("{} [CommandLine: {}]", event.proc_info, event.command_line) // コマンドライン付き
+    } else if let Some(logon_type) = event.logon_type {
+        format!("{} [LogonType={}]", event.proc_info, logon_type.describe()) // ログオンタイプ（名前）付き
+    } else {
+        event.proc_info.clone()
+    };
+    let detail = match event.logon_source().filter(|s| !s.is_empty()) {
+        Some(source) => format!("{} {}", detail, tr(Msg::LogonSourceMarker, &[&source])), // ログオン・ログオン失敗の接続元
+        None => detail,
+    };
+    let detail = match decoded_marker(&event.data, &event.decoded) {
+        Some(decoded) => format!("{} {}", detail, tr(Msg::DecodedMarker, &[&decoded])), // コードで記録された項目の表記
+        None => detail,
+    };
+    let detail = match &event.lifetime {
+        Some(lifetime) => format!("{} {}", detail, lifetime.marker(event, tz)), // プロセスの実行期間
+        None => detail,
+    };
+    let detail = match &event.session {
+        Some(session) => format!("{} {}", detail, session.marker(event, tz)), // ログオンセッション
+        None => detail,
+    };
+    let detail = match &event.excluded_by {
+        Some(rule) => format!("{} {}", detail, tr(Msg::ExcludedMarker, &[rule])), // 除外理由付き
+        None => detail,
+    };
+    // 詳細列のツールチップにEventData / UserDataの全項目を表示（表記に変換できる項目は表記を併記）
+    let data_title = if event.data.is_empty() {
+        String::new()
+    } else {
+        let items: Vec<String> = event
+            .data
+            .iter()
+            .map(|(k, v)| match event.decoded.get(k) {
+                Some(text) => format!("{}={} ({})", k, v, text),
+                None => format!("{}={}", k, v),
+            })
+            .collect();
+        format!(" title=\"{}\"", escape_html(&items.join("\n")))
+    };
+    // 検出事項は詳細列の末尾に強調表示
+    let findings: String = event
+        .findings
+        .iter()
+        .map(|f| format!("<br><span class=\"finding\">{}</span>", escape_html(&tr(Msg::FindingMarker, &[&f.message]))))
+        .collect();
+    writeln!(
+        out,
+        "<tr class=\"{}\"{} data-event-id=\"{}\" data-user=\"{}\"><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td class=\"cmd\"{}>{}{}</td></tr>",
+        class,
+        group_attr,
+        event.event_id,
+        escape_html(&event.user),
+        escape_html(&format_display(event.timestamp, tz)),
+        escape_html(&event.boot.map(|b| b.label()).unwrap_or_default()), // 起動番号・起動からの経過時間
+        escape_html(event.channel.name()),
+        escape_html(&event.user),
+        event.event_id,
+        escape_html(&event.action),
+        escape_html(&event.description),
+        escape_html(&event.svchost.as_ref().map(|c| c.describe()).unwrap_or_default()),
+        data_title,
+        escape_html(&detail),
+        findings
+    )
+}
@@ -0,0 +1,431 @@
+// --- 必要なクレートの説明 ---
+// このファイルは解析結果の出力形式（テキスト・JSON・JSON Lines・CSV・HTML・プロセスツリー）をまとめるモジュールです
+// serde / serde_json: 構造化出力用シリアライズ
+// std::io::Write: 出力先への書き込み用
+
+use std::io::Write; // 書き込み用
+use serde::Serialize; // シリアライズ
+use crate::event_types::{format_logon_id, format_utc, EventRecord}; // イベントレコード型
+use crate::event_decode::decoded_marker; // コードで記録された項目の表記
+use crate::event_util::{format_display, to_local_rfc3339, DisplayTz}; // 日時変換関数・表示タイムゾーン
+use crate::event_html::write_html; // HTMLレポート
+use crate::process_tree; // プロセスツリー（ツリー表示・DOT）
+use crate::process_lifetime; // プロセスの実行期間
+use crate::logon_session; // ログオンセッション
+use crate::integrity::IntegrityFinding; // ログの整合性の検出事項
+use crate::boot_session::{self, BootSession}; // 起動ごとの区間
+use crate::messages::{t, tr, Lang, Msg}; // メッセージカタログ
+
+/// 出力形式
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    Text,  // タブ区切りテキスト（従来形式）
+    Json,  // JSON（ヘッダ＋イベント配列）
+    Jsonl, // JSON Lines（1行目ヘッダ、以降1行1イベント）
+    Csv,   // CSV（RFC 4180、ヘッダ行付き）
+    Html,  // HTMLタイムラインレポート（単一ファイル）
+    Tree,  // プロセスツリーのインデント表示
+    Dot,   // プロセスツリーのDOT（Graphviz）形式
+    Lifetime, // プロセスの実行期間の一覧
+    Sessions, // ログオンセッションの一覧
+    Boots,    // 起動の一覧
+}
+
+impl OutputFormat {
+    /// --format の値から出力形式を決定する
+    pub fn parse(s: &str) -> Option<OutputFormat> {
+        match s.to_ascii_lowercase().as_str() {
+            "text" | "txt" => Some(OutputFormat::Text),
+            "json" => Some(OutputFormat::Json),
+            "jsonl" | "ndjson" => Some(OutputFormat::Jsonl),
+            "csv" => Some(OutputFormat::Csv),
+            "html" | "htm" => Some(OutputFormat::Html),
+            "tree" => Some(OutputFormat::Tree),
+            "dot" | "gv" => Some(OutputFormat::Dot),
+            "lifetime" | "lifetimes" => Some(OutputFormat::Lifetime),
+            "sessions" | "session" => Some(OutputFormat::Sessions),
+            "boots" | "boot" => Some(OutputFormat::Boots),
+            _ => None,
+        }
+    }
+
+    /// 既定の出力ファイル拡張子
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Text => "txt",
+            OutputFormat::Json => "json",
+            OutputFormat::Jsonl => "jsonl",
+            OutputFormat::Csv => "csv",
+            OutputFormat::Html => "html",
+            OutputFormat::Tree => "txt",
+            OutputFormat::Dot => "dot",
+            OutputFormat::Lifetime => "txt",
+            OutputFormat::Sessions => "txt",
+            OutputFormat::Boots => "txt",
+        }
+    }
+
+    /// イベント本体と別にメタ情報ファイルを出力する形式か（CSVはヘッダ情報を本体に含めない）
+    pub fn has_separate_metadata(&self) -> bool {
+        matches!(self, OutputFormat::Csv)
+    }
+}
+
+/// 出力オプション
+#[derive(Debug, Clone, Copy)]
+pub struct OutputOptions {
+    pub format: OutputFormat, // 出力形式
+    pub csv_bom: bool,        // CSV先頭にUTF-8 BOMを付与するか（Excel向け）
+}
+
+/// レポート先頭のメタ情報（監査範囲・出力設定）
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportHeader {
+    pub audit_start_utc: Option<String>,   // 監査開始時刻（UTC, RFC3339）
+    pub audit_end_utc: Option<String>,     // 監査終了時刻（UTC, RFC3339）
+    pub audit_range: String,               // 監査対象日時（表示用）
+    pub offline: bool,                     // オフライン解析か
+    pub filter_profile: String,             // 除外プロファイル名
+    pub filter_profile_description: String, // 除外プロファイルの説明
+    pub show_excluded: bool,               // 除外されたイベントも出力しているか
+    pub cmdline_audit_enabled: bool,       // コマンドライン監査有効フラグ
+    pub time_zone: String,                 // 表示タイムゾーン（名前とUTCオフセット）
+    pub integrity: Vec<IntegrityFinding>,  // ログの整合性の検出事項（重大度・日時順）
+    pub boots: Vec<BootSession>,           // 起動の一覧（--boot / --boots 指定時は選択した起動のみ）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot_selection: Option<String>,    // 出力する起動（--boot / --boots の表示）
+    pub environment: Vec<String>,          // 環境チェックの通知文（事前チェック結果）
+    pub system_root: String,               // システムルート（なりすまし検出・除外ルールの照合に使用、決定方法付き）
+    pub lang: Lang,                        // 表示言語
+}
+
+/// CSVの列名（EventRecordのフィールド順＋表示用日時）
+const CSV_COLUMNS: &[&str] = &[
+    "datetime_utc",
+    "datetime_local",
+    "boot",
+    "since_boot_ms",
+    "record_id",
+    "event_id",
+    "log_name",
+    "provider",
+    "computer",
+    "level",
+    "process_id",
+    "thread_id",
+    "user",
+    "action",
+    "description",
+    "category",
+    "proc_info",
+    "logon_type",
+    "logon_type_name",
+    "command_line",
+    "new_process_id",
+    "parent_process_id",
+    "exit_process_id",
+    "exit_status",
+    "process_start_utc",
+    "process_end_utc",
+    "duration_ms",
+    "lifetime_state",
+    "subject_logon_id",
+    "target_user",
+    "target_domain",
+    "target_logon_id",
+    "ip_address",
+    "workstation",
+    "logon_process",
+    "auth_package",
+    "failure_status",
+    "failure_sub_status",
+    "session_logon_id",
+    "session_account",
+    "session_start_utc",
+    "session_end_utc",
+    "session_duration_ms",
+    "session_state",
+    "service_group",
+    "service_split",
+    "service",
+    "data",
+    "decoded",
+    "excluded_by",
+    "findings",
+];
+
+/// JSON出力用のイベント（EventRecordの全フィールド＋表示用日時）
+#[derive(Serialize)]
+struct JsonEvent<'a> {
+    #[serde(flatten)]
+    record: &'a EventRecord, // イベント本体
+    datetime_local: String,  // 表示タイムゾーンでの日時
+}
+
+/// JSON Lines出力用の1行（record_typeで header / event を区別）
+#[derive(Serialize)]
+struct JsonLine<'a, T: Serialize> {
+    record_type: &'a str, // 行の種別
+    #[serde(flatten)]
+    body: &'a T,          // 行の内容
+}
+
+/// 指定形式でレポートを書き込む関数
+pub fn write_report(
+    options: OutputOptions,
+    header: &ReportHeader,
+    events: &[EventRecord],
+    tz: DisplayTz,
+    out: &mut dyn Write,
+) -> std::io::Result<()> {
+    match options.format {
+        OutputFormat::Text => write_text(header, events, tz, out),
+        OutputFormat::Json => write_json(header, events, tz, out),
+        OutputFormat::Jsonl => write_jsonl(header, events, tz, out),
+        OutputFormat::Csv => write_csv(events, tz, options.csv_bom, out),
+        OutputFormat::Html => write_html(header, events, tz, out),
+        OutputFormat::Tree => {
+            write_text_header(header, out)?; // テキストと同じヘッダ
+            process_tree::write_tree(events, tz, out)
+        }
+        OutputFormat::Dot => {
+            let mut buf = Vec::new();
+            write_text_header(header, &mut buf)?; // ヘッダはDOTのコメントとして出力
+            let lines: Vec<String> = String::from_utf8_lossy(&buf).lines().map(str::to_string).collect();
+            process_tree::write_dot(&lines, events, tz, out)
+        }
+        OutputFormat::Lifetime => {
+            write_text_header(header, out)?; // テキストと同じヘッダ
+            process_lifetime::write_lifetimes(events, tz, out)
+        }
+        OutputFormat::Sessions => {
+            write_text_header(header, out)?; // テキストと同じヘッダ
+            logon_session::write_sessions(events, tz, out)
+        }
+        OutputFormat::Boots => {
+            write_text_header(header, out)?; // テキストと同じヘッダ
+            boot_session::write_boots(&header.boots, tz, out)
+        }
+    }
+}
+
+/// メタ情報（ログの整合性・監査範囲・出力設定・環境チェック結果）をテキストで書き込む関数
+/// CSVなどイベント本体にヘッダ情報を含められない形式で、別ファイルとして出力する
+pub fn write_metadata(header: &ReportHeader, out: &mut dyn Write) -> std::io::Result<()> {
+    for finding in &header.integrity {
+        writeln!(out, "{}", finding.headline())?; // ログの整合性（先頭に表示）
+    }
+    writeln!(out, "{}", tr(Msg::HeaderAuditRange, &[&header.audit_range]))?; // 日時案内
+    write_filter_header(header, out)?; // 除外プロファイル案内
+    write_boot_header(header, out)?; // 起動の選択案内
+    let cmdline_state = if header.cmdline_audit_enabled { t(Msg::CmdlineAuditStateOn) } else { t(Msg::CmdlineAuditStateOff) }; // 監査ポリシー
+    writeln!(out, "{}", tr(Msg::HeaderCmdlineAudit, &[&cmdline_state]))?; // 監査ポリシー
+    writeln!(out, "{}", tr(Msg::HeaderTimeZone, &[&header.time_zone]))?; // タイムゾーン
+    writeln!(out, "{}", tr(Msg::HeaderSystemRoot, &[&header.system_root]))?; // システムルート
+    for msg in &header.environment {
+        writeln!(out, "{}", msg)?; // 環境チェック結果
+    }
+    Ok(())
+}
+
+/// 除外プロファイルの案内を書き込む関数
+fn write_filter_header(header: &ReportHeader, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", tr(Msg::HeaderFilterProfile, &[&header.filter_profile, &header.filter_profile_description]))?;
+    if header.show_excluded {
+        writeln!(out, "{}", t(Msg::HeaderShowExcluded))?; // --show-excluded 指定時
+    }
+    Ok(())
+}
+
+/// 起動の選択（--boot / --boots）の案内を書き込む関数
+fn write_boot_header(header: &ReportHeader, out: &mut dyn Write) -> std::io::Result<()> {
+    if let Some(selection) = &header.boot_selection {
+        writeln!(out, "{}", tr(Msg::HeaderBootSelection, &[selection, &header.boots.len()]))?;
+    }
+    Ok(())
+}
+
+/// テキスト形式（テキスト・ツリー表示）の先頭のヘッダを書き込む関数
+fn write_text_header(header: &ReportHeader, out: &mut dyn Write) -> std::io::Result<()> {
+    for finding in &header.integrity {
+        writeln!(out, "{}", finding.headline())?; // ログの整合性（最も目立つ先頭に表示）
+    }
+    for msg in &header.environment {
+        writeln!(out, "{}", msg)?; // 環境チェック結果
+    }
+    writeln!(out, "{}", tr(Msg::HeaderAuditRange, &[&header.audit_range]))?; // 日時案内
+    write_filter_header(header, out)?; // 除外プロファイル案内
+    write_boot_header(header, out)?; // 起動の選択案内
+    writeln!(out, "{}", tr(Msg::HeaderTimeZone, &[&header.time_zone]))?; // タイムゾーン
+    writeln!(out, "{}", tr(Msg::HeaderSystemRoot, &[&header.system_root])) // システムルート
+}
+
+/// タブ区切りテキスト形式で書き込む関数（従来のoutput.txt形式）
+fn write_text(header: &ReportHeader, events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    write_text_header(header, out)?; // ヘッダ
+    for event in events {
+        let log_disp = format!("{:<11}", event.channel.short_name()); // ログ名整形（タスクスケジューラは短縮）
+        let eventid_disp = format!("{:>5}", event.event_id); // イベントID整形
+        let date_local = format_display(event.timestamp, tz); // 表示タイムゾーンに変換
+        let boot_disp = event.boot.map(|b| b.label()).unwrap_or_default(); // 起動番号・起動からの経過時間
+        let proc_disp = match (event.event_id, event.logon_type) {
+            (4688, _) if !event.command_line.is_empty() => {
+                format!("{} [CommandLine: {}]", event.proc_info, event.command_line) // コマンドライン付き
+            }
+            (4624 | 4625, Some(logon_type)) => format!("{} [LogonType={}]", event.proc_info, logon_type.describe()), // ログオンタイプ（名前）付き
+            _ => event.proc_info.clone(), // それ以外はそのまま
+        };
+        let proc_disp = match &event.svchost {
+            Some(svchost) => format!("{} {}", proc_disp, tr(Msg::ServiceMarker, &[&svchost.describe()])), // svchost.exe のサービス
+            None => proc_disp,
+        };
+        let proc_disp = match event.logon_source().filter(|s| !s.is_empty()) {
+            Some(source) => format!("{} {}", proc_disp, tr(Msg::LogonSourceMarker, &[&source])), // ログオン・ログオン失敗の接続元
+            None => proc_disp,
+        };
+        let proc_disp = match decoded_marker(&event.data, &event.decoded) {
+            Some(decoded) => format!("{} {}", proc_disp, tr(Msg::DecodedMarker, &[&decoded])), // コードで記録された項目の表記
+            None => proc_disp,
+        };
+        let proc_disp = match &event.lifetime {
+            Some(lifetime) => format!("{} {}", proc_disp, lifetime.marker(event, tz)), // プロセスの実行期間
+            None => proc_disp,
+        };
+        let proc_disp = match &event.session {
+            Some(session) => format!("{} {}", proc_disp, session.marker(event, tz)), // ログオンセッション
+            None => proc_disp,
+        };
+        let proc_disp = match &event.excluded_by {
+            Some(rule) => format!("{} {}", proc_disp, tr(Msg::ExcludedMarker, &[rule])), // 除外理由付き（--show-excluded）
+            None => proc_disp,
+        };
+        let proc_disp = event.findings.iter().fold(proc_disp, |disp, f| format!("{} {}", disp, tr(Msg::FindingMarker, &[&f.message]))); // 検出事項付き
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            date_local, boot_disp, log_disp, event.user, eventid_disp, event.action, event.description, proc_disp
+        )?; // 出力行を書き込み
+    }
+    Ok(())
+}
+
+/// JSON形式（{"header": {...}, "events": [...]}）で書き込む関数
+fn write_json(header: &ReportHeader, events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    #[derive(Serialize)]
+    struct JsonReport<'a> {
+        header: &'a ReportHeader,  // メタ情報
+        events: Vec<JsonEvent<'a>>, // イベント一覧
+    }
+    let report = JsonReport { header, events: events.iter().map(|e| to_json_event(e, tz)).collect() };
+    serde_json::to_writer_pretty(&mut *out, &report)?; // 整形して書き込み
+    writeln!(out)
+}
+
+/// JSON Lines形式で書き込む関数（SIEM取り込み用）
+fn write_jsonl(header: &ReportHeader, events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    serde_json::to_writer(&mut *out, &JsonLine { record_type: "header", body: header })?; // 1行目: ヘッダ
+    writeln!(out)?;
+    for event in events {
+        serde_json::to_writer(&mut *out, &JsonLine { record_type: "event", body: &to_json_event(event, tz) })?; // 1行1イベント
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+/// EventRecordをJSON出力用の構造体に変換する関数
+fn to_json_event(record: &EventRecord, tz: DisplayTz) -> JsonEvent<'_> {
+    JsonEvent { record, datetime_local: to_local_rfc3339(record.timestamp, tz) }
+}
+
+/// CSV形式（RFC 4180、CRLF改行、ヘッダ行付き）で書き込む関数
+fn write_csv(events: &[EventRecord], tz: DisplayTz, bom: bool, out: &mut dyn Write) -> std::io::Result<()> {
+    if bom {
+        out.write_all(b"\xEF\xBB\xBF")?; // Excelで文字化けしないようUTF-8 BOMを付与
+    }
+    write_csv_row(out, CSV_COLUMNS.iter().copied())?; // ヘッダ行
+    for event in events {
+        let svchost = event.svchost.as_ref(); // svchost.exe のコマンドライン解析結果
+        let lifetime = event.lifetime.as_ref(); // プロセスの実行期間
+        let logon = event.logon.as_ref(); // ログオン・ログオフの詳細
+        let failure = event.failure.as_ref(); // ログオン失敗の詳細
+        let session = event.session.as_ref(); // ログオンセッション
+        let findings = event.findings.iter().map(|f| f.message.as_str()).collect::<Vec<_>>().join(" / ");
+        write_csv_row(
+            out,
+            [
+                event.datetime_utc().as_str(),
+                to_local_rfc3339(event.timestamp, tz).as_str(), // 表示タイムゾーンでの日時
+                opt(event.boot.map(|b| b.number)).as_str(), // 起動番号
+                opt(event.boot.and_then(|b| b.since_boot_ms)).as_str(), // 起動からの経過時間（ミリ秒）
+                event.record_id.to_string().as_str(),
+                event.event_id.to_string().as_str(),
+                event.channel.name(),
+                event.provider.as_str(),
+                event.computer.as_str(),
+                event.level.to_string().as_str(),
+                opt(event.process_id).as_str(),
+                opt(event.thread_id).as_str(),
+                event.user.as_str(),
+                event.action.as_str(),
+                event.description.as_str(),
+                event.category.css_class(),
+                event.proc_info.as_str(),
+                opt(event.logon_type.map(|l| l.code())).as_str(),
+                event.logon_type.and_then(|l| l.name()).unwrap_or(""), // ログオンタイプの名前
+                event.command_line.as_str(),
+                opt(event.new_process_id).as_str(),    // 作成されたプロセスのID（4688）
+                opt(event.parent_process_id).as_str(), // 親プロセスのID（4688）
+                opt(event.exit_process_id).as_str(),   // 終了したプロセスのID（4689）
+                opt(event.exit_status.map(|s| format!("0x{:x}", s))).as_str(), // 終了コード（4689）
+                opt(lifetime.and_then(|l| l.start).map(|dt| format_utc(&dt))).as_str(), // プロセスの開始日時
+                opt(lifetime.and_then(|l| l.end).map(|dt| format_utc(&dt))).as_str(),   // プロセスの終了日時
+                opt(lifetime.and_then(|l| l.duration_ms)).as_str(), // 実行時間（ミリ秒）
+                lifetime.map(|l| l.state.as_str()).unwrap_or(""), // 実行期間の終わり方
+                opt(event.subject_logon_id.map(format_logon_id)).as_str(), // 作成元のログオンID（4688）
+                logon.map(|l| l.target_user.as_str()).or(failure.map(|f| f.target_user.as_str())).unwrap_or(""), // ログオン対象のユーザー名
+                logon.map(|l| l.target_domain.as_str()).or(failure.map(|f| f.target_domain.as_str())).unwrap_or(""), // ログオン対象のドメイン名
+                opt(logon.map(|l| format_logon_id(l.target_logon_id))).as_str(), // ログオンID
+                logon.map(|l| l.ip_address.as_str()).or(failure.map(|f| f.ip_address.as_str())).unwrap_or(""), // 接続元のIPアドレス
+                logon.map(|l| l.workstation.as_str()).or(failure.map(|f| f.workstation.as_str())).unwrap_or(""), // 接続元のワークステーション名
+                logon.map(|l| l.logon_process.as_str()).unwrap_or(""), // ログオンプロセス名
+                logon.map(|l| l.auth_package.as_str()).or(failure.map(|f| f.auth_package.as_str())).unwrap_or(""), // 認証パッケージ名
+                opt(failure.map(|f| format!("0x{:x}", f.status))).as_str(), // ログオン失敗の理由（Status）
+                opt(failure.and_then(|f| f.sub_status).map(|s| format!("0x{:x}", s))).as_str(), // ログオン失敗の理由の詳細（SubStatus）
+                opt(session.map(|s| format_logon_id(s.logon_id))).as_str(), // ログオンセッションのID
+                session.map(|s| s.account.as_str()).unwrap_or(""), // ログオンセッションのアカウント
+                opt(session.and_then(|s| s.start).map(|dt| format_utc(&dt))).as_str(), // ログオン日時
+                opt(session.and_then(|s| s.end).map(|dt| format_utc(&dt))).as_str(),   // ログオフ日時
+                opt(session.and_then(|s| s.duration_ms)).as_str(), // セッションの継続時間（ミリ秒）
+                session.map(|s| s.state.as_str()).unwrap_or(""), // セッションの終わり方
+                svchost.and_then(|c| c.group.as_deref()).unwrap_or(""), // svchost.exe のサービスグループ（-k）
+                svchost.map(|c| if c.split { "true" } else { "false" }).unwrap_or(""), // -p の指定有無
+                svchost.and_then(|c| c.service.as_deref()).unwrap_or(""), // svchost.exe のサービス名（-s）
+                event.data.to_json().as_str(), // EventData / UserData の全項目（JSONオブジェクト）
+                event.decoded.to_json().as_str(), // コードで記録された項目の表記（JSONオブジェクト）
+                event.excluded_by.as_deref().unwrap_or(""), // 除外したルール名（--show-excluded 時のみ）
+                findings.as_str(), // 検出事項（複数は " / " 区切り）
+            ],
+        )?; // データ行（列順はCSV_COLUMNSと同じ）
+    }
+    Ok(())
+}
+
+/// CSVの値のない項目を空欄にする関数
+fn opt<T: std::fmt::Display>(v: Option<T>) -> String {
+    v.map(|n| n.to_string()).unwrap_or_default()
+}
+
+/// CSVの1行を書き込む関数（カンマ・二重引用符・改行・タブを含む値は引用符で囲む）
+fn write_csv_row<'a>(out: &mut dyn Write, fields: impl IntoIterator<Item = &'a str>) -> std::io::Result<()> {
+    let line: Vec<String> = fields.into_iter().map(csv_escape).collect();
+    write!(out, "{}\r\n", line.join(","))
+}
+
+/// RFC 4180に従ってCSVフィールドをエスケープする関数
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\r', '\n', '\t']) || field.starts_with(' ') || field.ends_with(' ') {
+        format!("\"{}\"", field.replace('"', "\"\"")) // 二重引用符は2つ重ねる
+    } else {
+        field.to_string()
+    }
+}
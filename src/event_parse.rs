@@ -1,62 +1,94 @@
 // --- 必要なクレートの説明 ---
 // quick_xml: XMLパース用（イベントログの解析）
-// crate::event_types::EventRecord: イベントレコード型
+// crate::event_types: パース結果（RawEventRecord）と型付きのイベントレコード（EventRecord）
+//
+// <System> の各項目に加え、<EventData> の全 Data/Binary 値と <UserData> 配下の全要素を
+// 名前→値のマップ（EventData）として取り出します。イベントID固有の項目（ユーザー名・コマンドライン等）は
+// XMLの解析後に known_fields でマップから取り出すため、新しいイベントへの対応で解析処理を変更する必要はありません。
 
-use crate::event_types::EventRecord; // イベントレコード型
+use crate::event_types::{EventData, EventRecord, ParsedLog, RawEventRecord, RecordMark}; // イベントレコード型
+use crate::messages::{tr, Msg}; // メッセージカタログ
 use quick_xml::Reader;               // quick_xmlリーダー
-use quick_xml::events::Event as XmlEvent; // quick_xmlイベント型
+use quick_xml::events::{BytesStart, Event as XmlEvent}; // quick_xmlイベント型
 
-/// XML文字列からイベント情報を抽出し、EventRecordのベクタを返す関数
-pub fn parse_events(xml: &str, ids: &[&str]) -> Vec<EventRecord> {
+/// XML文字列からイベント情報を抽出し、対象イベントIDのEventRecordと全イベントの位置を返す関数
+/// 日時やイベントIDが解釈できないイベントは警告して読み飛ばす
+/// XMLの構文エラーは位置（バイト）を警告し、次の <Event> から解析を再開する（途中で切れた・壊れたファイルでも以降のイベントを失わない）
+pub fn parse_events(xml: &str, ids: &[u32]) -> ParsedLog {
+    let mut parsed = ParsedLog::default(); // 結果格納用
+    let mut offset = 0; // 解析を再開する位置
+    while let Err((pos, e)) = parse_segment(&xml[offset..], ids, &mut parsed) {
+        let pos = offset + pos; // XML文字列の先頭からの位置
+        eprintln!("⚠️ {}", tr(Msg::XmlSyntaxError, &[&pos, &e]));
+        match next_event_start(xml, pos.max(offset + 1)) {
+            Some(next) => offset = next,
+            None => break, // 以降に <Event> がない
+        }
+    }
+    parsed // 結果を返す
+}
+
+/// pos 以降で最初の <Event> 開始タグの位置を返す関数（<EventData> / <EventID> などは対象外）
+fn next_event_start(xml: &str, pos: usize) -> Option<usize> {
+    let mut from = pos;
+    while let Some(found) = xml.get(from..)?.find("<Event") {
+        let start = from + found;
+        let after = xml.as_bytes().get(start + "<Event".len()).copied();
+        if matches!(after, Some(b'>' | b'/' | b' ' | b'\t' | b'\r' | b'\n')) {
+            return Some(start);
+        }
+        from = start + 1;
+    }
+    None
+}
+
+/// XML文字列を先頭から解析し、イベントを parsed に追加する関数
+/// 構文エラーの場合は (エラーの位置（xml の先頭からのバイト数）, エラー内容) を返す（解析中のイベントは破棄）
+fn parse_segment(xml: &str, ids: &[u32], parsed: &mut ParsedLog) -> Result<(), (usize, String)> {
     let mut reader = Reader::from_str(xml); // XMLリーダー生成
-    reader.trim_text(true); // 空白除去設定
+    reader.trim_text(false); // 項目値の前後の空白も保持するため空白除去は行わない
     let mut buf = Vec::new(); // quick_xmlのバッファ
     let mut in_event = false; // <Event>タグ内かどうか
     let mut in_eventid = false; // <EventID>タグ内かどうか
     let mut in_system = false; // <System>タグ内かどうか
-    let mut eventid_val = String::new(); // イベントID格納用
-    let mut systemtime_val = String::new(); // イベント発生時刻格納用
-    let mut proc_name_val = String::new(); // プロセス名格納用
-    let mut user_name_val = String::new(); // ユーザー名（4688/4624/4647用）
-    let mut _app_name_val = String::new(); // アプリ名（4688用）
-    let mut logon_type_val = String::new(); // LogonType（4624用）
-    let mut command_line_val = String::new(); // コマンドライン（4688用）
-    let mut _parent_process_id_val = String::new(); // 親プロセスID（4688用）
-    let mut _new_process_id_val = String::new();    // 新プロセスID（4688用）
-    let mut log_name_val = String::new(); // ログ名
-    let mut all_events = Vec::new(); // 結果格納用ベクタ
-    while let Ok(ev) = reader.read_event_into(&mut buf) { // XMLイベントを順次取得
+    let mut system_tag: Vec<u8> = Vec::new(); // <System>直下の現在のタグ名（テキスト値の格納先判定用）
+    let mut in_event_data = false; // <EventData>タグ内かどうか
+    let mut data_name: Option<String> = None; // <EventData>直下の現在の項目名（Data/@Name または要素名）
+    let mut user_data_stack: Vec<(String, bool)> = Vec::new(); // <UserData>配下の要素（要素名, 子要素の有無）
+    let mut text_val = String::new(); // EventData/UserData の項目値
+    let mut raw = RawEventRecord::default(); // 解析中のイベント
+    loop {
+        // XMLイベントを順次取得（構文エラーは位置とともに返す）
+        let ev = reader.read_event_into(&mut buf).map_err(|e| (reader.buffer_position(), e.to_string()))?;
+        let in_user_data = !user_data_stack.is_empty(); // <UserData>タグ内かどうか
         match ev {
             XmlEvent::Start(ref e) if e.name().as_ref() == b"Event" => {
                 in_event = true; // <Event>タグ開始
-                eventid_val.clear(); // イベントID初期化
-                systemtime_val.clear(); // 日時初期化
-                proc_name_val.clear(); // プロセス名初期化
-                user_name_val = String::new(); // ユーザー名初期化
-                _app_name_val = String::new(); // アプリ名初期化
-                logon_type_val = String::new(); // ログオンタイプ初期化
-                command_line_val = String::new(); // コマンドライン初期化
-                _parent_process_id_val = String::new(); // 親プロセスID初期化
-                _new_process_id_val = String::new(); // 新プロセスID初期化
-                log_name_val = String::new(); // ログ名初期化
+                raw = RawEventRecord::default(); // 各項目を初期化
             }
             XmlEvent::End(ref e) if e.name().as_ref() == b"Event" => {
                 in_event = false; // <Event>タグ終了
-                if !eventid_val.is_empty() && !systemtime_val.is_empty() && ids.contains(&eventid_val.as_str()) {
-                    // 必要なイベントIDのみ格納
-                    all_events.push(EventRecord {
-                        datetime_utc: systemtime_val.clone(), // 発生日時
-                        log_name: log_name_val.clone(),       // ログ名
-                        user: user_name_val.clone(),          // ユーザー名
-                        event_id: eventid_val.clone(),        // イベントID
-                        action: String::new(),                // アクション（後で付与）
-                        description: String::new(),           // 説明（後で付与）
-                        proc_info: proc_name_val.clone(),     // プロセス情報
-                        logon_type: logon_type_val.clone(),   // ログオンタイプ
-                        command_line: command_line_val.clone(), // コマンドライン
-                    });
+                if let Some(mark) = record_mark(&raw) {
+                    parsed.records.push(mark); // 対象外のイベントも位置を記録（EventRecordID の連続性の確認用）
+                }
+                let wanted = raw.event_id.trim().parse::<u32>().is_ok_and(|id| ids.contains(&id)); // 対象イベントIDか
+                if wanted && !raw.datetime_utc.is_empty() {
+                    // 必要なイベントIDのみ型付きのレコードに変換して格納
+                    known_fields(&mut raw); // イベントID固有の項目を取り出し
+                    match EventRecord::try_from(std::mem::take(&mut raw)) {
+                        Ok(record) => {
+                            if !record.invalid_fields.is_empty() {
+                                // 解釈できない項目は値なしとしてイベントは残す
+                                let fields = record.invalid_fields.join(", ");
+                                eprintln!("⚠️ {}", tr(Msg::EventFieldInvalid, &[&record.event_id, &record.record_id, &fields]));
+                            }
+                            parsed.events.push(record)
+                        }
+                        Err(e) => eprintln!("⚠️ {}", tr(Msg::EventMalformed, &[&e])), // 解釈できないイベントは読み飛ばし
+                    }
                 }
             }
+            // --- <System> ---
             XmlEvent::Start(ref e) if in_event && e.name().as_ref() == b"EventID" => {
                 in_eventid = true; // <EventID>タグ開始
             }
@@ -64,7 +96,7 @@ pub fn parse_events(xml: &str, ids: &[&str]) -> Vec<EventRecord> {
                 in_eventid = false; // <EventID>タグ終了
             }
             XmlEvent::Text(e) if in_eventid => {
-                eventid_val = e.unescape().unwrap_or_default().to_string(); // イベントID取得
+                raw.event_id = e.unescape().unwrap_or_default().to_string(); // イベントID取得
             }
             XmlEvent::Start(ref e) if in_event && e.name().as_ref() == b"System" => {
                 in_system = true; // <System>タグ開始
@@ -72,92 +104,298 @@ pub fn parse_events(xml: &str, ids: &[&str]) -> Vec<EventRecord> {
             XmlEvent::End(ref e) if in_event && e.name().as_ref() == b"System" => {
                 in_system = false; // <System>タグ終了
             }
+            XmlEvent::Start(ref e) | XmlEvent::Empty(ref e) if in_system && e.name().as_ref() == b"Provider" => {
+                if let Some(name) = attr_value(e, b"Name") {
+                    raw.provider = name; // プロバイダ名取得
+                }
+            }
+            XmlEvent::Empty(ref e) if in_system && e.name().as_ref() == b"Execution" => {
+                raw.process_id = attr_value(e, b"ProcessID").unwrap_or_default(); // プロセスID取得
+                raw.thread_id = attr_value(e, b"ThreadID").unwrap_or_default(); // スレッドID取得
+            }
             XmlEvent::Empty(ref e) if in_system && e.name().as_ref() == b"TimeCreated" => {
-                for attr in e.attributes().flatten() { // 属性を走査
-                    if attr.key.as_ref() == b"SystemTime" {
-                        systemtime_val = attr.unescape_value().unwrap_or_default().to_string(); // 日時取得
-                    }
+                if let Some(time) = attr_value(e, b"SystemTime") {
+                    raw.datetime_utc = time; // 日時取得
                 }
             }
-            XmlEvent::Empty(ref e) if in_event && e.name().as_ref() == b"ProcessName" => {
-                for attr in e.attributes().flatten() { // 属性を走査
-                    if attr.key.as_ref() == b"Name" {
-                        proc_name_val = attr.unescape_value().unwrap_or_default().to_string(); // プロセス名取得
-                    }
+            XmlEvent::Start(ref e) if in_system => {
+                system_tag = e.name().as_ref().to_vec(); // <System>直下のタグ開始
+            }
+            XmlEvent::End(_) if in_system => {
+                system_tag.clear(); // <System>直下のタグ終了
+            }
+            XmlEvent::Text(e) if in_system => {
+                let text = e.unescape().unwrap_or_default().to_string(); // タグ内のテキスト
+                match system_tag.as_slice() {
+                    b"EventRecordID" => raw.record_id = text, // EventRecordID取得
+                    b"Channel" => raw.log_name = text,        // ログ名取得
+                    b"Computer" => raw.computer = text,       // コンピュータ名取得
+                    b"Level" => raw.level = text,             // レベル取得
+                    _ => {}
                 }
             }
-            XmlEvent::Start(ref e) if in_event && e.name().as_ref() == b"ProcessName" => {
-                if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                    proc_name_val = e2.unescape().unwrap_or_default().to_string(); // プロセス名取得
+            // --- <EventData>: Data（Name属性がなければ "Data"）・Binary 等の直下の要素を1項目とする ---
+            XmlEvent::Start(ref e) if in_event && e.local_name().as_ref() == b"EventData" => {
+                in_event_data = true; // <EventData>タグ開始
+            }
+            XmlEvent::End(ref e) if in_event_data && e.local_name().as_ref() == b"EventData" => {
+                in_event_data = false; // <EventData>タグ終了
+            }
+            XmlEvent::Start(ref e) if in_event_data => {
+                data_name = Some(data_item_name(e)); // 項目開始
+                text_val.clear();
+            }
+            XmlEvent::Empty(ref e) if in_event_data => {
+                raw.data.insert(&data_item_name(e), ""); // 値のない項目
+            }
+            XmlEvent::End(_) if in_event_data => {
+                if let Some(name) = data_name.take() {
+                    raw.data.insert(&name, &text_val); // 項目終了
                 }
             }
-            XmlEvent::Start(ref e) if in_event && e.name().as_ref() == b"Data" => {
-                if let Some(Ok(attr)) = e.attributes().next() { // 最初の属性のみ取得
-                    if attr.key.as_ref() == b"Name" {
-                        let name = attr.unescape_value().unwrap_or_default(); // DataタグのName属性
-                        if eventid_val == "4688" {
-                            // 4688イベント用の各種情報抽出
-                            if name == "NewProcessName" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                _app_name_val = text_val; // 新プロセス名
-                            } else if name == "SubjectUserName" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                user_name_val = text_val; // ユーザー名
-                            } else if name == "CommandLine" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                command_line_val = text_val; // コマンドライン
-                            } else if name == "ParentProcessId" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                _parent_process_id_val = text_val; // 親プロセスID
-                            } else if name == "NewProcessId" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                _new_process_id_val = text_val; // 新プロセスID
-                            }
-                        } else if eventid_val == "4624" || eventid_val == "4647" {
-                            // 4624/4647イベント用の各種情報抽出
-                            if name == "SubjectUserName" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                user_name_val = text_val; // ユーザー名
-                            } else if eventid_val == "4624" && name == "LogonType" {
-                                let text_val = if let Ok(XmlEvent::Text(e2)) = reader.read_event_into(&mut buf) {
-                                    let s = e2.unescape().unwrap_or_default().to_string();
-                                    buf.clear();
-                                    s
-                                } else { String::new() };
-                                logon_type_val = text_val; // ログオンタイプ
-                            }
-                        }
+            // --- <UserData>: 子要素を持たない要素を "要素名"、属性を "要素名@属性名" の項目とする ---
+            XmlEvent::Start(ref e) if in_event && !in_user_data && e.local_name().as_ref() == b"UserData" => {
+                user_data_stack.push(("UserData".to_string(), false)); // <UserData>タグ開始
+            }
+            XmlEvent::Start(ref e) if in_user_data => {
+                if let Some(parent) = user_data_stack.last_mut() {
+                    parent.1 = true; // 親要素は子要素あり
+                }
+                let name = local_name(e);
+                insert_attrs(&mut raw.data, &name, e); // 属性
+                user_data_stack.push((name, false));
+                text_val.clear();
+            }
+            XmlEvent::Empty(ref e) if in_user_data => {
+                if let Some(parent) = user_data_stack.last_mut() {
+                    parent.1 = true; // 親要素は子要素あり
+                }
+                let name = local_name(e);
+                if !insert_attrs(&mut raw.data, &name, e) {
+                    raw.data.insert(&name, ""); // 属性もない空要素
+                }
+            }
+            XmlEvent::End(_) if in_user_data => {
+                if let Some((name, has_child)) = user_data_stack.pop() {
+                    if !has_child && !user_data_stack.is_empty() {
+                        raw.data.insert(&name, &text_val); // 子要素を持たない要素の値
                     }
                 }
+                text_val.clear();
+            }
+            XmlEvent::Text(e) if in_event_data || in_user_data => {
+                text_val.push_str(&e.unescape().unwrap_or_default()); // 項目値
+            }
+            XmlEvent::CData(e) if in_event_data || in_user_data => {
+                text_val.push_str(&String::from_utf8_lossy(&e)); // 項目値（CDATA）
             }
             XmlEvent::Eof => break, // EOFでループ終了
             _ => {} // その他は無視
         }
         buf.clear(); // バッファクリア
     }
-    all_events // 結果ベクタを返す
+    Ok(())
+}
+
+/// XML文字列から各イベントの <Computer>（記録したコンピュータ名）をログ上の順に取り出す関数
+/// 構文エラーは parse_events と同様に次の <Event> から再開する（警告は parse_events で表示）
+pub fn computer_names(xml: &str) -> Vec<String> {
+    let mut names = Vec::new(); // 結果格納用
+    let mut offset = 0; // 解析を再開する位置
+    loop {
+        let mut reader = Reader::from_str(&xml[offset..]); // XMLリーダー生成
+        let mut buf = Vec::new(); // quick_xmlのバッファ
+        let mut in_computer = false; // <Computer>タグ内かどうか
+        let error_pos = loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(XmlEvent::Start(ref e)) if e.name().as_ref() == b"Computer" => in_computer = true,
+                Ok(XmlEvent::End(ref e)) if e.name().as_ref() == b"Computer" => in_computer = false,
+                Ok(XmlEvent::Text(e)) if in_computer => {
+                    let name = e.unescape().unwrap_or_default().trim().to_string();
+                    if !name.is_empty() {
+                        names.push(name);
+                    }
+                }
+                Ok(XmlEvent::Eof) => return names,
+                Err(_) => break offset + reader.buffer_position(),
+                _ => {}
+            }
+            buf.clear(); // バッファクリア
+        };
+        match next_event_start(xml, error_pos.max(offset + 1)) {
+            Some(next) => offset = next,
+            None => return names,
+        }
+    }
+}
+
+/// イベントの位置（EventRecordID・イベントID・日時）を取り出す関数（いずれかが解釈できなければNone）
+fn record_mark(raw: &RawEventRecord) -> Option<RecordMark> {
+    Some(RecordMark {
+        record_id: raw.record_id.trim().parse().ok()?,
+        event_id: raw.event_id.trim().parse().ok()?,
+        timestamp: chrono::DateTime::parse_from_rfc3339(raw.datetime_utc.trim()).ok()?.with_timezone(&chrono::Utc),
+    })
+}
+
+/// EventData / UserData のマップからイベントID固有の項目を取り出す関数
+fn known_fields(raw: &mut RawEventRecord) {
+    let data = &raw.data;
+    let field = |name: &str| data.get(name).unwrap_or_default().to_string(); // 項目がなければ空文字列
+    raw.proc_info = data.get("ProcessName").or_else(|| data.get("ProcessName@Name")).unwrap_or_default().to_string(); // プロセス名
+    match raw.event_id.trim() {
+        "4688" => {
+            raw.user = field("SubjectUserName");       // ユーザー名
+            raw.command_line = field("CommandLine");   // コマンドライン
+            raw.new_process_id = field("NewProcessId"); // 作成されたプロセスのID
+            raw.parent_process_id = field("ProcessId"); // 作成元（親）プロセスのID
+            raw.subject_logon_id = field("SubjectLogonId"); // 作成元のログオンID
+        }
+        "1102" | "104" | "4719" | "4616" => {
+            raw.user = field("SubjectUserName");       // 操作したユーザー名（ログの消去・監査ポリシー・時刻の変更）
+        }
+        "4689" => {
+            raw.user = field("SubjectUserName");       // ユーザー名
+            raw.exit_process_id = field("ProcessId");  // 終了したプロセスのID
+            raw.exit_status = field("Status");         // 終了コード
+        }
+        "4624" | "4634" | "4647" => {
+            // ログオン・ログオフはログオン対象（Target*）のアカウントを記録（Subject* は要求元で、多くはコンピュータアカウント）
+            let field = |name: &str| Some(field(name)).filter(|v| v != "-").unwrap_or_default(); // "-" は値なし
+            raw.user = field("TargetUserName");         // ユーザー名
+            raw.logon_type = field("LogonType");        // ログオンタイプ（4624 / 4634）
+            raw.target_user = field("TargetUserName");  // ログオン対象のユーザー名
+            raw.target_domain = field("TargetDomainName"); // ログオン対象のドメイン名
+            raw.target_logon_id = field("TargetLogonId"); // ログオンID
+            raw.ip_address = field("IpAddress");        // 接続元のIPアドレス
+            raw.workstation = field("WorkstationName"); // 接続元のワークステーション名
+            raw.logon_process = field("LogonProcessName").trim().to_string(); // ログオンプロセス名（末尾に空白が入る場合がある）
+            raw.auth_package = field("AuthenticationPackageName"); // 認証パッケージ名
+        }
+        "4625" | "4771" | "4776" => {
+            // ログオン失敗（4625）・Kerberos 事前認証の失敗（4771）・資格情報の検証（4776、成功も記録される）
+            let field = |name: &str| Some(field(name)).filter(|v| v != "-").unwrap_or_default(); // "-" は値なし
+            raw.user = field("TargetUserName");         // ユーザー名
+            raw.logon_type = field("LogonType");        // ログオンタイプ（4625）
+            raw.target_user = field("TargetUserName");  // ログオン対象のユーザー名
+            raw.target_domain = field("TargetDomainName"); // ログオン対象のドメイン名（4625）
+            raw.ip_address = field("IpAddress").trim_start_matches("::ffff:").to_string(); // 接続元のIPアドレス（4771 はIPv4射影アドレス）
+            raw.workstation = Some(field("WorkstationName")).filter(|w| !w.is_empty()).unwrap_or_else(|| field("Workstation")); // 接続元のワークステーション名（4776 は Workstation）
+            raw.logon_process = field("LogonProcessName").trim().to_string(); // ログオンプロセス名（4625）
+            raw.auth_package = Some(field("AuthenticationPackageName")).filter(|p| !p.is_empty()).unwrap_or_else(|| field("PackageName")); // 認証パッケージ名（4776 は PackageName）
+            raw.status = field("Status");               // 失敗の理由
+            raw.sub_status = field("SubStatus");        // 失敗の理由の詳細（4625）
+        }
+        _ => {}
+    }
+}
+
+/// 指定した属性の値を取得する関数
+fn attr_value(e: &BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == key)
+        .map(|attr| attr.unescape_value().unwrap_or_default().to_string())
+}
+
+/// 要素名（名前空間接頭辞を除く）を取得する関数
+fn local_name(e: &BytesStart) -> String {
+    String::from_utf8_lossy(e.local_name().as_ref()).to_string()
+}
+
+/// <EventData>直下の要素の項目名（Data は Name 属性、それ以外は要素名）を決定する関数
+fn data_item_name(e: &BytesStart) -> String {
+    let name = local_name(e);
+    if name == "Data" {
+        if let Some(n) = attr_value(e, b"Name").filter(|n| !n.is_empty()) {
+            return n;
+        }
+    }
+    name
+}
+
+/// 要素の属性（xmlns を除く）を "要素名@属性名" の項目として追加する関数（1つ以上追加した場合はtrue）
+fn insert_attrs(data: &mut EventData, element: &str, e: &BytesStart) -> bool {
+    let mut inserted = false;
+    for attr in e.attributes().flatten() {
+        let key = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+        if key == "xmlns" || key.starts_with("xmlns:") {
+            continue; // 名前空間宣言は対象外
+        }
+        data.insert(&format!("{}@{}", element, key), &attr.unescape_value().unwrap_or_default());
+        inserted = true;
+    }
+    inserted
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(record_id: u32, event_id: u32, data: &str) -> String {
+        format!(
+            "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Security-Auditing'/>\
+             <EventID>{}</EventID><TimeCreated SystemTime='2025-07-10T06:00:{:02}.000Z'/><EventRecordID>{}</EventRecordID>\
+             <Channel>Security</Channel><Computer>HOST1</Computer></System><EventData>{}</EventData></Event>\r\n",
+            event_id, record_id, record_id, data
+        )
+    }
+
+    fn record_ids(parsed: &ParsedLog) -> Vec<u64> {
+        parsed.events.iter().map(|e| e.record_id).collect()
+    }
+
+    #[test]
+    fn parses_events_and_records() {
+        let xml = [event(1, 4624, ""), event(2, 4688, "<Data Name='NewProcessId'>0x1a4</Data>"), event(3, 4689, "")].concat();
+        let parsed = parse_events(&xml, &[4688, 4689]);
+        assert_eq!(record_ids(&parsed), [2, 3]);
+        assert_eq!(parsed.records.iter().map(|m| m.record_id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(parsed.events[0].new_process_id, Some(0x1a4));
+        assert_eq!(computer_names(&xml), ["HOST1", "HOST1", "HOST1"]);
+    }
+
+    #[test]
+    fn syntax_error_skips_to_the_next_event() {
+        let broken = event(2, 4624, "<Data Name='TargetUserName'>alice</Dat>");
+        let xml = [event(1, 4624, ""), broken, event(3, 4624, ""), event(4, 4624, "")].concat();
+        let parsed = parse_events(&xml, &[4624]);
+        assert_eq!(record_ids(&parsed), [1, 3, 4]);
+        assert_eq!(parsed.records.iter().map(|m| m.record_id).collect::<Vec<_>>(), [1, 3, 4]);
+        assert_eq!(computer_names(&xml).len(), 4); // 壊れたイベントの <Computer> は構文エラーより前
+    }
+
+    #[test]
+    fn truncated_export_keeps_the_events_before_the_cut() {
+        let full = [event(1, 4624, ""), event(2, 4624, ""), event(3, 4624, "")].concat();
+        let cut = &full[..full.len() - 60];
+        let parsed = parse_events(&format!("<Events>{}", cut), &[4624]);
+        assert_eq!(record_ids(&parsed), [1, 2]);
+    }
+
+    #[test]
+    fn next_event_start_ignores_event_data_and_event_id() {
+        let xml = "<EventData><EventID>1</EventID><Events><Event xmlns='x'>";
+        assert_eq!(next_event_start(xml, 0), Some(xml.find("<Event ").unwrap()));
+        assert_eq!(next_event_start("<EventData/>", 0), None);
+        assert_eq!(next_event_start("<Event>", 1), None);
+    }
+
+    #[test]
+    fn unparsable_field_keeps_the_event() {
+        let data = "<Data Name='NewProcessId'>0xzz</Data><Data Name='ProcessId'>0x2f0</Data><Data Name='SubjectLogonId'>bad</Data>";
+        let parsed = parse_events(&event(7, 4688, data), &[4688]);
+        assert_eq!(record_ids(&parsed), [7]);
+        let ev = &parsed.events[0];
+        assert_eq!(ev.new_process_id, None);
+        assert_eq!(ev.parent_process_id, Some(0x2f0));
+        assert_eq!(ev.subject_logon_id, None);
+        assert_eq!(ev.invalid_fields, ["NewProcessId: 0xzz", "SubjectLogonId: bad"]);
+    }
+
+    #[test]
+    fn bad_timestamp_or_event_id_still_drops_the_event() {
+        let xml = event(1, 4624, "").replace("2025-07-10T06:00:01.000Z", "yesterday");
+        assert!(parse_events(&xml, &[4624]).events.is_empty());
+    }
 }
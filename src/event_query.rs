@@ -1,15 +1,76 @@
 // --- 必要なクレートの説明 ---
-// std::process::Command: 外部コマンド実行用（wevtutil呼び出し）
+// crate::command_runner::CommandRunner: 外部コマンド実行用（wevtutil呼び出し）
+// std::fs / std::path: 保存済みXML/EVTXファイルの読み込み用（オフライン解析）
 
 // wevtutilコマンドでイベントログを取得する関数群
 // XML文字列を返す
-use std::process::Command; // コマンド実行用
+use crate::command_runner::CommandRunner; // 外部コマンド実行
+use std::path::{Path, PathBuf}; // ファイルパス操作用
+use crate::event_evtx::read_evtx_file; // EVTXバイナリ読み込み
+use crate::messages::{tr, Msg}; // メッセージカタログ
 
 /// 指定ログ・クエリでwevtutilを実行し、XML文字列を返す関数
-pub fn fetch_eventlog_xml(log: &str, query: &str) -> Result<String, String> {
-    let mut cmd = Command::new("wevtutil"); // wevtutilコマンド生成
-    cmd.args(&["qe", log, &format!("/q:{}", query), "/f:xml"]); // 引数セット
-    let output = cmd.output().map_err(|e| format!("コマンド実行失敗: {}", e))?; // コマンド実行
-    let result = String::from_utf8_lossy(&output.stdout).to_string(); // 標準出力を文字列化
-    Ok(result) // XML文字列を返す
+/// 異常終了（ログが存在しない・アクセス拒否など）は「イベントなし」と区別するためErrとする
+pub fn fetch_eventlog_xml(runner: &dyn CommandRunner, log: &str, query: &str) -> Result<String, String> {
+    let query = format!("/q:{}", query); // クエリ引数
+    let args = ["qe", log, query.as_str(), "/f:xml"]; // 引数
+    let output = runner.run("wevtutil", &args)?; // コマンド実行
+    if !output.success {
+        let command = format!("wevtutil {}", args.join(" "));
+        return Err(tr(Msg::CommandFailed, &[&command, &output.stderr.trim()])); // 異常終了
+    }
+    Ok(output.stdout) // XML文字列を返す
+}
+
+/// 保存済みのwevtutil XML出力ファイルを読み込み、XML文字列を返す関数
+/// PowerShellのリダイレクトで保存されたUTF-16LE(BOM付き)ファイルにも対応
+pub fn read_eventlog_xml_file(path: &Path) -> Result<String, String> {
+    let bytes = std::fs::read(path).map_err(|e| tr(Msg::FileReadFailed, &[&path.display(), &e]))?; // ファイル読み込み
+    if bytes.starts_with(&[0xFF, 0xFE]) {
+        // UTF-16LE（BOM付き）
+        let units: Vec<u16> = bytes[2..].chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    if bytes.starts_with(&[0xFE, 0xFF]) {
+        // UTF-16BE（BOM付き）
+        let units: Vec<u16> = bytes[2..].chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+        return Ok(String::from_utf16_lossy(&units));
+    }
+    let body = bytes.strip_prefix(&[0xEF, 0xBB, 0xBF]).unwrap_or(&bytes); // UTF-8 BOMを除去
+    Ok(String::from_utf8_lossy(body).to_string()) // XML文字列を返す
+}
+
+/// 保存済みイベントログファイルを読み込み、XML文字列を返す関数
+/// 拡張子が .evtx の場合はEVTXバイナリとして解析し、それ以外はXMLとして読み込む
+pub fn read_event_file(path: &Path) -> Result<String, String> {
+    let is_evtx = path.extension().map(|e| e.eq_ignore_ascii_case("evtx")).unwrap_or(false); // 拡張子判定
+    if is_evtx {
+        read_evtx_file(path) // EVTXバイナリ
+    } else {
+        read_eventlog_xml_file(path) // wevtutil XML
+    }
+}
+
+/// ファイル名（拡張子なし）からログ名を復元する関数
+/// 例: "Microsoft-Windows-TaskScheduler%4Operational" → "Microsoft-Windows-TaskScheduler/Operational"
+pub fn log_name_from_file_stem(stem: &str) -> String {
+    stem.replace("%4", "/") // Windowsのファイル名規則（%4 = '/'）を戻す
+}
+
+/// ディレクトリ内の指定拡張子（xml/evtx）のファイルを列挙し、(ログ名, ファイルパス) のリストを返す関数
+pub fn list_event_dir(dir: &Path, ext: &str) -> Result<Vec<(String, PathBuf)>, String> {
+    let entries = std::fs::read_dir(dir).map_err(|e| tr(Msg::DirReadFailed, &[&dir.display(), &e]))?; // ディレクトリ走査
+    let mut files = Vec::new(); // 結果格納用
+    for entry in entries.flatten() {
+        let path = entry.path(); // ファイルパス
+        let matched = path.extension().map(|e| e.eq_ignore_ascii_case(ext)).unwrap_or(false); // 拡張子判定
+        if !path.is_file() || !matched {
+            continue; // 対象拡張子以外は無視
+        }
+        if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+            files.push((log_name_from_file_stem(stem), path.clone())); // ログ名とパスを格納
+        }
+    }
+    files.sort(); // 出力順を安定させる
+    Ok(files)
 }
@@ -0,0 +1,213 @@
+// --- 必要なクレートの説明 ---
+// このファイルはイベントログの取得元を抽象化するモジュールです
+// crate::event_query: wevtutil呼び出し・保存済みファイル読み込み
+// crate::event_parse::parse_events: XMLパース
+// chrono: 監査範囲の指定
+
+use std::path::PathBuf; // ファイルパス
+use crate::command_runner::CommandRunner; // 外部コマンド実行
+use crate::event_types::{Channel, ParsedLog}; // 取得結果・チャネル
+use crate::event_query::{fetch_eventlog_xml, read_event_file}; // XML取得関数
+use crate::event_parse::{computer_names, parse_events}; // XMLパース関数
+
+/// 監査範囲（UTCの開始・終了時刻）
+pub type TimeRange = (chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>);
+
+/// イベントログの取得元を表すトレイト
+/// wevtutil（ライブ）・保存済みXML/EVTX・テスト用のメモリ上データを同じ解析処理で扱うために使用
+pub trait EventSource {
+    /// 取得可能なログ（チャネル）名の一覧を返す
+    fn channels(&self) -> Vec<String>;
+    /// 指定チャネルから、対象イベントIDかつ監査範囲内（Noneなら全期間）のイベントを取得する
+    /// 監査範囲内の全イベントの位置（EventRecordID）も併せて返す
+    fn fetch_events(&self, channel: &str, ids: &[u32], range: Option<TimeRange>) -> Result<ParsedLog, String>;
+}
+
+/// wevtutilでローカルのイベントログを取得する取得元
+pub struct WevtutilSource<'a> {
+    runner: &'a dyn CommandRunner, // wevtutilの実行に使うコマンド実行
+    channels: Vec<String>,         // 取得対象のログ名
+}
+
+impl<'a> WevtutilSource<'a> {
+    /// コマンド実行と取得対象のログ名を指定して生成する
+    pub fn new(runner: &'a dyn CommandRunner, channels: &[&str]) -> Self {
+        WevtutilSource { runner, channels: channels.iter().map(|c| c.to_string()).collect() }
+    }
+}
+
+impl EventSource for WevtutilSource<'_> {
+    fn channels(&self) -> Vec<String> {
+        self.channels.clone()
+    }
+
+    fn fetch_events(&self, channel: &str, ids: &[u32], range: Option<TimeRange>) -> Result<ParsedLog, String> {
+        // wevtutil用クエリ文字列（範囲指定なしの場合は全件）
+        let query = match range {
+            Some((start_time, end_time)) => format!(
+                "*[System[TimeCreated[@SystemTime>='{}' and @SystemTime<='{}']]]",
+                start_time.format("%Y-%m-%dT%H:%M:%S%.3fZ"), // 開始時刻文字列
+                end_time.format("%Y-%m-%dT%H:%M:%S%.3fZ")    // 終了時刻文字列
+            ),
+            None => "*".to_string(),
+        };
+        let xml = fetch_eventlog_xml(self.runner, channel, &query)?; // wevtutilでXML取得
+        Ok(with_channel(parse_events(&xml, ids), channel)) // XMLをパースしてイベント抽出
+    }
+}
+
+/// メモリ上のXML文字列を取得元とする（保存済みファイルの読み込み結果やテスト用データ）
+pub struct MemorySource {
+    logs: Vec<(String, String)>, // (ログ名, XML文字列)
+}
+
+impl MemorySource {
+    /// (ログ名, XML文字列) のリストから生成する
+    pub fn new(logs: Vec<(String, String)>) -> Self {
+        MemorySource { logs }
+    }
+
+    /// 保存済みXML/EVTXファイルを読み込んで生成する（読み込み失敗は警告して続行）
+    pub fn from_files(files: &[(String, PathBuf)]) -> Self {
+        let mut logs = Vec::new(); // 読み込み結果
+        for (log, path) in files {
+            match read_event_file(path) {
+                Ok(xml) => logs.push((log.clone(), xml)), // XML取得
+                Err(e) => eprintln!("⚠️ {}", e), // 読み込み失敗は警告
+            }
+        }
+        MemorySource::new(logs)
+    }
+}
+
+impl MemorySource {
+    /// 全ログのうち最新のイベントの日時（イベントがなければNone、オフライン解析の --last の基準）
+    pub fn latest_timestamp(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.logs.iter().filter_map(|(_, xml)| parse_events(xml, &[]).records.iter().map(|mark| mark.timestamp).max()).max()
+    }
+
+    /// ログを記録したコンピュータ名（全イベントの Computer のうち最も多いもの、同数なら先に現れたもの）
+    /// オフライン解析の出力ファイル名の {host} に使う（解析端末ではなく、ログの収集元のホスト名）
+    pub fn host_name(&self) -> Option<String> {
+        let mut counts: Vec<(String, usize)> = Vec::new(); // (コンピュータ名, 出現回数)（出現順）
+        for name in self.logs.iter().flat_map(|(_, xml)| computer_names(xml)) {
+            match counts.iter_mut().find(|(n, _)| n.eq_ignore_ascii_case(&name)) {
+                Some((_, count)) => *count += 1,
+                None => counts.push((name, 1)),
+            }
+        }
+        counts.into_iter().rev().max_by_key(|(_, count)| *count).map(|(name, _)| name)
+    }
+}
+
+impl EventSource for MemorySource {
+    fn channels(&self) -> Vec<String> {
+        let mut channels: Vec<String> = Vec::new(); // 重複を除いたログ名
+        for (log, _) in &self.logs {
+            if !channels.iter().any(|c| c.eq_ignore_ascii_case(log)) {
+                channels.push(log.clone());
+            }
+        }
+        channels
+    }
+
+    fn fetch_events(&self, channel: &str, ids: &[u32], range: Option<TimeRange>) -> Result<ParsedLog, String> {
+        let mut parsed = ParsedLog::default(); // 結果格納用
+        for (_, xml) in self.logs.iter().filter(|(log, _)| log.eq_ignore_ascii_case(channel)) {
+            let mut log = parse_events(xml, ids); // XMLをパースしてイベント抽出
+            parsed.events.append(&mut log.events);
+            parsed.records.append(&mut log.records);
+        }
+        if let Some((start_time, end_time)) = range {
+            // 範囲指定がある場合は監査範囲内のイベントのみ残す
+            parsed.events.retain(|ev| ev.timestamp >= start_time && ev.timestamp <= end_time);
+            parsed.records.retain(|mark| mark.timestamp >= start_time && mark.timestamp <= end_time);
+        }
+        Ok(with_channel(parsed, channel))
+    }
+}
+
+/// XMLにChannelが含まれないイベントのログ名を取得元のログ名で補う関数
+fn with_channel(mut parsed: ParsedLog, channel: &str) -> ParsedLog {
+    for ev in parsed.events.iter_mut().filter(|ev| ev.channel.name().is_empty()) {
+        ev.channel = Channel::from_name(channel);
+    }
+    parsed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::ScriptedRunner;
+    use crate::messages::{tr, Msg};
+    use chrono::TimeZone;
+
+    fn fixture(name: &str) -> String {
+        let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(name);
+        std::fs::read_to_string(path).unwrap()
+    }
+
+    #[test]
+    fn wevtutil_source_queries_the_audit_range() {
+        let xml = fixture("boots/System.xml");
+        let runner = ScriptedRunner::new().on(
+            "wevtutil",
+            &[
+                "qe",
+                "System",
+                "/q:*[System[TimeCreated[@SystemTime>='2025-07-10T00:00:00.000Z' and @SystemTime<='2025-07-11T00:00:00.000Z']]]",
+                "/f:xml",
+            ],
+            true,
+            &xml,
+        );
+        let range = (chrono::Utc.with_ymd_and_hms(2025, 7, 10, 0, 0, 0).unwrap(), chrono::Utc.with_ymd_and_hms(2025, 7, 11, 0, 0, 0).unwrap());
+        let source = WevtutilSource::new(&runner, &["System"]);
+        let parsed = source.fetch_events("System", &[6005, 6006], Some(range)).unwrap();
+        assert_eq!(parsed.events.len(), parse_events(&xml, &[6005, 6006]).events.len());
+        assert!(!parsed.events.is_empty());
+    }
+
+    #[test]
+    fn wevtutil_failure_is_an_error_not_an_empty_log() {
+        let runner = ScriptedRunner::new().on_failure("wevtutil", &["qe", "Security", "/q:*", "/f:xml"], "Access is denied.\r\n");
+        let source = WevtutilSource::new(&runner, &["Security"]);
+        assert_eq!(
+            source.fetch_events("Security", &[4624], None).unwrap_err(),
+            tr(Msg::CommandFailed, &[&"wevtutil qe Security /q:* /f:xml", &"Access is denied."])
+        );
+    }
+
+    #[test]
+    fn wevtutil_spawn_failure_is_an_error() {
+        let runner = ScriptedRunner::new().on_error("wevtutil", &["qe", "Security", "/q:*", "/f:xml"], "not found");
+        let source = WevtutilSource::new(&runner, &["Security"]);
+        assert_eq!(source.fetch_events("Security", &[4624], None).unwrap_err(), "not found");
+    }
+
+    fn event(computer: &str, time: &str) -> String {
+        format!(
+            "<Event><System><EventID>6005</EventID><TimeCreated SystemTime='{}'/><EventRecordID>1</EventRecordID><Computer>{}</Computer></System></Event>",
+            time, computer
+        )
+    }
+
+    #[test]
+    fn host_name_is_the_most_common_computer_in_the_logs() {
+        let source = MemorySource::new(vec![
+            ("System".to_string(), [event("dc01.corp.local", "2025-07-10T00:00:00Z"), event("WS07", "2025-07-10T00:00:01Z")].concat()),
+            ("Security".to_string(), [event("WS07", "2025-07-10T00:00:02Z"), event("ws07", "2025-07-10T00:00:03Z")].concat()),
+        ]);
+        assert_eq!(source.host_name().as_deref(), Some("WS07"));
+        assert_eq!(MemorySource::new(vec![("System".to_string(), event("", "2025-07-10T00:00:00Z"))]).host_name(), None);
+        let tie = MemorySource::new(vec![("System".to_string(), [event("B", "2025-07-10T00:00:00Z"), event("A", "2025-07-10T00:00:01Z")].concat())]);
+        assert_eq!(tie.host_name().as_deref(), Some("B"));
+    }
+
+    #[test]
+    fn fixture_host_name_and_latest_timestamp() {
+        let source = MemorySource::new(vec![("System".to_string(), fixture("boots/System.xml")), ("Security".to_string(), fixture("boots/Security.xml"))]);
+        assert_eq!(source.host_name().as_deref(), Some("HOST1"));
+        assert_eq!(source.latest_timestamp(), Some(chrono::Utc.with_ymd_and_hms(2025, 7, 11, 9, 2, 0).unwrap()));
+    }
+}
@@ -1,19 +1,589 @@
 // --- 必要なクレートの説明 ---
 // このファイルはイベント情報を保持する構造体や型定義をまとめるモジュールです
-// 標準クレートのみで動作（外部依存なし）
+// serde: JSON等の構造化出力用シリアライズ
+// chrono: イベント発生日時（UTC）の保持用
 
-/// イベント情報を保持する構造体や型定義をまとめる
-/// 今後必要に応じて拡張
+use serde::{Serialize, Serializer}; // シリアライズ
+use serde::ser::SerializeMap; // 名前→値の順序付きマップのシリアライズ
+use chrono::{DateTime, Utc}; // 日時
+use crate::event_catalog::EventCategory; // 色分けカテゴリ
+use crate::event_decode::decode_data; // コードで記録された項目の表記
+use crate::svchost::{parse_svchost, SvchostCommand}; // svchost.exe のコマンドライン解析
+use crate::process_lifetime::ProcessLifetime; // プロセスの実行期間
+use crate::logon_session::LogonSession; // ログオンセッション
+use crate::boot_session::BootMark; // 起動番号と起動からの経過時間
 
-#[derive(Debug, Clone)] // デバッグ表示・クローン可
+/// XMLパーサが抽出したままの文字列のイベント情報
+/// EventRecord へは TryFrom で変換する（日時・イベントIDの解釈に失敗した場合はErr、それ以外の数値項目は値なしとして記録）
+#[derive(Debug, Clone, Default)]
+pub struct RawEventRecord {
+    pub datetime_utc: String,  // UTC日時文字列（TimeCreated/@SystemTime）
+    pub record_id: String,     // EventRecordID
+    pub event_id: String,      // イベントID
+    pub log_name: String,      // ログ名（Channel）
+    pub provider: String,      // プロバイダ名（Provider/@Name）
+    pub computer: String,      // コンピュータ名
+    pub level: String,         // レベル
+    pub process_id: String,    // 記録したプロセスのID（Execution/@ProcessID）
+    pub thread_id: String,     // 記録したスレッドのID（Execution/@ThreadID）
+    pub user: String,          // ユーザー名
+    pub proc_info: String,     // プロセス名やコマンドライン等
+    pub logon_type: String,    // ログオンタイプ（4624用）
+    pub command_line: String,  // コマンドライン（4688用）
+    pub new_process_id: String,    // 作成されたプロセスのID（4688用、0x1a4 形式）
+    pub parent_process_id: String, // 作成元（親）プロセスのID（4688用、0x2f0 形式）
+    pub exit_process_id: String,   // 終了したプロセスのID（4689用、0x1a4 形式）
+    pub exit_status: String,       // 終了コード（4689用、0x0 形式）
+    pub subject_logon_id: String,  // 作成元のログオンID（4688用、0x3e7 形式）
+    pub target_user: String,       // ログオン対象のユーザー名（4624 / 4634 / 4647用）
+    pub target_domain: String,     // ログオン対象のドメイン名（4624 / 4634 / 4647用）
+    pub target_logon_id: String,   // ログオンID（4624 / 4634 / 4647用、0x3e7 形式）
+    pub ip_address: String,        // 接続元のIPアドレス（4624用）
+    pub workstation: String,       // 接続元のワークステーション名（4624用）
+    pub logon_process: String,     // ログオンプロセス名（4624用）
+    pub auth_package: String,      // 認証パッケージ名（4624用）
+    pub status: String,            // 失敗の理由の状態コード（4625 / 4771 / 4776用、0xc000006d 形式）
+    pub sub_status: String,        // 失敗の理由の詳細な状態コード（4625用、0xc000006a 形式）
+    pub data: EventData,       // EventData / UserData の全項目
+}
+
+/// ログ上の1イベントの位置（対象外のイベントも含め、EventRecordID の連続性の確認に使用）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecordMark {
+    pub record_id: u64,            // EventRecordID
+    pub event_id: u32,             // イベントID
+    pub timestamp: DateTime<Utc>,  // 発生日時
+}
+
+/// 1つのログから取り出したイベント（対象イベントのレコードと、全イベントの位置をログ上の順に保持）
+#[derive(Debug, Clone, Default)]
+pub struct ParsedLog {
+    pub events: Vec<EventRecord>,  // 対象イベントIDのイベント
+    pub records: Vec<RecordMark>,  // 全イベントの位置（ログ上の順）
+}
+
+/// EventData / UserData の項目（名前→値、ログ上の出現順を保持）
+/// 同じ名前が複数回現れた場合は2つ目以降を "名前#2", "名前#3", ... として保持する
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventData {
+    entries: Vec<(String, String)>, // (名前, 値) の並び
+}
+
+impl EventData {
+    /// 項目を末尾に追加する（重複する名前は連番を付ける）
+    pub fn insert(&mut self, name: &str, value: &str) {
+        let mut key = name.to_string(); // 格納する名前
+        let mut n = 1;
+        while self.get(&key).is_some() {
+            n += 1;
+            key = format!("{}#{}", name, n);
+        }
+        self.entries.push((key, value.to_string()));
+    }
+
+    /// 名前で値を取得する
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.entries.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// 全項目を出現順に返す
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|(k, v)| (k.as_str(), v.as_str()))
+    }
+
+    /// 項目がないか
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// 全項目をJSONオブジェクト文字列にする（CSVの1列に格納する場合に使用）
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_default()
+    }
+}
+
+impl Serialize for EventData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.entries.len()))?; // 出現順のままオブジェクトとして出力
+        for (k, v) in &self.entries {
+            map.serialize_entry(k, v)?;
+        }
+        map.end()
+    }
+}
+
+/// イベントログのチャネル（ログ名）
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Channel {
+    System,        // System
+    Application,   // Application
+    Security,      // Security
+    TaskScheduler, // Microsoft-Windows-TaskScheduler/Operational
+    Other(String), // その他（名前をそのまま保持）
+}
+
+impl Channel {
+    /// ログ名からチャネルを決定する（大文字小文字は区別しない）
+    pub fn from_name(name: &str) -> Channel {
+        [Channel::System, Channel::Application, Channel::Security, Channel::TaskScheduler]
+            .into_iter()
+            .find(|c| c.name().eq_ignore_ascii_case(name))
+            .unwrap_or_else(|| Channel::Other(name.to_string()))
+    }
+
+    /// ログ名
+    pub fn name(&self) -> &str {
+        match self {
+            Channel::System => "System",
+            Channel::Application => "Application",
+            Channel::Security => "Security",
+            Channel::TaskScheduler => "Microsoft-Windows-TaskScheduler/Operational",
+            Channel::Other(name) => name,
+        }
+    }
+
+    /// テキスト出力用の短縮名
+    pub fn short_name(&self) -> &str {
+        match self {
+            Channel::TaskScheduler => "TaskSchd",
+            _ => self.name(),
+        }
+    }
+}
+
+impl Serialize for Channel {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name()) // ログ名の文字列として出力
+    }
+}
+
+/// ログオンタイプ（4624 の LogonType）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogonType {
+    System,                  // 0: システム
+    Interactive,             // 2: 対話型（コンソール）
+    Network,                 // 3: ネットワーク
+    Batch,                   // 4: バッチ
+    Service,                 // 5: サービス
+    Unlock,                  // 7: ロック解除
+    NetworkCleartext,        // 8: ネットワーク（平文）
+    NewCredentials,          // 9: 新しい資格情報（RunAs /netonly）
+    RemoteInteractive,       // 10: リモート対話型（RDP）
+    CachedInteractive,       // 11: キャッシュされた対話型
+    CachedRemoteInteractive, // 12: キャッシュされたリモート対話型
+    CachedUnlock,            // 13: キャッシュされたロック解除
+    Other(u32),              // その他の値
+}
+
+impl LogonType {
+    /// 数値からログオンタイプを決定する
+    pub fn from_code(code: u32) -> LogonType {
+        match code {
+            0 => LogonType::System,
+            2 => LogonType::Interactive,
+            3 => LogonType::Network,
+            4 => LogonType::Batch,
+            5 => LogonType::Service,
+            7 => LogonType::Unlock,
+            8 => LogonType::NetworkCleartext,
+            9 => LogonType::NewCredentials,
+            10 => LogonType::RemoteInteractive,
+            11 => LogonType::CachedInteractive,
+            12 => LogonType::CachedRemoteInteractive,
+            13 => LogonType::CachedUnlock,
+            other => LogonType::Other(other),
+        }
+    }
+
+    /// ログに記録された数値
+    pub fn code(&self) -> u32 {
+        match self {
+            LogonType::System => 0,
+            LogonType::Interactive => 2,
+            LogonType::Network => 3,
+            LogonType::Batch => 4,
+            LogonType::Service => 5,
+            LogonType::Unlock => 7,
+            LogonType::NetworkCleartext => 8,
+            LogonType::NewCredentials => 9,
+            LogonType::RemoteInteractive => 10,
+            LogonType::CachedInteractive => 11,
+            LogonType::CachedRemoteInteractive => 12,
+            LogonType::CachedUnlock => 13,
+            LogonType::Other(code) => *code,
+        }
+    }
+
+    /// 名前（Windowsの表記、その他の値はNone）
+    pub fn name(&self) -> Option<&'static str> {
+        match self {
+            LogonType::System => Some("System"),
+            LogonType::Interactive => Some("Interactive"),
+            LogonType::Network => Some("Network"),
+            LogonType::Batch => Some("Batch"),
+            LogonType::Service => Some("Service"),
+            LogonType::Unlock => Some("Unlock"),
+            LogonType::NetworkCleartext => Some("NetworkCleartext"),
+            LogonType::NewCredentials => Some("NewCredentials"),
+            LogonType::RemoteInteractive => Some("RemoteInteractive"),
+            LogonType::CachedInteractive => Some("CachedInteractive"),
+            LogonType::CachedRemoteInteractive => Some("CachedRemoteInteractive"),
+            LogonType::CachedUnlock => Some("CachedUnlock"),
+            LogonType::Other(_) => None,
+        }
+    }
+
+    /// 表示用の文字列（例: "10 (RemoteInteractive)"、名前のない値は数値のみ）
+    pub fn describe(&self) -> String {
+        match self.name() {
+            Some(name) => format!("{} ({})", self.code(), name),
+            None => self.code().to_string(),
+        }
+    }
+}
+
+impl Serialize for LogonType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.code()) // ログに記録された数値として出力
+    }
+}
+
+/// イベント情報
+/// 並び順は発生日時、同時刻の場合は EventRecordID（cmp_chronological）
+#[derive(Debug, Clone, Serialize)]
 pub struct EventRecord {
-    pub datetime_utc: String,      // UTC日時文字列
-    pub log_name: String,          // ログ名
-    pub user: String,              // ユーザー名
-    pub event_id: String,          // イベントID
-    pub action: String,            // アクション種別
-    pub description: String,       // イベント説明
-    pub proc_info: String,         // プロセス名やコマンドライン等
-    pub logon_type: String,        // ログオンタイプ（4624用）
-    pub command_line: String,      // コマンドライン（4688用）
+    #[serde(rename = "datetime_utc", serialize_with = "serialize_utc")]
+    pub timestamp: DateTime<Utc>,        // 発生日時（UTC、100ナノ秒精度）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub boot: Option<BootMark>,          // 起動番号と起動からの経過時間（起動ごとの区間を求めて付与）
+    pub record_id: u64,                  // EventRecordID（ログ内の通し番号）
+    pub event_id: u32,                   // イベントID
+    #[serde(rename = "log_name")]
+    pub channel: Channel,                // ログ名
+    pub provider: String,                // プロバイダ名
+    pub computer: String,                // コンピュータ名
+    pub level: u8,                       // レベル（0: 情報/監査, 1: 重大, 2: エラー, 3: 警告, 4: 情報）
+    pub process_id: Option<u32>,         // 記録したプロセスのID
+    pub thread_id: Option<u32>,          // 記録したスレッドのID
+    pub user: String,                    // ユーザー名
+    pub action: String,                  // アクション種別
+    pub description: String,             // イベント説明
+    pub category: EventCategory,         // 色分けカテゴリ（イベントカタログで付与）
+    pub proc_info: String,               // プロセス名やコマンドライン等
+    pub logon_type: Option<LogonType>,   // ログオンタイプ（4624用）
+    pub command_line: String,            // コマンドライン（4688用）
+    pub new_process_id: Option<u32>,     // 作成されたプロセスのID（4688用）
+    pub parent_process_id: Option<u32>,  // 作成元（親）プロセスのID（4688用）
+    pub exit_process_id: Option<u32>,    // 終了したプロセスのID（4689用）
+    pub exit_status: Option<u32>,        // 終了コード（4689用）
+    #[serde(serialize_with = "serialize_opt_logon_id")]
+    pub subject_logon_id: Option<u64>,   // 作成元のログオンID（4688用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub logon: Option<LogonDetails>,     // ログオン・ログオフの詳細（4624 / 4634 / 4647用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure: Option<LogonFailure>,   // ログオン失敗の詳細（4625 / 4771 / 4776用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub svchost: Option<SvchostCommand>, // svchost.exe のサービスグループ・-p・サービス名（コマンドラインから解析）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lifetime: Option<ProcessLifetime>, // プロセスの実行期間（4688 / 4689、作成と終了を組み合わせて付与）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<LogonSession>,   // ログオンセッション（4624 / 4634 / 4647 / 4688、ログオンIDで組み合わせて付与）
+    pub data: EventData,                 // EventData / UserData の全項目
+    #[serde(skip_serializing_if = "EventData::is_empty")]
+    pub decoded: EventData,              // data のうちコードで記録された項目の表記（LogonType・%%nnnn・MandatoryLabel・Status）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub excluded_by: Option<String>,     // 除外したルール名（--show-excluded 時のみ）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub findings: Vec<Finding>,          // 検出事項（なりすましの疑い等）
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub invalid_fields: Vec<String>,     // 解釈できず値なしとした項目（"NewProcessId: 0xzz" 形式）
+}
+
+/// ログオン・ログオフの詳細（記録されていない項目は空文字列）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogonDetails {
+    pub target_user: String,    // ユーザー名（TargetUserName）
+    pub target_domain: String,  // ドメイン名（TargetDomainName）
+    #[serde(serialize_with = "serialize_logon_id")]
+    pub target_logon_id: u64,   // ログオンID（TargetLogonId）
+    pub ip_address: String,     // 接続元のIPアドレス（IpAddress）
+    pub workstation: String,    // 接続元のワークステーション名（WorkstationName）
+    pub logon_process: String,  // ログオンプロセス名（LogonProcessName）
+    pub auth_package: String,   // 認証パッケージ名（AuthenticationPackageName）
+}
+
+impl LogonDetails {
+    /// 「ドメイン\ユーザー」形式の名前（ドメインがなければユーザー名のみ）
+    pub fn account(&self) -> String {
+        if self.target_domain.is_empty() {
+            self.target_user.clone()
+        } else {
+            format!("{}\\{}", self.target_domain, self.target_user)
+        }
+    }
+
+    /// 接続元の表示（IPアドレス・ワークステーション名・ログオンプロセス / 認証パッケージ、記録されている項目のみ）
+    pub fn source(&self) -> String {
+        let auth = [self.logon_process.as_str(), self.auth_package.as_str()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" / ");
+        [self.ip_address.as_str(), self.workstation.as_str(), auth.as_str()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// ログオン失敗の詳細（記録されていない項目は空文字列）
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LogonFailure {
+    pub target_user: String,    // ユーザー名（TargetUserName）
+    pub target_domain: String,  // ドメイン名（TargetDomainName、4625のみ）
+    pub ip_address: String,     // 接続元のIPアドレス（IpAddress、::ffff: は除く）
+    pub workstation: String,    // 接続元のワークステーション名（WorkstationName / Workstation）
+    pub auth_package: String,   // 認証パッケージ名（AuthenticationPackageName / PackageName）
+    #[serde(serialize_with = "serialize_status")]
+    pub status: u32,            // 失敗の理由（Status、4771 は Kerberos のエラーコード）
+    #[serde(serialize_with = "serialize_opt_status")]
+    pub sub_status: Option<u32>, // 失敗の理由の詳細（SubStatus、4625のみ）
+}
+
+impl LogonFailure {
+    /// 「ドメイン\ユーザー」形式の名前（ドメインがなければユーザー名のみ）
+    pub fn account(&self) -> String {
+        if self.target_domain.is_empty() {
+            self.target_user.clone()
+        } else {
+            format!("{}\\{}", self.target_domain, self.target_user)
+        }
+    }
+
+    /// 接続元の表示（IPアドレス・ワークステーション名・認証パッケージ、記録されている項目のみ）
+    pub fn source(&self) -> String {
+        [self.ip_address.as_str(), self.workstation.as_str(), self.auth_package.as_str()]
+            .into_iter()
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// 検出事項の種別
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FindingKind {
+    MasqueradePath, // システムバイナリ名が想定外の場所から実行された
+    MasqueradeName, // システムバイナリに酷似した名前
+    BruteForceAccount, // 同じアカウントへのログオン失敗が閾値以上（ブルートフォース）
+    BruteForceSource,  // 同じ接続元から複数アカウントへのログオン失敗が閾値以上（パスワードスプレー）
+    BruteForceSuccess, // ブルートフォース・パスワードスプレーの後のログオン成功
+    LogCleared,         // ログの消去（1102 / 104）
+    LogServiceStopped,  // シャットダウンを伴わないイベントログサービスの停止（1100）
+    AuditPolicyChanged, // 監査ポリシーの変更（4719）
+    SystemTimeChanged,  // システム時刻の変更（4616）
+    RecordIdGap,        // EventRecordID の欠落
+    RecordIdReset,      // EventRecordID の巻き戻り
+    SystemRootConflict, // システムルートを確定できず、既定値と異なるシステムルートを示すプロセスがある
+}
+
+/// イベントに付与する検出事項（出力時に強調表示）
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub kind: FindingKind, // 種別
+    pub message: String,   // 説明（表示言語）
+}
+
+impl EventRecord {
+    /// 発生日時順（同時刻はEventRecordID順）で比較する
+    pub fn cmp_chronological(&self, other: &EventRecord) -> std::cmp::Ordering {
+        self.timestamp.cmp(&other.timestamp).then(self.record_id.cmp(&other.record_id))
+    }
+
+    /// 発生日時のRFC 3339文字列（UTC、ログと同じ7桁の小数秒）
+    pub fn datetime_utc(&self) -> String {
+        format_utc(&self.timestamp)
+    }
+
+    /// 対象プロセスのパス（4688は新しいプロセス、それ以外はイベントのプロセス名）
+    pub fn image(&self) -> &str {
+        self.data.get("NewProcessName").filter(|s| !s.is_empty()).unwrap_or(&self.proc_info)
+    }
+
+    /// 親プロセスのパス（4688のみ、ない場合は空文字列）
+    pub fn parent_image(&self) -> &str {
+        self.data.get("ParentProcessName").unwrap_or("")
+    }
+
+    /// ログオン・ログオン失敗の接続元の表示（それ以外のイベントはNone）
+    pub fn logon_source(&self) -> Option<String> {
+        self.logon.as_ref().map(|l| l.source()).or_else(|| self.failure.as_ref().map(|f| f.source()))
+    }
+}
+
+impl TryFrom<RawEventRecord> for EventRecord {
+    type Error = String;
+
+    /// パーサ出力の文字列項目を型付きの値に変換する（空の数値項目は0またはNone）
+    /// 日時・イベントID以外の数値項目が解釈できない場合はイベントを残し、その項目を値なし（0またはNone）として invalid_fields に記録する
+    fn try_from(raw: RawEventRecord) -> Result<Self, Self::Error> {
+        let timestamp = DateTime::parse_from_rfc3339(&raw.datetime_utc)
+            .map_err(|e| format!("TimeCreated: {}: {}", raw.datetime_utc, e))?
+            .with_timezone(&Utc); // 発生日時
+        let event_id = raw.event_id.trim().parse::<u32>().map_err(|e| format!("EventID: {}: {}", raw.event_id, e))?; // イベントID
+        let mut invalid = Vec::new(); // 解釈できなかった項目
+        let status = lenient(parse_hex(&raw.status, "Status"), &mut invalid); // 失敗の理由
+        let target_logon_id = lenient(parse_logon_id(&raw.target_logon_id, "TargetLogonId"), &mut invalid); // ログオンID
+        Ok(EventRecord {
+            timestamp,
+            record_id: lenient(parse_optional(&raw.record_id, "EventRecordID"), &mut invalid).unwrap_or_default(),
+            event_id,
+            channel: Channel::from_name(&raw.log_name),
+            provider: raw.provider,
+            computer: raw.computer,
+            level: lenient(parse_optional(&raw.level, "Level"), &mut invalid).unwrap_or_default(),
+            process_id: lenient(parse_optional(&raw.process_id, "ProcessID"), &mut invalid),
+            thread_id: lenient(parse_optional(&raw.thread_id, "ThreadID"), &mut invalid),
+            user: raw.user,
+            action: String::new(),      // アクション（後で付与）
+            description: String::new(), // 説明（後で付与）
+            category: EventCategory::Other, // カテゴリ（後で付与）
+            proc_info: raw.proc_info,
+            logon_type: lenient(parse_optional(&raw.logon_type, "LogonType"), &mut invalid).map(LogonType::from_code),
+            svchost: parse_svchost(&raw.command_line),
+            command_line: raw.command_line,
+            new_process_id: lenient(parse_hex(&raw.new_process_id, "NewProcessId"), &mut invalid),
+            parent_process_id: lenient(parse_hex(&raw.parent_process_id, "ProcessId"), &mut invalid),
+            exit_process_id: lenient(parse_hex(&raw.exit_process_id, "ProcessId"), &mut invalid),
+            exit_status: lenient(parse_hex(&raw.exit_status, "Status"), &mut invalid),
+            subject_logon_id: lenient(parse_logon_id(&raw.subject_logon_id, "SubjectLogonId"), &mut invalid),
+            failure: match status {
+                // 4776 は成功（0x0）も記録されるため、0以外を失敗とする（ログオンの詳細より先に評価）
+                Some(status) if status != 0 => Some(LogonFailure {
+                    target_user: raw.target_user.clone(),
+                    target_domain: raw.target_domain.clone(),
+                    ip_address: raw.ip_address.clone(),
+                    workstation: raw.workstation.clone(),
+                    auth_package: raw.auth_package.clone(),
+                    status,
+                    sub_status: lenient(parse_hex(&raw.sub_status, "SubStatus"), &mut invalid),
+                }),
+                _ => None, // ログオン失敗以外のイベント
+            },
+            logon: match target_logon_id {
+                Some(target_logon_id) => Some(LogonDetails {
+                    target_user: raw.target_user,
+                    target_domain: raw.target_domain,
+                    target_logon_id,
+                    ip_address: raw.ip_address,
+                    workstation: raw.workstation,
+                    logon_process: raw.logon_process,
+                    auth_package: raw.auth_package,
+                }),
+                None => None, // ログオン・ログオフ以外のイベント
+            },
+            boot: None,     // 起動番号（後で付与）
+            lifetime: None, // 実行期間（後で付与）
+            session: None,  // ログオンセッション（後で付与）
+            decoded: decode_data(event_id, &raw.data),
+            data: raw.data,
+            excluded_by: None,
+            findings: Vec::new(),
+            invalid_fields: invalid,
+        })
+    }
+}
+
+/// 項目の解釈結果を受け取る関数（解釈できない場合は項目を invalid に記録してNone）
+fn lenient<T>(parsed: Result<Option<T>, String>, invalid: &mut Vec<String>) -> Option<T> {
+    parsed.unwrap_or_else(|e| {
+        invalid.push(e);
+        None
+    })
+}
+
+/// 数値項目を解釈する関数（空ならNone）
+fn parse_optional<T: std::str::FromStr>(s: &str, field: &str) -> Result<Option<T>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    s.parse::<T>().map(Some).map_err(|_| format!("{}: {}", field, s))
+}
+
+/// プロセスID・状態コードを解釈する関数（0x1a4 形式の16進数、または10進数。空ならNone）
+fn parse_hex(s: &str, field: &str) -> Result<Option<u32>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u32::from_str_radix(hex, 16),
+        None => s.parse::<u32>(),
+    };
+    parsed.map(Some).map_err(|_| format!("{}: {}", field, s))
+}
+
+/// ログオンIDを解釈する関数（0x3e7 形式の16進数、または10進数。空ならNone）
+fn parse_logon_id(s: &str, field: &str) -> Result<Option<u64>, String> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Ok(None);
+    }
+    let parsed = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => u64::from_str_radix(hex, 16),
+        None => s.parse::<u64>(),
+    };
+    parsed.map(Some).map_err(|_| format!("{}: {}", field, s))
+}
+
+/// ログオンIDの表示（ログと同じ 0x 形式）
+pub fn format_logon_id(id: u64) -> String {
+    format!("0x{:x}", id)
+}
+
+/// ログオンIDをログと同じ 0x 形式の文字列としてシリアライズする関数
+pub fn serialize_logon_id<S: Serializer>(id: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_logon_id(*id))
+}
+
+/// ログオンID（Noneならnull）を 0x 形式の文字列としてシリアライズする関数
+fn serialize_opt_logon_id<S: Serializer>(id: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+    match id {
+        Some(id) => serialize_logon_id(id, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// 状態コードをログと同じ 0x 形式の文字列としてシリアライズする関数
+fn serialize_status<S: Serializer>(status: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("0x{:x}", status))
+}
+
+/// 状態コード（Noneならnull）を 0x 形式の文字列としてシリアライズする関数
+fn serialize_opt_status<S: Serializer>(status: &Option<u32>, serializer: S) -> Result<S::Ok, S::Error> {
+    match status {
+        Some(status) => serialize_status(status, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+/// UTC日時をログと同じ形式（7桁の小数秒＋Z）の文字列にする関数
+pub fn format_utc(dt: &DateTime<Utc>) -> String {
+    format!("{}.{:07}Z", dt.format("%Y-%m-%dT%H:%M:%S"), dt.timestamp_subsec_nanos() / 100)
+}
+
+/// 発生日時をシリアライズする関数
+pub fn serialize_utc<S: Serializer>(dt: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_utc(dt))
+}
+
+/// 日時（Noneならnull）をUTCのRFC 3339文字列としてシリアライズする関数
+pub fn serialize_opt_utc<S: Serializer>(dt: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match dt {
+        Some(dt) => serialize_utc(dt, serializer),
+        None => serializer.serialize_none(),
+    }
 }
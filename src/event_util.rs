@@ -1,12 +1,152 @@
 // --- 必要なクレートの説明 ---
 // chrono: 日時操作・タイムゾーン変換用
+// chrono_tz: IANAタイムゾーン（Asia/Tokyo, America/New_York など、夏時間対応）
 
 // 日時変換や文字列整形などの共通ユーティリティ
 
-/// UTCのISO8601文字列をJSTの"yyyy/MM/dd HH:mm:ss"形式に変換する関数
-pub fn utc_str_to_jst(utc_str: &str, jst: chrono::FixedOffset) -> String {
-    // 文字列をRFC3339形式でパースし、JSTタイムゾーンに変換
-    chrono::DateTime::parse_from_rfc3339(utc_str)
-        .map(|dt| dt.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S").to_string()) // JST形式に整形
-        .unwrap_or_else(|_| utc_str.to_string()) // パース失敗時は元の文字列を返す
+use chrono::{DateTime, FixedOffset, Offset, TimeZone, Utc}; // 日時・タイムゾーン
+
+/// 表示タイムゾーン（--tz で指定）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisplayTz {
+    Utc,                  // UTC
+    Local,                // 実行環境のローカルタイムゾーン
+    Named(chrono_tz::Tz), // IANAタイムゾーン名（夏時間対応）
+    Fixed(FixedOffset),   // 固定オフセット（+09:00 など）
+}
+
+impl Default for DisplayTz {
+    /// 既定は日本標準時（従来の出力と同じ）
+    fn default() -> Self {
+        DisplayTz::Named(chrono_tz::Asia::Tokyo)
+    }
+}
+
+impl DisplayTz {
+    /// --tz の値から表示タイムゾーンを決定する（UTC / local / IANA名 / ±HH:MM）
+    pub fn parse(s: &str) -> Option<DisplayTz> {
+        match s.to_ascii_lowercase().as_str() {
+            "utc" | "z" => return Some(DisplayTz::Utc),
+            "local" => return Some(DisplayTz::Local),
+            _ => {}
+        }
+        if s.starts_with(['+', '-']) {
+            // 固定オフセット（+09:00 形式）
+            return DateTime::parse_from_str(&format!("2000-01-01T00:00:00{}", s), "%Y-%m-%dT%H:%M:%S%:z")
+                .ok()
+                .map(|dt| DisplayTz::Fixed(*dt.offset()));
+        }
+        s.parse::<chrono_tz::Tz>()
+            .ok()
+            .or_else(|| chrono_tz::TZ_VARIANTS.iter().copied().find(|tz| tz.name().eq_ignore_ascii_case(s))) // 大文字小文字を区別しない
+            .map(DisplayTz::Named)
+    }
+
+    /// タイムゾーン名（レポートに記録する名前）
+    pub fn name(&self) -> String {
+        match self {
+            DisplayTz::Utc => "UTC".to_string(),
+            DisplayTz::Local => "local".to_string(),
+            DisplayTz::Named(tz) => tz.name().to_string(),
+            DisplayTz::Fixed(offset) => offset.to_string(),
+        }
+    }
+
+    /// 指定時刻におけるUTCオフセット（夏時間を考慮）
+    pub fn offset_at(&self, utc: DateTime<Utc>) -> FixedOffset {
+        match self {
+            DisplayTz::Utc => Utc.fix(),
+            DisplayTz::Local => *utc.with_timezone(&chrono::Local).offset(),
+            DisplayTz::Named(tz) => tz.offset_from_utc_datetime(&utc.naive_utc()).fix(),
+            DisplayTz::Fixed(offset) => *offset,
+        }
+    }
+
+    /// UTC時刻を表示タイムゾーンの時刻に変換する
+    pub fn convert(&self, utc: DateTime<Utc>) -> DateTime<FixedOffset> {
+        utc.with_timezone(&self.offset_at(utc))
+    }
+
+    /// 表示タイムゾーンでの日時（タイムゾーン指定なし）をUTCに変換する（夏時間の重複時刻は早い方）
+    pub fn local_to_utc(&self, naive: chrono::NaiveDateTime) -> Option<DateTime<Utc>> {
+        match self {
+            DisplayTz::Utc => Some(naive.and_utc()),
+            DisplayTz::Local => chrono::Local.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+            DisplayTz::Named(tz) => tz.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+            DisplayTz::Fixed(offset) => offset.from_local_datetime(&naive).earliest().map(|dt| dt.with_timezone(&Utc)),
+        }
+    }
+
+    /// 案内表示用の説明（例: "Asia/Tokyo (UTC+09:00)"、オフセットは指定時刻のもの）
+    pub fn describe(&self, at: DateTime<Utc>) -> String {
+        match self {
+            DisplayTz::Utc => "UTC".to_string(),
+            DisplayTz::Fixed(offset) => format!("UTC{}", offset),
+            _ => format!("{} (UTC{})", self.name(), self.offset_at(at)),
+        }
+    }
+}
+
+/// UTC時刻を表示タイムゾーンの"yyyy/MM/dd HH:mm:ss"形式に変換する関数
+pub fn format_display(utc: DateTime<Utc>, tz: DisplayTz) -> String {
+    tz.convert(utc).format("%Y/%m/%d %H:%M:%S").to_string()
+}
+
+/// UTC時刻を指定タイムゾーンのRFC3339文字列（オフセット付き）に変換する関数
+pub fn to_local_rfc3339(utc: DateTime<Utc>, tz: DisplayTz) -> String {
+    tz.convert(utc).to_rfc3339_opts(chrono::SecondsFormat::Millis, false) // オフセット付きで整形
+}
+
+/// システムルートの既定値（正規化済み、ログから求められない場合に使う）
+pub const DEFAULT_SYSTEM_ROOT: &str = "c:\\windows";
+/// システムルート配下のパスをシステムルートに依存しない表記にするための接頭辞（除外ルールの %SystemRoot%\... と照合）
+pub const SYSTEM_ROOT_TOKEN: &str = "%systemroot%";
+
+/// プロセスのパスを照合用に正規化する関数（小文字化・区切り文字の統一・環境変数/NTパス表記の展開・. と .. の解決）
+/// 例: \SystemRoot\System32\svchost.exe → c:\windows\system32\svchost.exe
+pub fn normalize_image_path(path: &str) -> String {
+    normalize_image_path_in(path, DEFAULT_SYSTEM_ROOT)
+}
+
+/// システムルート（正規化済み、例: d:\windows）を指定してプロセスのパスを正規化する関数
+pub fn normalize_image_path_in(path: &str, system_root: &str) -> String {
+    let mut p = path.trim().trim_matches('"').replace('/', "\\").to_ascii_lowercase();
+    // NTパス表記（\??\C:\...）の接頭辞を除去
+    for prefix in ["\\??\\", "\\\\?\\", "\\\\.\\"] {
+        if let Some(rest) = p.strip_prefix(prefix) {
+            p = rest.to_string();
+        }
+    }
+    // システムルートの表記を展開
+    for root in ["\\systemroot\\", "%systemroot%\\", "%windir%\\"] {
+        if let Some(rest) = p.strip_prefix(root) {
+            p = format!("{}\\{}", system_root, rest);
+        }
+    }
+    // . と .. を解決（C:\Windows\System32\..\..\Temp\svchost.exe のような表記で照合をすり抜けないように）
+    let mut parts: Vec<&str> = Vec::new();
+    for part in p.split('\\') {
+        match part {
+            "" | "." if !parts.is_empty() => {} // 連続する区切り文字・カレントディレクトリ
+            ".." => {
+                if parts.len() > 1 {
+                    parts.pop(); // 1つ上のディレクトリ（ドライブより上には戻らない）
+                }
+            }
+            _ => parts.push(part),
+        }
+    }
+    parts.join("\\")
+}
+
+/// 正規化済みのパスがシステムルート（正規化済み）配下であれば %systemroot%\... の表記を返す関数（配下でなければNone）
+/// 例: d:\winnt\system32\svchost.exe（システムルート d:\winnt）→ %systemroot%\system32\svchost.exe
+pub fn system_relative(path: &str, system_root: &str) -> Option<String> {
+    let rest = path.strip_prefix(system_root)?.strip_prefix('\\')?;
+    Some(format!("{}\\{}", SYSTEM_ROOT_TOKEN, rest))
+}
+
+/// パスからファイル名部分を取り出す関数
+pub fn file_name_of(path: &str) -> &str {
+    path.rsplit(['\\', '/']).next().unwrap_or(path)
 }
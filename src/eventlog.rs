@@ -1,132 +1,382 @@
 // --- 必要なクレートの説明 ---
-// std::process::Command: 外部コマンド実行用（wevtutil等の呼び出しに使用）
-// std::io::Write: ファイルへの書き込み用
-// quick_xml: XMLパース用（イベントログの解析）
-// chrono: 日時操作用（JST変換や期間指定）
-// regex: コマンドラインからサービス名抽出用
-//
-// ※Cargo.tomlで quick-xml, chrono, regex を明示的に追加してください
-
-use std::io::Write;        // ファイル書き込み用
-use crate::event_types::EventRecord; // イベントレコード型
-use crate::event_query::fetch_eventlog_xml; // イベントログXML取得関数
-use crate::event_parse::parse_events; // XMLパース関数
-use crate::event_filter::should_exclude; // 除外判定関数
-use crate::event_util::utc_str_to_jst; // UTC→JST変換関数
-
-// イベントログを解析し、output.txtに出力するメイン関数
+// このファイルはイベントログ解析の本体です（取得元に依存しない: ライブ取得・XML・EVTXは crate::event_source が担当）
+// 1. 取得元（EventSource）からイベントカタログの対象イベントを取得し、カタログでアクション・説明・カテゴリを付与
+// 2. なりすまし・起動ごとの区間・ブルートフォース・ログの整合性・実行期間・ログオンセッション・プロセスツリーを付与
+// 3. 起動の選択（--boot / --boots）・出力するイベントID（--event-id）・除外プロファイルで出力対象を絞り込む
+// 4. crate::event_output の指定形式（テキスト・JSON・JSON Lines・CSV・HTML）で crate::output_sink の出力先に書き込む
+// chrono: 日時操作用（監査範囲の表示・表示タイムゾーンの基準時刻）
+
+use crate::event_types::{EventRecord, RecordMark}; // イベントレコード型・イベントの位置
+use crate::event_source::{EventSource, TimeRange}; // イベントログ取得元
+use crate::event_filter::FilterProfile; // 除外プロファイル
+use crate::masquerade::{self, SystemRoot}; // なりすまし検出・システムルート
+use crate::brute_force::{self, BruteForceOptions}; // ブルートフォース検出
+use crate::process_tree::ProcessTree; // プロセスツリー（祖先の判定）
+use crate::process_lifetime; // プロセスの実行期間
+use crate::logon_session; // ログオンセッション
+use crate::integrity; // ログの整合性
+use crate::boot_session::{self, BootSelection}; // 起動ごとの区間・起動の選択
+use crate::event_util::{format_display, normalize_image_path_in, DisplayTz}; // 日時変換関数・パスの正規化・表示タイムゾーン
+use crate::event_output::{write_metadata, write_report, OutputOptions, ReportHeader}; // 出力形式
+use crate::output_sink::OutputSink; // 出力先
+use crate::event_catalog::EventCatalog; // イベントカタログ
+use crate::messages::{lang, t, tr, Msg}; // メッセージカタログ
+use std::io::Write; // 書き込み用
+
+/// 解析条件（監査範囲・表示タイムゾーン・除外設定・環境チェック結果）
+#[derive(Debug, Clone)]
+pub struct AnalysisSettings {
+    pub time_range: Option<TimeRange>, // 監査開始・終了時刻（Noneなら全期間）
+    pub offline: bool,                 // オフライン解析（保存済みファイル）か
+    pub tz: DisplayTz,                 // 表示タイムゾーン
+    pub cmdline_audit_enabled: bool,   // コマンドライン監査有効フラグ
+    pub filter: FilterProfile,         // 除外プロファイル
+    pub show_excluded: bool,           // 除外されたイベントも除外理由付きで出力するか
+    pub environment: Vec<String>,      // 環境チェックの通知文
+    pub event_ids: Vec<u32>,           // 出力対象のイベントID（空なら全対象）
+    pub catalog: EventCatalog,         // イベントカタログ（対象イベント・表示内容）
+    pub brute_force: BruteForceOptions, // ブルートフォース検出の条件（閾値・ウィンドウ）
+    pub boot_selection: Option<BootSelection>, // 出力する起動（Noneなら全て）
+    pub system_root: Option<SystemRoot>, // システムルート（ライブ取得は実行中のシステムの %SystemRoot%、Noneならログから求める）
+}
+
+// イベントログを解析し、指定の出力先（既定は output.txt 等）に出力するメイン関数
+// time_rangeがNoneの場合は取得元の全イベントを対象とする
 pub fn analyze_and_output_events(
-    start_time: chrono::DateTime<chrono::Utc>, // 監査開始時刻
-    end_time: chrono::DateTime<chrono::Utc>,   // 監査終了時刻
-    jst: chrono::FixedOffset,                  // JSTタイムゾーン
-    cmdline_audit_enabled: bool,               // コマンドライン監査有効フラグ
-    svchost_level: u8                          // svchost.exe出力レベル
-) {
-    // svchost出力レベルの説明文を決定
-    let svchost_level_msg = match svchost_level {
-        0 => "システムサービス関連(svchost.exe)の出力レベルは「全て出力（-s0）」です", // -s0: 全て出力
-        1 => "システムサービス関連(svchost.exe)の出力レベルは「除外リスト方式（-s1）」です", // -s1: 除外リスト方式
-        2 => "システムサービス関連(svchost.exe)の出力レベルは「全て除外（-s2）」です", // -s2: 全て除外
-        _ => "システムサービス関連(svchost.exe)の出力レベルは「全て出力（デフォルト）」です", // デフォルト
-    };
-    // 監査対象日時の案内文を作成
-    let date_range_msg = format!(
-        ">>> イベント監査対象日時: {} ～ {}\n",
-        start_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S"), // JST変換
-        end_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S")    // JST変換
-    );
-    // svchost出力レベル案内文を作成
-    let svchost_level_msg_line = format!(">>> {}\n", svchost_level_msg);
-    // イベントIDごとのアクション・説明マップ
-    let event_id_action_map = [
-        ("6005", ("起動", "イベントログサービスの起動")), // サービス起動
-        ("6006", ("終了", "イベントログサービスの終了")), // サービス終了
-        ("1100", ("ログサービス停止", "イベントログサービスの停止")), // ログサービス停止
-        ("1102", ("ログ消去", "イベントログの消去")), // ログ消去
-        ("12",   ("起動", "システム起動")), // システム起動
-        ("1074", ("シャットダウン/再起動", "ユーザーまたはプロセスによるシャットダウン/再起動")), // シャットダウン/再起動
-        ("6009", ("起動", "システム起動(バージョン情報)")), // バージョン情報
-        ("1000", ("起動", "アプリケーション起動")), // アプリ起動
-        ("1001", ("終了", "アプリケーション終了")), // アプリ終了
-        ("200",  ("起動", "タスクスケジューラ起動")), // タスクスケジューラ起動
-        ("201",  ("終了", "タスクスケジューラ終了")), // タスクスケジューラ終了
-        ("41",   ("予期せぬシャットダウン", "予期せぬシャットダウン")), // 予期せぬシャットダウン
-        ("13",   ("予期せぬシャットダウン(電源)", "予期せぬシャットダウン(電源)")), // 電源系
-        ("6008", ("予期しないシャットダウン", "予期しないシャットダウン")), // 予期しないシャットダウン
-        ("4688", ("アプリ起動", "新しいプロセスの作成 (プロセス起動)")), // プロセス作成
-        ("4624", ("ログオン", "アカウントの正常なログオン")), // ログオン
-        ("4647", ("ログオフ", "ユーザーのログオフ")), // ログオフ
-        // ...他にも必要に応じて追加...
-    ];
-    // ログ種別ごとの対象イベントIDリスト
-    let event_id_map = [
-        ("System", vec!["6005", "6006", "1100", "1102", "12", "1074", "6009", "13", "41", "6008"]), // システム系
-        ("Application", vec!["1000", "1001"]), // アプリ系
-        ("Microsoft-Windows-TaskScheduler/Operational", vec!["200", "201"]), // タスクスケジューラ
-        ("Security", vec!["4688", "4624", "4647"]), // セキュリティ
-        // ...他にも必要に応じて追加...
-    ];
-    // output.txtの先頭に案内2行を書き込む（毎回上書き）
-    let file_path = "output.txt"; // 出力ファイル名
-    let mut file = std::fs::OpenOptions::new()
-        .write(true) // 書き込みモード
-        .truncate(true) // 既存内容を消去
-        .open(file_path) // ファイルを開く
-        .expect("ファイルオープン失敗"); // エラー時
-    file.write_all(date_range_msg.as_bytes()).unwrap(); // 日時案内を書き込む
-    file.write_all(svchost_level_msg_line.as_bytes()).unwrap(); // svchost案内を書き込む
-    // ここでfileは閉じる
-    // イベントログ抽出範囲の文字列（UTC）を作成
-    let start_str = start_time.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string(); // 開始時刻文字列
-    let end_str = end_time.format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string();   // 終了時刻文字列
-    // appendでイベント出力用ファイルを再オープン
-    let mut file = std::fs::OpenOptions::new()
-        .append(true) // 追記モード
-        .open("output.txt") // ファイルを開く
-        .expect("ファイルオープン失敗"); // エラー時
+    settings: &AnalysisSettings, // 解析条件
+    source: &dyn EventSource,    // イベントログの取得元
+    options: OutputOptions,      // 出力オプション
+    sink: &OutputSink            // 出力先
+) -> Result<(), String> {
+    let time_range = settings.time_range; // 監査開始・終了時刻
+    let tz = settings.tz; // 表示タイムゾーン
+    let cmdline_audit_enabled = settings.cmdline_audit_enabled; // コマンドライン監査有効フラグ
     let mut all_events: Vec<EventRecord> = Vec::new(); // 全イベント格納用ベクタ
-    for (log, ids) in &event_id_map { // 各ログ種別ごとに処理
-        let query = format!(
-            "*[System[TimeCreated[@SystemTime>='{}' and @SystemTime<='{}']]]",
-            start_str, end_str
-        ); // wevtutil用クエリ文字列
-        // wevtutilでXML取得
-        let xml_result = fetch_eventlog_xml(log, &query).unwrap_or_default(); // XML取得
-        // XMLをパースしてイベント抽出
-        let mut events = parse_events(&xml_result, &ids); // イベント抽出
-        all_events.append(&mut events); // 全イベントに追加
-    }
-    // 日時順にソート
-    all_events.sort_by(|a, b| a.datetime_utc.cmp(&b.datetime_utc)); // 日時で昇順ソート
-    // 出力処理
-    for mut event in all_events { // 各イベントごとに
-        // イベントIDに応じてアクション・説明を付与
-        if let Some((_, (action, desc))) = event_id_action_map.iter().find(|(id, _)| *id == event.event_id.as_str()) {
-            event.action = action.to_string(); // アクション設定
-            event.description = desc.to_string(); // 説明設定
+    let mut records: Vec<(String, Vec<RecordMark>)> = Vec::new(); // ログごとの全イベントの位置（EventRecordID の確認用）
+    let channels = source.channels(); // 取得元のログ名一覧
+    let catalog_channels = settings.catalog.channels(); // カタログに定義されたログ種別ごとの対象イベントID
+    for channel in &channels {
+        if !catalog_channels.iter().any(|(log, _)| log.eq_ignore_ascii_case(channel)) {
+            eprintln!("{}", tr(Msg::SkipUnsupportedLog, &[channel])); // 未対応ログ
         }
-        if should_exclude(&event, svchost_level, cmdline_audit_enabled) {
-            continue; // 除外対象は出力しない
+    }
+    for (log, ids) in &catalog_channels { // 各ログ種別ごとに処理
+        if !channels.iter().any(|c| c.eq_ignore_ascii_case(log)) {
+            continue; // 取得元にないログは対象外
+        }
+        // --event-id 指定時もカタログの全IDを取得する（起動の区間・実行期間・ログオンセッション・整合性の判定に使うため、出力時に限定）
+        match source.fetch_events(log, ids, time_range) { // 取得元からイベント抽出
+            Ok(mut parsed) => {
+                all_events.append(&mut parsed.events); // 全イベントに追加
+                records.push((log.clone(), parsed.records));
+            }
+            Err(e) => eprintln!("{}", tr(Msg::FetchFailed, &[log, &e])), // 取得失敗は警告して続行
+        }
+    }
+    // 日時順にソート（同時刻はEventRecordID順）
+    all_events.sort_by(EventRecord::cmp_chronological); // 日時で昇順ソート
+    // 監査対象日時の案内文を作成（オフライン解析の場合は抽出イベントの最初と最後）
+    let audit_range = if !settings.offline {
+        match time_range {
+            Some((start_time, end_time)) => tr(
+                Msg::RangeFormat,
+                &[&format_display(start_time, tz), &format_display(end_time, tz)], // 表示タイムゾーンに変換
+            ),
+            None => t(Msg::RangeAllTime).to_string(),
         }
-        let log_disp = match event.log_name.as_str() {
-            "Microsoft-Windows-TaskScheduler/Operational" => "TaskSchd", // ログ名短縮
-            _ => event.log_name.as_str(), // それ以外はそのまま
+    } else {
+        let scope = if time_range.is_some() { t(Msg::OfflineScopeRange) } else { t(Msg::OfflineScopeAll) }; // 対象範囲の説明
+        match (all_events.first(), all_events.last()) {
+            (Some(first), Some(last)) => tr(
+                Msg::OfflineRange,
+                &[&format_display(first.timestamp, tz), &format_display(last.timestamp, tz), &scope],
+            ),
+            _ => t(Msg::OfflineNoEvents).to_string(),
+        }
+    };
+    // タイムゾーン表示のオフセット基準時刻（夏時間のあるタイムゾーンは監査終了時点、または最後のイベントのオフセット）
+    let tz_reference = time_range
+        .map(|(_, end)| end.min(chrono::Utc::now()))
+        .or_else(|| all_events.last().map(|e| e.timestamp))
+        .unwrap_or_else(chrono::Utc::now);
+    // 監査範囲の境界（片側のみ指定で無制限の側は出力しない）
+    let bound_str = |dt: chrono::DateTime<chrono::Utc>| {
+        (dt != chrono::DateTime::<chrono::Utc>::MIN_UTC && dt != chrono::DateTime::<chrono::Utc>::MAX_UTC)
+            .then(|| dt.to_rfc3339_opts(chrono::SecondsFormat::Millis, true))
+    };
+    let mut header = ReportHeader {
+        audit_start_utc: time_range.and_then(|(s, _)| bound_str(s)), // 監査開始（UTC）
+        audit_end_utc: time_range.and_then(|(_, e)| bound_str(e)),   // 監査終了（UTC）
+        audit_range,                                             // 監査対象日時（表示用）
+        offline: settings.offline,                               // オフライン解析か
+        filter_profile: settings.filter.name.clone(),            // 除外プロファイル名
+        filter_profile_description: settings.filter.description().to_string(), // 除外プロファイルの説明
+        show_excluded: settings.show_excluded,                   // 除外されたイベントも出力するか
+        cmdline_audit_enabled,                                   // コマンドライン監査有効フラグ
+        time_zone: tz.describe(tz_reference),                    // 表示タイムゾーン
+        integrity: Vec::new(),                                   // ログの整合性（イベントの確認後に設定）
+        boots: Vec::new(),                                       // 起動の一覧（起動ごとの区間を求めた後に設定）
+        boot_selection: None,                                    // 出力する起動（--boot / --boots）
+        environment: settings.environment.clone(),               // 環境チェックの通知文
+        system_root: String::new(),                              // システムルート（イベントから求めた後に設定）
+        lang: lang(),                                            // 表示言語
+    };
+    // システムルート（なりすまし検出・除外ルールの照合で共通、C:\Windows 以外にも対応）
+    let system_root = settings.system_root.clone().unwrap_or_else(|| SystemRoot::from_events(&all_events));
+    header.system_root = system_root.describe();
+    // イベントカタログに従ってアクション・説明・カテゴリを付与し、なりすましを検出する
+    let mut catalog_events: Vec<EventRecord> = Vec::new(); // カタログに定義されたイベント
+    for mut event in all_events { // 各イベントごとに
+        let Some(entry) = settings.catalog.lookup(&event) else {
+            continue; // カタログにないプロバイダのイベントは対象外
         };
-        let log_disp = format!("{:<11}", log_disp); // ログ名整形
-        let eventid_disp = format!("{:>5}", event.event_id); // イベントID整形
-        let date_jst = utc_str_to_jst(&event.datetime_utc, jst); // JST変換
-        let proc_disp = if event.event_id == "4688" && !event.command_line.is_empty() {
-            format!("{} [CommandLine: {}]", event.proc_info, event.command_line) // コマンドライン付き
-        } else if event.event_id == "4624" && !event.logon_type.is_empty() {
-            format!("{} [LogonType={}]", event.proc_info, event.logon_type) // ログオンタイプ付き
-        } else {
-            event.proc_info.clone() // それ以外はそのまま
+        event.action = entry.action.render(&event.data); // アクション設定
+        event.description = entry.description.render(&event.data); // 説明設定
+        event.category = entry.category; // 色分けカテゴリ設定
+        event.findings = masquerade::check_event(&event, &system_root); // なりすましの疑い
+        catalog_events.push(event);
+    }
+    // 起動番号と起動からの経過時間（実行期間・ログオンセッション・プロセスツリーも同じ起動番号を使う）
+    let boots = boot_session::annotate(&mut catalog_events);
+    let selected = |boot: u32| settings.boot_selection.map_or(true, |s| s.contains(boot, boots.count()));
+    header.boots = boots.sessions().iter().filter(|s| selected(s.number)).cloned().collect();
+    header.boot_selection = settings.boot_selection.map(|s| s.describe());
+    brute_force::detect(&mut catalog_events, &settings.brute_force, tz); // ログオン失敗の集中（ブルートフォース）
+    header.integrity = integrity::check(&mut catalog_events, &records, tz); // ログの消去・欠落・監査ポリシー・時刻の変更
+    // 除外対象を取り除く（実行期間・祖先のプロセスは除外前の全イベントで判定）
+    let exits = process_lifetime::annotate(&mut catalog_events); // 終了イベント→作成イベント
+    logon_session::annotate(&mut catalog_events); // ログオンセッション
+    let tree = ProcessTree::build(&catalog_events);
+    let mut output_events: Vec<EventRecord> = Vec::new(); // 出力対象イベント
+    for (index, mut event) in catalog_events.into_iter().enumerate() {
+        if !selected(event.boot.map_or(0, |b| b.number)) {
+            continue; // --boot / --boots で選択されていない起動
+        }
+        if !settings.event_ids.is_empty() && !settings.event_ids.contains(&event.event_id) {
+            continue; // --event-id で指定されていないイベント
+        }
+        let created = exits.get(&index).copied().unwrap_or(index); // 終了イベントは作成イベントのノードで判定
+        let ancestors: Vec<String> = tree
+            .node_of_event(created)
+            .map(|node| tree.ancestors(node).map(|a| normalize_image_path_in(&a.image, &system_root.path)).collect())
+            .unwrap_or_default();
+        // なりすましの疑いがあるイベントは除外ルールに一致しても出力する
+        let excluded = if event.findings.is_empty() { settings.filter.excluded_by(&event, &ancestors, cmdline_audit_enabled, &system_root.path) } else { None };
+        if let Some(reason) = excluded {
+            if !settings.show_excluded {
+                continue; // 除外対象は出力しない
+            }
+            event.excluded_by = Some(reason); // 除外したルール名（または指定オプション）を記録して出力
+        }
+        output_events.push(event);
+    }
+    // 出力処理
+    let mut out = sink.open()?; // 出力先を開く
+    write_report(options, &header, &output_events, tz, &mut out)
+        .and_then(|_| out.flush())
+        .map_err(|e| tr(Msg::WriteFailed, &[&sink.display_name(), &e]))?; // 指定形式で書き込み
+    eprintln!("{}", tr(Msg::WroteOutput, &[&sink.display_name()])); // 完了案内
+    if options.format.has_separate_metadata() {
+        // ヘッダ情報は別ファイル（<出力ファイル名>.meta.txt、標準出力時は標準エラー出力）に出力
+        match sink.metadata_sink() {
+            Some(meta_sink) => {
+                let mut meta = meta_sink.open()?; // メタ情報ファイルを開く
+                write_metadata(&header, &mut meta)
+                    .and_then(|_| meta.flush())
+                    .map_err(|e| tr(Msg::WriteFailed, &[&meta_sink.display_name(), &e]))?; // メタ情報を書き込み
+                eprintln!("{}", tr(Msg::WroteMetadata, &[&meta_sink.display_name()])); // 完了案内
+            }
+            None => write_metadata(&header, &mut std::io::stderr()).map_err(|e| e.to_string())?, // 標準エラー出力
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_filter::FilterConfig;
+    use crate::event_output::OutputFormat;
+    use crate::event_source::MemorySource;
+    use crate::event_query::list_event_dir;
+    use serde_json::Value;
+
+    /// オフライン解析の既定の条件（全て出力・UTC）
+    fn settings() -> AnalysisSettings {
+        AnalysisSettings {
+            time_range: None,
+            offline: true,
+            tz: DisplayTz::Utc,
+            cmdline_audit_enabled: true,
+            filter: FilterConfig::load(&[] as &[&str]).unwrap().profile(Some("all")).unwrap(),
+            show_excluded: false,
+            environment: Vec::new(),
+            event_ids: Vec::new(),
+            catalog: EventCatalog::load(&[] as &[&str]).unwrap(),
+            brute_force: BruteForceOptions::default(),
+            boot_selection: None,
+            system_root: None,
+        }
+    }
+
+    /// testdata/<dir> の保存済みファイルを MemorySource 経由で解析し、JSON出力を返す
+    fn analyze(dir: &str, ext: &str, settings: &AnalysisSettings) -> Value {
+        let files = list_event_dir(&std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata").join(dir), ext).unwrap();
+        analyze_source(&MemorySource::from_files(&files), settings)
+    }
+
+    /// 取得元を解析し、JSON出力を返す
+    fn analyze_source(source: &MemorySource, settings: &AnalysisSettings) -> Value {
+        static RUN: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0); // 並列実行するテストごとの出力ファイル
+        let run = RUN.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("wbec-test-{}-{}.json", std::process::id(), run));
+        let sink = OutputSink::File(path.clone());
+        analyze_and_output_events(settings, source, OutputOptions { format: OutputFormat::Json, csv_bom: false }, &sink).unwrap();
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+        serde_json::from_str(&json).unwrap()
+    }
+
+    fn events(report: &Value) -> &Vec<Value> {
+        report["events"].as_array().unwrap()
+    }
+
+    #[test]
+    fn boots_fixture_end_to_end() {
+        let report = analyze("boots", "xml", &settings());
+        let boots = report["header"]["boots"].as_array().unwrap();
+        let states: Vec<&str> = boots.iter().map(|b| b["end_state"].as_str().unwrap()).collect();
+        assert_eq!(states, ["clean", "dirty", "running"]);
+        assert!(boots[0]["shutdown_initiator"].as_str().unwrap().contains("HOST1\\alice"));
+        assert_eq!(events(&report).len(), 19);
+    }
+
+    #[test]
+    fn event_id_limits_rows_but_not_boot_and_session_analysis() {
+        let mut settings = settings();
+        settings.event_ids = vec![4624];
+        settings.boot_selection = Some(BootSelection::Range(2, 2));
+        let report = analyze("boots", "xml", &settings);
+        let rows = events(&report);
+        assert_eq!(rows.len(), 2);
+        for row in rows {
+            assert_eq!(row["event_id"], 4624);
+            assert_eq!(row["boot"]["number"], 2);
+            assert_eq!(row["session"]["state"], "rebooted"); // 次の起動があるためログオン中ではない
+        }
+        assert_eq!(report["header"]["boots"].as_array().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn event_id_does_not_hide_integrity_findings() {
+        let mut settings = settings();
+        settings.event_ids = vec![4624];
+        let report = analyze("integrity", "xml", &settings);
+        let kinds: Vec<&str> = report["header"]["integrity"].as_array().unwrap().iter().map(|f| f["kind"].as_str().unwrap()).collect();
+        assert!(kinds.contains(&"log_cleared"), "{:?}", kinds);
+        assert!(kinds.contains(&"record_id_gap"), "{:?}", kinds);
+        assert!(events(&report).iter().all(|e| e["event_id"] == 4624));
+    }
+
+    #[test]
+    fn brute_force_fixture_end_to_end() {
+        let report = analyze("brute_force", "xml", &settings());
+        let kinds: Vec<&str> = events(&report)
+            .iter()
+            .flat_map(|e| e["findings"].as_array().into_iter().flatten())
+            .map(|f| f["kind"].as_str().unwrap())
+            .collect();
+        assert!(kinds.contains(&"brute_force_account"));
+        assert!(kinds.contains(&"brute_force_source"));
+        assert!(kinds.contains(&"brute_force_success"));
+    }
+
+    #[test]
+    fn evtx_fixture_end_to_end() {
+        let report = analyze("evtx", "evtx", &settings());
+        let ids: Vec<u64> = events(&report).iter().map(|e| e["event_id"].as_u64().unwrap()).collect();
+        assert_eq!(ids, [6005, 4688, 4624, 6006]);
+        assert_eq!(report["header"]["offline"], true);
+    }
+
+    #[test]
+    fn twelve_and_thirteen_from_other_providers_are_not_boots_or_shutdowns() {
+        let fixture = |name: &str| std::fs::read_to_string(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("testdata/boots").join(name)).unwrap();
+        let event = |provider: &str, id: u32, time: &str, record: u32| {
+            format!(
+                "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='{}'/><EventID>{}</EventID>\
+                 <Level>4</Level><TimeCreated SystemTime='{}'/><EventRecordID>{}</EventRecordID><Channel>System</Channel><Computer>HOST1</Computer></System></Event>\r\n",
+                provider, id, time, record
+            )
         };
-        let line = format!(
-            "{}\t{}\t{}\t{}\t{}\t{}\t{}\n",
-            date_jst, log_disp, event.user, eventid_disp, event.action, event.description, proc_disp
-        ); // 出力行を作成
-        file.write_all(line.as_bytes()).unwrap(); // ファイルに書き込み
+        // 起動 #1 の途中に Time-Service の 12 と、別のプロバイダの 13（いずれも起動・終了ではない）
+        let extra = [
+            event("Microsoft-Windows-Time-Service", 12, "2025-07-10T12:00:00.0000000Z", 950),
+            event("Microsoft-Windows-Kernel-Power", 13, "2025-07-10T12:00:01.0000000Z", 951),
+        ]
+        .concat();
+        let source = MemorySource::new(vec![
+            ("System".to_string(), fixture("System.xml") + &extra),
+            ("Security".to_string(), fixture("Security.xml")),
+        ]);
+        let report = analyze_source(&source, &settings());
+        let boots = report["header"]["boots"].as_array().unwrap();
+        let states: Vec<&str> = boots.iter().map(|b| b["end_state"].as_str().unwrap()).collect();
+        assert_eq!(states, ["clean", "dirty", "running"]);
+        assert_eq!(events(&report).len(), 19); // カタログにないプロバイダの 12 / 13 は対象外
+    }
+
+    /// プロセス作成イベント（4688）だけの Security ログ
+    fn process_log(processes: &[(&str, &str, &str)]) -> MemorySource {
+        let xml: String = processes
+            .iter()
+            .enumerate()
+            .map(|(i, (image, parent, parent_pid))| {
+                format!(
+                    "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Security-Auditing'/>\
+                     <EventID>4688</EventID><TimeCreated SystemTime='2025-07-10T06:00:{:02}.000Z'/><EventRecordID>{}</EventRecordID>\
+                     <Channel>Security</Channel><Computer>HOST1</Computer></System><EventData>\
+                     <Data Name='NewProcessId'>0x{:x}</Data><Data Name='NewProcessName'>{}</Data>\
+                     <Data Name='ProcessId'>{}</Data><Data Name='ParentProcessName'>{}</Data></EventData></Event>\r\n",
+                    i, i + 1, 0x100 + i, image, parent_pid, parent
+                )
+            })
+            .collect();
+        MemorySource::new(vec![("Security".to_string(), xml)])
+    }
+
+    fn images(report: &Value) -> Vec<&str> {
+        events(report).iter().map(|e| e["data"]["NewProcessName"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn default_rules_follow_a_non_default_system_root() {
+        let source = process_log(&[
+            (r"D:\WINNT\System32\smss.exe", "", "0x4"),
+            (r"D:\WINNT\System32\lsass.exe", r"D:\WINNT\System32\wininit.exe", "0x1f0"),
+            (r"D:\WINNT\System32\svchost.exe", r"D:\WINNT\System32\services.exe", "0x2a0"),
+            (r"C:\Windows\System32\svchost.exe", r"D:\WINNT\System32\services.exe", "0x2a0"), // システムルート外
+        ]);
+        let mut settings = settings();
+        settings.cmdline_audit_enabled = false;
+        let report = analyze_source(&source, &settings);
+        assert!(report["header"]["system_root"].as_str().unwrap().starts_with(r"D:\Winnt"));
+        assert_eq!(images(&report), [r"D:\WINNT\System32\smss.exe", r"C:\Windows\System32\svchost.exe"]);
+        // 実行中のシステムの %SystemRoot% が優先（D:\WINNT 配下はなりすましの疑いとして除外されない）
+        settings.system_root = SystemRoot::live(r"C:\Windows");
+        let report = analyze_source(&source, &settings);
+        assert_eq!(images(&report).len(), 4);
+        assert!(events(&report)[1]["findings"][0]["kind"] == "masquerade_path");
+    }
+
+    #[test]
+    fn literal_windows_patterns_still_match_under_the_system_root() {
+        let path = std::env::temp_dir().join(format!("wbec-filter-{}.toml", std::process::id()));
+        std::fs::write(&path, "[[profile]]\nname = \"literal\"\nextends = \"all\"\n[[profile.rule]]\nname = \"notepad\"\naction = \"exclude\"\nimage = 'C:\\Windows\\System32\\notepad.exe'\n").unwrap();
+        let mut settings = settings();
+        settings.filter = FilterConfig::load(&[&path]).unwrap().profile(Some("literal")).unwrap();
+        std::fs::remove_file(&path).ok();
+        let source = process_log(&[(r"%SystemRoot%\System32\notepad.exe", "", "0x200"), (r"C:\Temp\notepad.exe", "", "0x200")]);
+        assert_eq!(images(&analyze_source(&source, &settings)), [r"C:\Temp\notepad.exe"]);
     }
-    println!("ℹ️ output.txt に出力しました"); // 完了案内
 }
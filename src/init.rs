@@ -1,176 +1,228 @@
 // --- 必要なクレートの説明 ---
-// std::process::Command: 外部コマンド実行用（wevtutil, powershell, AuditPol等の呼び出しに使用）
-// std::fs::File: ファイル作成・書き込み用
-// std::io::Write: ファイルへの書き込み用
-// chrono: 日時操作用（JST変換や期間指定、稼働時間計算）
+// crate::command_runner::CommandRunner: 外部コマンド実行用（wevtutil, powershell, AuditPol等の呼び出しに使用）
+// chrono: 日時操作用（表示タイムゾーン変換や期間指定、稼働時間計算）
 // regex: 正規表現でイベントログXMLから情報抽出
 //
 // ※Cargo.tomlで chrono, regex を明示的に追加してください
 
-use std::process::Command; // 外部コマンド実行
-use std::fs::File;         // ファイル作成
-use std::io::Write;        // ファイル書き込み
-use chrono;                // 日時操作
-
-// Security監査状態をチェックし、標準出力に通知
-pub fn check_audit_log() {
-    let output = Command::new("AuditPol") // AuditPolコマンド実行
-        .args(&["/get", "/category:*"])
-        .output()
-        .expect("Failed");
-    let result = String::from_utf8_lossy(&output.stdout); // コマンド出力を文字列化
-    if result.contains("No Auditing") {
-        println!("⚠️ Security監査が無効です。ログ改ざん検知が機能しません！");
-    } else {
-        println!("✅ Security監査は有効です。ログを分析可能です！");
-    }
+use crate::command_runner::CommandRunner; // 外部コマンド実行
+use crate::time_window::{format_duration, WindowOptions}; // 監査範囲の指定
+use crate::event_util::{format_display, DisplayTz}; // 表示タイムゾーン
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+/// 最終起動時刻（UTC、ISO 8601）を取得する PowerShell のコマンド
+const BOOT_TIME_COMMAND: &str = "(Get-CimInstance -ClassName Win32_OperatingSystem).LastBootUpTime.ToUniversalTime().ToString('yyyy-MM-ddTHH:mm:ss.fffZ')";
+/// コマンドライン監査ポリシー（ProcessCreationIncludeCmdLine_Enabled）を取得する PowerShell のコマンド
+const CMDLINE_AUDIT_COMMAND: &str = "Get-ItemProperty -Path 'HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Policies\\System\\Audit' -ErrorAction SilentlyContinue | Select-Object -ExpandProperty ProcessCreationIncludeCmdLine_Enabled";
+
+// Security監査状態をチェックし、標準出力に通知（通知文を返す）
+pub fn check_audit_log(runner: &dyn CommandRunner) -> String {
+    let msg = match runner.run("AuditPol", &["/get", "/category:*"]) { // AuditPolコマンド実行
+        Ok(out) if out.success && out.stdout.contains("No Auditing") => t(Msg::SecurityAuditDisabled),
+        Ok(out) if out.success => t(Msg::SecurityAuditEnabled),
+        _ => t(Msg::SecurityAuditUnknown),
+    };
+    eprintln!("{}", msg);
+    msg.to_string()
 }
 
-// 指定ログが有効かどうかを判定
-pub fn check_log_enabled(log_name: &str) -> bool {
-    let output = Command::new("wevtutil") // wevtutil glコマンド実行
-        .args(&["gl", log_name])
-        .output()
-        .expect("Failed to run wevtutil gl");
-    let result = String::from_utf8_lossy(&output.stdout); // コマンド出力を文字列化
+// 指定ログが有効かどうかを判定（コマンド実行失敗時は無効扱い）
+pub fn check_log_enabled(runner: &dyn CommandRunner, log_name: &str) -> bool {
+    let Ok(output) = runner.run("wevtutil", &["gl", log_name]) else { // wevtutil glコマンド実行
+        return false;
+    };
+    let result = output.stdout.to_lowercase(); // コマンド出力を小文字化
     // enabled: true または enabled: 1 を含むかで判定
-    result.to_lowercase().contains("enabled: true") || result.to_lowercase().contains("enabled: 1")
+    result.contains("enabled: true") || result.contains("enabled: 1")
+}
+
+/// 通知文を標準エラー出力に表示し、レポート用に記録する関数
+fn notify(messages: &mut Vec<String>, msg: &str) {
+    eprintln!("{}", msg);
+    messages.push(msg.to_string());
+}
+
+/// 事前チェックの結果（監査範囲・監査ポリシー・環境チェックの通知文）
+#[derive(Debug, Clone)]
+pub struct PrecheckInfo {
+    pub start_time: chrono::DateTime<chrono::Utc>, // 監査開始時刻
+    pub end_time: chrono::DateTime<chrono::Utc>,   // 監査終了時刻
+    pub cmdline_audit_enabled: bool,               // コマンドライン監査有効フラグ
+    pub messages: Vec<String>,                     // 環境チェックの通知文（レポート用）
 }
 
 /// 管理者権限チェック・ログ有効化チェック・現在日時・稼働時間・監査状態・監査範囲出力をまとめて実行し、
-/// 監査対象の開始・終了時刻（UTC）を返す。
-pub fn precheck_and_info() -> Option<(chrono::DateTime<chrono::Utc>, chrono::DateTime<chrono::Utc>, chrono::FixedOffset, bool)> {
+/// 監査対象の開始・終了時刻（UTC、window の指定と最終起動時刻から決定）等を返す。
+/// 通知文は標準エラー出力に表示し、レポートに含めるため PrecheckInfo.messages にも格納する。
+/// 続行できない場合（管理者権限なし・監査範囲を決定できない）は通知文をErrで返す。
+pub fn precheck_and_info(runner: &dyn CommandRunner, window: &WindowOptions, tz: DisplayTz) -> Result<PrecheckInfo, String> {
+    let mut messages: Vec<String> = Vec::new(); // 環境チェックの通知文
     // --- 管理者権限チェック（net sessionで判定） ---
-    let admin_check = Command::new("cmd") // cmdでnet session実行
-        .args(&["/C", "net session >nul 2>&1"])
-        .status()
-        .expect("Failed to check admin");
-    if !admin_check.success() {
-        let msg = "🛑 このアプリは管理者権限で実行してください。\n"; // 権限警告
-        println!("{}", msg.trim());
-        let mut file = File::create("output.txt").expect("ファイル作成失敗"); // 警告をoutput.txtにも出力
-        file.write_all(msg.as_bytes()).unwrap();
-        return None;
+    let is_admin = runner
+        .run("cmd", &["/C", "net session >nul 2>&1"]) // cmdでnet session実行
+        .map(|out| out.success)
+        .unwrap_or(false); // 実行できない場合も権限なし扱い
+    if !is_admin {
+        let msg = t(Msg::AdminRequired); // 権限警告
+        eprintln!("{}", msg);
+        return Err(msg.to_string());
     }
 
     // --- イベントログの有効化状態をチェック ---
-    let mut file = File::create("output.txt").expect("ファイル作成失敗"); // 出力ファイル作成
     let mut all_enabled = true; // すべて有効か
     let mut log_enabled_map = std::collections::HashMap::new(); // ログ名→有効/無効
     for log in ["System", "Application", "Microsoft-Windows-TaskScheduler/Operational", "Security"] {
-        let enabled = check_log_enabled(log); // ログ有効判定
+        let enabled = check_log_enabled(runner, log); // ログ有効判定
         log_enabled_map.insert(log, enabled); // 結果をマップに格納
         if enabled {
-            let msg = format!("✅ {} ログは記録有効です。\n", log); // 有効通知
-            println!("{}", msg.trim());
-            file.write_all(msg.as_bytes()).unwrap();
+            let msg = tr(Msg::LogEnabled, &[&log]); // 有効通知
+            notify(&mut messages, &msg);
         } else {
-            let msg = format!("⚠️ {} ログは記録が無効です。\n", log); // 無効通知
-            println!("{}", msg.trim());
-            file.write_all(msg.as_bytes()).unwrap();
+            let msg = tr(Msg::LogDisabled, &[&log]); // 無効通知
+            notify(&mut messages, &msg);
             all_enabled = false;
         }
     }
     if !all_enabled {
-        let msg = "⚠️ 記録が無効なログはイベントビューアーでプロパティから「ログの有効化」にチェックを入れてください。\n"; // 全体警告
-        println!("{}", msg.trim());
-        file.write_all(msg.as_bytes()).unwrap();
+        let msg = t(Msg::EnableLogHint); // 全体警告
+        notify(&mut messages, msg);
         // return; ← ここを削除して続行
     }
 
-    // --- 現在日時（アプリ起動日時）をJSTで出力 ---
+    // --- 現在日時（アプリ起動日時）を表示タイムゾーンで出力 ---
     let now = chrono::Utc::now(); // UTC現在時刻取得
-    let jst = chrono::FixedOffset::east_opt(9*3600).unwrap(); // JSTタイムゾーン
-    let mut file = File::create("output.txt").expect("ファイル作成失敗"); // 出力ファイル再作成
-    let msg = format!("ℹ️ アプリ起動日時: {}\n", now.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S")); // 起動日時
-    println!("{}", msg.trim());
-    file.write_all(msg.as_bytes()).unwrap();
-
-    // --- システム稼働時間をPowerShellで取得 ---
-    let uptime_cmd = ["-Command", "(Get-CimInstance -ClassName Win32_OperatingSystem).LastBootUpTime.ToUniversalTime().ToString('yyyy-MM-ddTHH:mm:ss.fffZ')"];
-    let uptime_output = Command::new("powershell")
-        .args(&uptime_cmd)
-        .output()
-        .expect("Failed to get uptime");
-    let boot_time_str = String::from_utf8_lossy(&uptime_output.stdout).trim().to_string(); // ブート時刻文字列
-    // システム稼働時間-180秒からアプリ起動日時までの間のイベントを抽出
-    let mut start_time = now;
-    let mut end_time = now;
-    if !boot_time_str.is_empty() {
-        // --- ブート時刻のパース（ISO8601） ---
-        if let Ok(boot_time) = chrono::DateTime::parse_from_rfc3339(&boot_time_str) {
-            let now_utc = now.with_timezone(&chrono::Utc);
-            start_time = boot_time.with_timezone(&chrono::Utc) - chrono::Duration::seconds(30); // ブート時刻-30秒
-            // 直近のブート後1.5時間（90分）までを監査対象の終端とする
-            let boot_plus_90 = boot_time.with_timezone(&chrono::Utc) + chrono::Duration::minutes(90);
-            end_time = if now_utc < boot_plus_90 { now_utc } else { boot_plus_90 };
-            let uptime_msg = format!("ℹ️ システム稼働時間: {}日 {}時間 {}分 {}秒\n", (now_utc - boot_time.with_timezone(&chrono::Utc)).num_days(), (now_utc - boot_time.with_timezone(&chrono::Utc)).num_hours()%24, (now_utc - boot_time.with_timezone(&chrono::Utc)).num_minutes()%60, (now_utc - boot_time.with_timezone(&chrono::Utc)).num_seconds()%60); // 稼働時間
-            println!("{}", uptime_msg.trim());
-            file.write_all(uptime_msg.as_bytes()).unwrap();
-            // 監査対象日時範囲をJSTで出力
-            let audit_range_msg = format!(
-                "ℹ️ イベント監査対象日時: {} ～ {}\n",
-                start_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S"),
-                end_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S")
-            );
-            println!("{}", audit_range_msg.trim());
-            file.write_all(audit_range_msg.as_bytes()).unwrap();
-        } else if let Ok(boot_time) = chrono::DateTime::parse_from_str(&boot_time_str, "%Y-%m-%dT%H:%M:%S%.3fZ") {
-            // --- 予備パース（ミリ秒3桁Z付き） ---
-            let now_utc = now.with_timezone(&chrono::Utc);
-            start_time = boot_time.with_timezone(&chrono::Utc) - chrono::Duration::seconds(180); // ブート時刻-180秒
-            let boot_plus_90 = boot_time.with_timezone(&chrono::Utc) + chrono::Duration::minutes(90);
-            end_time = if now_utc < boot_plus_90 { now_utc } else { boot_plus_90 };
-            let uptime_msg = format!("ℹ️ システム稼働時間: {}日 {}時間 {}分 {}秒\n", (now_utc - boot_time.with_timezone(&chrono::Utc)).num_days(), (now_utc - boot_time.with_timezone(&chrono::Utc)).num_hours()%24, (now_utc - boot_time.with_timezone(&chrono::Utc)).num_minutes()%60, (now_utc - boot_time.with_timezone(&chrono::Utc)).num_seconds()%60); // 稼働時間
-            println!("{}", uptime_msg.trim());
-            file.write_all(uptime_msg.as_bytes()).unwrap();
-            // 監査対象日時範囲をJSTで出力
-            let audit_range_msg = format!(
-                "ℹ️ イベント監査対象日時: {} ～ {}\n",
-                start_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S"),
-                end_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S")
-            );
-            println!("{}", audit_range_msg.trim());
-            file.write_all(audit_range_msg.as_bytes()).unwrap();
-        } else {
-            // --- パース失敗時 ---
-            let msg = format!("⚠️ システム稼働時間: 取得失敗 (生データ: {})\n", boot_time_str); // 失敗通知
-            println!("{}", msg.trim());
-            file.write_all(msg.as_bytes()).unwrap();
+    let msg = tr(Msg::AppStarted, &[&format_display(now, tz), &tz.describe(now)]); // 起動日時
+    notify(&mut messages, &msg);
+
+    // --- システム稼働時間（最終起動時刻）をPowerShellで取得 ---
+    let boot_time_str = runner
+        .run("powershell", &["-Command", BOOT_TIME_COMMAND])
+        .map(|out| out.stdout.trim().to_string())
+        .unwrap_or_default(); // ブート時刻文字列（取得失敗時は空）
+    // ブート時刻のパース（ISO8601、ミリ秒3桁Z付き）
+    let boot_time = chrono::DateTime::parse_from_rfc3339(&boot_time_str).ok().map(|t| t.with_timezone(&chrono::Utc));
+    match boot_time {
+        Some(boot_time) => {
+            let uptime = now - boot_time; // 稼働時間
+            let uptime_msg = tr(Msg::Uptime, &[&uptime.num_days(), &(uptime.num_hours()%24), &(uptime.num_minutes()%60), &(uptime.num_seconds()%60)]); // 稼働時間
+            notify(&mut messages, &uptime_msg);
+        }
+        None if boot_time_str.is_empty() => notify(&mut messages, t(Msg::UptimeFailed)), // 取得失敗
+        None => {
+            let msg = tr(Msg::UptimeParseFailed, &[&boot_time_str]); // パース失敗
+            notify(&mut messages, &msg);
         }
-    } else {
-        // --- 取得失敗時 ---
-        let msg = "⚠️ システム稼働時間: 取得失敗\n"; // 失敗通知
-        println!("{}", msg.trim());
-        file.write_all(msg.as_bytes()).unwrap();
     }
+    // --- 監査範囲の決定（--since / --last 指定時はその範囲、それ以外は最終起動時刻の前後） ---
+    let (start_time, end_time) = match window.resolve_checked(now, boot_time) {
+        Ok(range) => range,
+        Err(e) => {
+            let msg = format!("🛑 {}", e); // 範囲決定失敗
+            eprintln!("{}", msg);
+            return Err(msg);
+        }
+    };
+    let basis = if window.is_explicit() {
+        t(Msg::RangeExplicit).to_string()
+    } else {
+        tr(Msg::RangeBootRelative, &[&format_duration(window.boot_offset), &format_duration(window.boot_window)])
+    }; // 監査範囲の根拠
+    // 監査対象日時範囲を表示タイムゾーンで出力
+    let audit_range_msg = tr(Msg::AuditRangeNotice, &[&format_display(start_time, tz), &format_display(end_time, tz), &basis]);
+    notify(&mut messages, &audit_range_msg);
 
     // --- Security監査状態を確認 ---
-    check_audit_log(); // 監査状態チェック
+    messages.push(check_audit_log(runner)); // 監査状態チェック
 
     // --- コマンドライン監査ポリシーの有効/未構成チェック ---
     let mut cmdline_audit_enabled = true; // 監査ポリシーフラグ
-    let auditpol_output = Command::new("powershell")
-        .args(["-Command", "Get-ItemProperty -Path 'HKLM:\\Software\\Microsoft\\Windows\\CurrentVersion\\Policies\\System\\Audit' -ErrorAction SilentlyContinue | Select-Object -ExpandProperty ProcessCreationIncludeCmdLine_Enabled"])
-        .output();
+    let auditpol_output = runner.run("powershell", &["-Command", CMDLINE_AUDIT_COMMAND]);
     if let Ok(out) = auditpol_output {
-        let val = String::from_utf8_lossy(&out.stdout).trim().to_string(); // レジストリ値
+        let val = out.stdout.trim().to_string(); // レジストリ値
         if val != "1" {
             cmdline_audit_enabled = false;
-            let msg = "⚠️ プロセス作成イベントにコマンドラインを含める監査ポリシーが未構成または無効です。svchost.exeの挙動詳細は出力できません。\n".to_string(); // 警告
-            println!("{}", msg.trim());
-            file.write_all(msg.as_bytes()).unwrap();
+            let msg = t(Msg::CmdlineAuditOff); // 警告
+            notify(&mut messages, msg);
+        }
+    }
+
+    Ok(PrecheckInfo { start_time, end_time, cmdline_audit_enabled, messages }) // 監査範囲・監査ポリシー有効フラグを返す
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::command_runner::ScriptedRunner;
+
+    const LOGS: [&str; 4] = ["System", "Application", "Microsoft-Windows-TaskScheduler/Operational", "Security"];
+
+    /// 管理者権限・ログの有効化・最終起動時刻・コマンドライン監査ポリシーの応答を登録した再生用の実行環境
+    fn scripted(admin: bool, disabled_log: Option<&str>, boot_time: &str, cmdline: &str) -> ScriptedRunner {
+        let mut runner = ScriptedRunner::new().on("cmd", &["/C", "net session >nul 2>&1"], admin, "");
+        for log in LOGS {
+            let enabled = if Some(log) == disabled_log { "false" } else { "true" };
+            runner = runner.on("wevtutil", &["gl", log], true, &format!("name: {}\r\nenabled: {}\r\n", log, enabled));
         }
+        runner
+            .on("powershell", &["-Command", BOOT_TIME_COMMAND], true, boot_time)
+            .on("AuditPol", &["/get", "/category:*"], true, "System audit policy\r\n  Logon  Success and Failure\r\n")
+            .on("powershell", &["-Command", CMDLINE_AUDIT_COMMAND], true, cmdline)
     }
 
-    // --- output.txt冒頭に監査対象日時のみ出力 ---
-    let mut file = std::fs::File::create("output.txt").expect("ファイル作成失敗"); // ファイル再作成
-    let audit_range_msg = format!(
-        ">>> イベント監査対象日時: {} ～ {}\n",
-        start_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S"),
-        end_time.with_timezone(&jst).format("%Y/%m/%d %H:%M:%S")
-    ); // 監査範囲
-    file.write_all(audit_range_msg.as_bytes()).unwrap();
+    fn recent_boot() -> String {
+        (chrono::Utc::now() - chrono::Duration::minutes(30)).format("%Y-%m-%dT%H:%M:%S%.3fZ").to_string()
+    }
+
+    #[test]
+    fn admin_denied_stops_before_other_checks() {
+        let runner = ScriptedRunner::new().on("cmd", &["/C", "net session >nul 2>&1"], false, "");
+        let result = precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc);
+        assert_eq!(result.unwrap_err(), t(Msg::AdminRequired));
+    }
+
+    #[test]
+    fn admin_check_spawn_failure_is_treated_as_denied() {
+        let runner = ScriptedRunner::new().on_error("cmd", &["/C", "net session >nul 2>&1"], "not found");
+        assert!(precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc).is_err());
+    }
 
-    Some((start_time, end_time, jst, cmdline_audit_enabled)) // 監査範囲・JST・監査ポリシー有効フラグを返す
+    #[test]
+    fn disabled_log_is_reported_and_analysis_continues() {
+        let runner = scripted(true, Some("Security"), &recent_boot(), "1");
+        let info = precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc).unwrap();
+        assert!(info.messages.contains(&tr(Msg::LogDisabled, &[&"Security"])));
+        assert!(info.messages.contains(&tr(Msg::LogEnabled, &[&"System"])));
+        assert!(info.messages.iter().any(|m| m == t(Msg::EnableLogHint)));
+        assert!(info.cmdline_audit_enabled);
+    }
+
+    #[test]
+    fn unparsable_boot_time_is_reported() {
+        let runner = scripted(true, None, "2025/07/10 9:00", "1");
+        let err = precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc).unwrap_err();
+        assert!(err.contains(t(Msg::BootTimeUnavailable)), "{}", err); // 既定の監査範囲は最終起動時刻が必要
+
+        let runner = scripted(true, None, "2025/07/10 9:00", "1");
+        let window = WindowOptions { last: Some(chrono::Duration::hours(1)), ..WindowOptions::default() };
+        let info = precheck_and_info(&runner, &window, DisplayTz::Utc).unwrap();
+        assert!(info.messages.contains(&tr(Msg::UptimeParseFailed, &[&"2025/07/10 9:00"])));
+        assert_eq!(info.end_time - info.start_time, chrono::Duration::hours(1));
+    }
+
+    #[test]
+    fn boot_time_sets_boot_relative_range() {
+        let boot = recent_boot();
+        let runner = scripted(true, None, &boot, "1");
+        let info = precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc).unwrap();
+        let boot = chrono::DateTime::parse_from_rfc3339(&boot).unwrap().with_timezone(&chrono::Utc);
+        assert_eq!(info.start_time, boot - chrono::Duration::seconds(crate::time_window::DEFAULT_BOOT_OFFSET_SECS));
+    }
+
+    #[test]
+    fn missing_cmdline_policy_disables_cmdline_audit() {
+        let runner = scripted(true, None, &recent_boot(), "");
+        let info = precheck_and_info(&runner, &WindowOptions::default(), DisplayTz::Utc).unwrap();
+        assert!(!info.cmdline_audit_enabled);
+        assert!(info.messages.iter().any(|m| m == t(Msg::CmdlineAuditOff)));
+    }
 }
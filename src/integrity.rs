@@ -0,0 +1,306 @@
+// --- 必要なクレートの説明 ---
+// このファイルはイベントログ自体の改ざん・欠落の兆候を検出するモジュールです
+// ログの消去（Security の 1102、System の 104）、シャットダウンを伴わないイベントログサービスの停止（1100）、
+// 監査ポリシーの変更（4719）、システム時刻の変更（4616）と、ログごとの EventRecordID の欠落・巻き戻りを検出します
+// 検出結果は重大度付きでレポートの先頭（ReportHeader.integrity）に表示し、該当するイベントにも検出事項（Finding）として付与します
+// chrono: 前後のシャットダウン・時刻の変更幅の計算用
+// serde: JSON出力用シリアライズ
+
+use chrono::{DateTime, Duration, Utc}; // 日時
+use serde::Serialize; // シリアライズ
+use crate::event_catalog::EventCategory; // シャットダウン・起動の判定
+use crate::event_types::{serialize_utc, EventRecord, Finding, FindingKind, RecordMark}; // イベントレコード型・検出事項
+use crate::event_util::{format_display, DisplayTz}; // 日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+use crate::time_window::format_duration; // 期間の表示
+
+/// 1100 の前後でシャットダウン・起動の記録を探す範囲（分）
+const SHUTDOWN_WINDOW_MINS: i64 = 10;
+/// 検出するシステム時刻の変更幅（分、時刻同期による小さな補正は対象外）
+const TIME_CHANGE_MINS: i64 = 5;
+/// 4719 で監査の削除を表すメッセージID（成功の監査を削除・失敗の監査を削除）
+const AUDIT_REMOVED: &[&str] = &["%%8448", "%%8450"];
+
+/// 検出事項の重大度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    High,   // 重大（ログの消去・欠落など、証跡が失われた可能性）
+    Medium, // 注意（監査の追加・時刻を進める変更など）
+}
+
+/// ログの整合性に関する検出事項（レポートの先頭に表示）
+#[derive(Debug, Clone, Serialize)]
+pub struct IntegrityFinding {
+    pub severity: Severity,        // 重大度
+    pub kind: FindingKind,         // 種別
+    pub channel: String,           // ログ名
+    #[serde(serialize_with = "serialize_utc")]
+    pub timestamp: DateTime<Utc>,  // 発生日時（EventRecordID の欠落・巻き戻りは直後のイベントの日時）
+    pub record_id: u64,            // EventRecordID（欠落・巻き戻りは直後のイベント）
+    pub message: String,           // 説明（表示言語）
+}
+
+impl IntegrityFinding {
+    /// ヘッダに表示する1行（重大度付き）
+    pub fn headline(&self) -> String {
+        match self.severity {
+            Severity::High => tr(Msg::IntegrityHigh, &[&self.message]),
+            Severity::Medium => tr(Msg::IntegrityMedium, &[&self.message]),
+        }
+    }
+}
+
+/// ログの整合性を確認し、検出事項をイベントに付与して重大度・日時順に返す関数
+/// events はカタログに定義されたイベント（発生日時順）、records は取得したログごとの全イベントの位置（ログ上の順）
+pub fn check(events: &mut [EventRecord], records: &[(String, Vec<RecordMark>)], tz: DisplayTz) -> Vec<IntegrityFinding> {
+    let mut found: Vec<(Option<usize>, IntegrityFinding)> = Vec::new(); // (付与するイベント, 検出事項)
+    for (index, event) in events.iter().enumerate() {
+        if let Some(finding) = check_event(events, event, tz) {
+            found.push((Some(index), finding));
+        }
+    }
+    for (channel, marks) in records {
+        for finding in check_records(channel, marks, tz) {
+            // 欠落・巻き戻りの直後のイベントが出力対象なら、そのイベントにも付与する
+            let index = events
+                .iter()
+                .position(|e| e.record_id == finding.record_id && e.channel.name().eq_ignore_ascii_case(channel));
+            found.push((index, finding));
+        }
+    }
+    for (index, finding) in &found {
+        if let Some(index) = index {
+            events[*index].findings.push(Finding { kind: finding.kind, message: finding.message.clone() });
+        }
+    }
+    let mut findings: Vec<IntegrityFinding> = found.into_iter().map(|(_, f)| f).collect();
+    findings.sort_by(|a, b| a.severity.cmp(&b.severity).then(a.timestamp.cmp(&b.timestamp)));
+    findings
+}
+
+/// 1つのイベントを確認する関数（ログの消去・サービスの停止・監査ポリシーの変更・時刻の変更）
+fn check_event(events: &[EventRecord], event: &EventRecord, tz: DisplayTz) -> Option<IntegrityFinding> {
+    let when = format_display(event.timestamp, tz);
+    let (severity, kind, message) = match event.event_id {
+        1102 | 104 => {
+            // 104 は消去されたログ名を UserData の Channel に記録する
+            let cleared = event.data.get("Channel").filter(|c| !c.is_empty()).unwrap_or(event.channel.name());
+            (Severity::High, FindingKind::LogCleared, tr(Msg::IntegrityLogCleared, &[&cleared, &when, &subject(event)]))
+        }
+        1100 => {
+            let window = Duration::minutes(SHUTDOWN_WINDOW_MINS);
+            let near_shutdown = events.iter().any(|e| {
+                matches!(e.category, EventCategory::Shutdown | EventCategory::Boot | EventCategory::Unexpected)
+                    && (e.timestamp - event.timestamp).abs() <= window
+            });
+            if near_shutdown {
+                return None; // 通常のシャットダウンに伴う停止
+            }
+            (Severity::High, FindingKind::LogServiceStopped, tr(Msg::IntegrityServiceStopped, &[&when, &format_duration(window)]))
+        }
+        4719 => {
+            let changes = event.data.get("AuditPolicyChanges").unwrap_or("");
+            let removed = AUDIT_REMOVED.iter().any(|id| changes.contains(id));
+            let severity = if removed { Severity::High } else { Severity::Medium };
+            let subcategory = decoded_or_raw(event, "SubcategoryId")
+                .or_else(|| event.data.get("SubcategoryGuid").map(str::to_string))
+                .unwrap_or_else(|| "-".to_string());
+            let changes = decoded_or_raw(event, "AuditPolicyChanges").unwrap_or_else(|| "-".to_string());
+            (severity, FindingKind::AuditPolicyChanged, tr(Msg::IntegrityAuditPolicy, &[&when, &subcategory, &changes, &subject(event)]))
+        }
+        4616 => {
+            let previous = parse_time(event.data.get("PreviousTime")?)?;
+            let new = parse_time(event.data.get("NewTime")?)?;
+            let shift = new - previous;
+            if shift.abs() < Duration::minutes(TIME_CHANGE_MINS) {
+                return None; // 時刻同期による小さな補正
+            }
+            let (severity, direction) = if shift < Duration::zero() {
+                (Severity::High, t(Msg::IntegrityTimeBackward)) // 巻き戻しは記録の前後関係を偽装できる
+            } else {
+                (Severity::Medium, t(Msg::IntegrityTimeForward))
+            };
+            let process = event.data.get("ProcessName").filter(|p| !p.is_empty() && *p != "-").unwrap_or("-");
+            let args: [&dyn std::fmt::Display; 6] = [
+                &format_display(previous, tz),
+                &format_display(new, tz),
+                &format_duration(shift.abs()),
+                &direction,
+                &process,
+                &subject(event),
+            ];
+            (severity, FindingKind::SystemTimeChanged, tr(Msg::IntegrityTimeChange, &args))
+        }
+        _ => return None,
+    };
+    Some(IntegrityFinding {
+        severity,
+        kind,
+        channel: event.channel.name().to_string(),
+        timestamp: event.timestamp,
+        record_id: event.record_id,
+        message,
+    })
+}
+
+/// ログ上の順に EventRecordID を確認し、欠落（番号の飛び）・巻き戻り（番号の減少・重複）を検出する関数
+/// 巻き戻りの直後がログの消去（1102 / 104）なら、消去として検出済みのため重大度を下げる
+fn check_records(channel: &str, marks: &[RecordMark], tz: DisplayTz) -> Vec<IntegrityFinding> {
+    let mut findings = Vec::new();
+    for pair in marks.windows(2) {
+        let (prev, next) = (pair[0], pair[1]);
+        let (severity, kind, message) = if next.record_id > prev.record_id + 1 {
+            let missing = next.record_id - prev.record_id - 1;
+            let args: [&dyn std::fmt::Display; 6] = [
+                &channel,
+                &prev.record_id,
+                &next.record_id,
+                &missing,
+                &format_display(prev.timestamp, tz),
+                &format_display(next.timestamp, tz),
+            ];
+            (Severity::High, FindingKind::RecordIdGap, tr(Msg::IntegrityRecordGap, &args))
+        } else if next.record_id <= prev.record_id {
+            let args: [&dyn std::fmt::Display; 4] = [&channel, &prev.record_id, &next.record_id, &format_display(next.timestamp, tz)];
+            if matches!(next.event_id, 1102 | 104) {
+                (Severity::Medium, FindingKind::RecordIdReset, tr(Msg::IntegrityRecordResetCleared, &args))
+            } else {
+                (Severity::High, FindingKind::RecordIdReset, tr(Msg::IntegrityRecordReset, &args))
+            }
+        } else {
+            continue; // 連続している
+        };
+        findings.push(IntegrityFinding {
+            severity,
+            kind,
+            channel: channel.to_string(),
+            timestamp: next.timestamp,
+            record_id: next.record_id,
+            message,
+        });
+    }
+    findings
+}
+
+/// 操作したアカウント（SubjectDomainName\SubjectUserName、なければイベントのユーザー）
+fn subject(event: &EventRecord) -> String {
+    let user = event.data.get("SubjectUserName").unwrap_or("").trim();
+    let domain = event.data.get("SubjectDomainName").unwrap_or("").trim();
+    match (domain, user) {
+        (_, "") if !event.user.is_empty() => event.user.clone(),
+        (_, "") => "-".to_string(),
+        ("", user) => user.to_string(),
+        (domain, user) => format!("{}\\{}", domain, user),
+    }
+}
+
+/// 項目の表記（変換できなければ元の値、値がなければNone）
+fn decoded_or_raw(event: &EventRecord, name: &str) -> Option<String> {
+    event
+        .decoded
+        .get(name)
+        .or_else(|| event.data.get(name))
+        .map(str::trim)
+        .filter(|v| !v.is_empty())
+        .map(str::to_string)
+}
+
+/// 4616 の PreviousTime / NewTime（RFC 3339、UTC）を解釈する関数
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value.trim()).ok().map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event_catalog::EventCatalog;
+    use crate::event_parse::parse_events;
+
+    /// testdata/integrity の Security.xml / System.xml を読み込み、カタログのイベント（発生日時順）とログごとの全イベントの位置を返す
+    fn fixture() -> (Vec<EventRecord>, Vec<(String, Vec<RecordMark>)>) {
+        let catalog = EventCatalog::load(&[] as &[&str]).unwrap();
+        let mut events = Vec::new();
+        let mut records = Vec::new();
+        for (log, ids) in catalog.channels().into_iter().filter(|(log, _)| log == "Security" || log == "System") {
+            let path = format!("{}/testdata/integrity/{}.xml", env!("CARGO_MANIFEST_DIR"), log);
+            let parsed = parse_events(&std::fs::read_to_string(path).unwrap(), &ids);
+            events.extend(parsed.events);
+            records.push((log, parsed.records));
+        }
+        for event in &mut events {
+            event.category = catalog.lookup(event).unwrap().category;
+        }
+        events.sort_by(EventRecord::cmp_chronological);
+        (events, records)
+    }
+
+    fn checked() -> (Vec<EventRecord>, Vec<IntegrityFinding>) {
+        let (mut events, records) = fixture();
+        let findings = check(&mut events, &records, DisplayTz::Utc);
+        (events, findings)
+    }
+
+    /// 指定種別の検出事項（重大度, ログ名, EventRecordID）
+    fn of_kind(findings: &[IntegrityFinding], kind: FindingKind) -> Vec<(Severity, &str, u64)> {
+        findings.iter().filter(|f| f.kind == kind).map(|f| (f.severity, f.channel.as_str(), f.record_id)).collect()
+    }
+
+    #[test]
+    fn log_clears_are_high() {
+        let (events, findings) = checked();
+        assert_eq!(of_kind(&findings, FindingKind::LogCleared), [(Severity::High, "Security", 1), (Severity::High, "System", 1)]);
+        let cleared = events.iter().find(|e| e.event_id == 104).unwrap();
+        assert_eq!(cleared.findings[0].kind, FindingKind::LogCleared); // 該当イベントにも付与
+        assert!(cleared.findings[0].message.contains("HOST1\\alice"), "{}", cleared.findings[0].message);
+    }
+
+    #[test]
+    fn record_id_gap_is_high() {
+        let (_, findings) = checked();
+        assert_eq!(of_kind(&findings, FindingKind::RecordIdGap), [(Severity::High, "Security", 231)]);
+        let gap = findings.iter().find(|f| f.kind == FindingKind::RecordIdGap).unwrap();
+        assert!(gap.message.contains("25"), "{}", gap.message); // 206 ～ 230 の25件
+    }
+
+    #[test]
+    fn record_id_reset_after_clear_is_medium() {
+        let (_, findings) = checked();
+        assert_eq!(
+            of_kind(&findings, FindingKind::RecordIdReset),
+            [(Severity::Medium, "Security", 1), (Severity::Medium, "System", 1)]
+        );
+        // 消去の記録なしに巻き戻った場合は重大
+        let marks = [
+            RecordMark { record_id: 10, event_id: 4624, timestamp: chrono::Utc::now() },
+            RecordMark { record_id: 3, event_id: 4624, timestamp: chrono::Utc::now() },
+        ];
+        let reset = check_records("Security", &marks, DisplayTz::Utc);
+        assert_eq!((reset[0].severity, reset[0].kind), (Severity::High, FindingKind::RecordIdReset));
+    }
+
+    #[test]
+    fn audit_policy_changes() {
+        let (_, findings) = checked();
+        // 201: 監査の削除を含む変更、202: 監査の追加のみ
+        assert_eq!(
+            of_kind(&findings, FindingKind::AuditPolicyChanged),
+            [(Severity::High, "Security", 201), (Severity::Medium, "Security", 202)]
+        );
+    }
+
+    #[test]
+    fn system_time_rollback_is_high_and_small_shift_is_ignored() {
+        let (_, findings) = checked();
+        // 203: 5分以上の巻き戻し、204: 時刻同期による小さな補正（検出しない）
+        assert_eq!(of_kind(&findings, FindingKind::SystemTimeChanged), [(Severity::High, "Security", 203)]);
+    }
+
+    #[test]
+    fn service_stop_without_shutdown_and_ordering() {
+        let (_, findings) = checked();
+        assert_eq!(of_kind(&findings, FindingKind::LogServiceStopped), [(Severity::High, "Security", 232)]);
+        // 重大度順（重大が先）、同じ重大度は日時順
+        assert!(findings.windows(2).all(|w| (w[0].severity, w[0].timestamp) <= (w[1].severity, w[1].timestamp)));
+    }
+}
@@ -0,0 +1,217 @@
+// --- 必要なクレートの説明 ---
+// このファイルはログオン（4624）とログオフ（4647 / 4634）をログオンIDで組み合わせ、ログオンセッションを求めるモジュールです
+// プロセス作成（4688）は SubjectLogonId で作成元のセッションに対応付けます
+// ログオンIDは起動ごとに割り当てられるため、同じ起動の中でのみ組み合わせます
+// セッションの一覧（--format sessions）もこのモジュールで出力します
+// chrono: ログオン・ログオフ日時と継続時間の計算用
+// serde: JSON出力用シリアライズ
+
+use std::collections::HashMap; // 起動番号・ログオンID→セッション
+use std::io::Write; // 書き込み用
+use chrono::{DateTime, Duration, Utc}; // 日時
+use serde::Serialize; // シリアライズ
+use crate::boot_session::BootSessions; // 起動ごとの区間
+use crate::event_types::{format_logon_id, serialize_logon_id, serialize_opt_utc, EventRecord, LogonType}; // イベントレコード型
+use crate::event_util::{format_display, DisplayTz}; // 日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+use crate::time_window::format_duration; // 期間の表示
+
+/// ログオンイベントなしで使われる既定のログオンID（ログオンID, アカウント名）
+const WELL_KNOWN_LOGON_IDS: &[(u64, &str)] = &[
+    (0x3e7, "SYSTEM"),
+    (0x3e4, "NETWORK SERVICE"),
+    (0x3e5, "LOCAL SERVICE"),
+];
+
+/// セッションの終わり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionState {
+    LoggedOff, // ログオフイベントあり
+    Active,    // 監査範囲の終了時点でログオン中（最後の起動でログオフイベントなし）
+    Rebooted,  // ログオフイベントがないまま次の起動を迎えた
+}
+
+impl SessionState {
+    /// 出力用の名前（JSONの値と同じ）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionState::LoggedOff => "logged_off",
+            SessionState::Active => "active",
+            SessionState::Rebooted => "rebooted",
+        }
+    }
+
+    /// 表示用の文字列
+    fn text(&self) -> &'static str {
+        match self {
+            SessionState::LoggedOff => t(Msg::SessionLoggedOff),
+            SessionState::Active => t(Msg::SessionActive),
+            SessionState::Rebooted => t(Msg::SessionRebooted),
+        }
+    }
+}
+
+/// ログオンセッション（ログオン・ログオフ・セッション内のプロセス作成イベントに付与）
+#[derive(Debug, Clone, Serialize)]
+pub struct LogonSession {
+    #[serde(serialize_with = "serialize_logon_id")]
+    pub logon_id: u64,                        // ログオンID
+    pub boot: u32,                            // 起動番号（0は最初の起動イベントより前）
+    pub account: String,                      // アカウント（ドメイン\ユーザー）
+    pub logon_type: Option<LogonType>,        // ログオンタイプ
+    pub source: String,                       // 接続元（IPアドレス・ワークステーション名・認証方式）
+    #[serde(serialize_with = "serialize_opt_utc")]
+    pub start: Option<DateTime<Utc>>,         // ログオン日時（ログオンが監査範囲外ならNone）
+    #[serde(serialize_with = "serialize_opt_utc")]
+    pub end: Option<DateTime<Utc>>,           // ログオフ日時（ログオフイベントがなければNone）
+    pub duration_ms: Option<i64>,             // 継続時間（ミリ秒、ログオン・ログオフの両方がある場合のみ）
+    pub logoff_event_id: Option<u32>,         // ログオフのイベントID（4634 / 4647）
+    pub state: SessionState,                  // 終わり方
+    pub process_count: usize,                 // セッション内で作成されたプロセスの数
+    pub start_record_id: Option<u64>,         // ログオンイベントの EventRecordID
+    pub end_record_id: Option<u64>,           // ログオフイベントの EventRecordID
+}
+
+impl LogonSession {
+    /// 継続時間
+    fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::milliseconds)
+    }
+
+    /// イベントの詳細に付ける表示（ログオンにはログオフ、ログオフにはログオン、プロセス作成にはセッションの情報）
+    pub fn marker(&self, event: &EventRecord, tz: DisplayTz) -> String {
+        let id = format_logon_id(self.logon_id);
+        match event.event_id {
+            4624 => match (self.end, self.duration()) {
+                (Some(end), Some(d)) => tr(Msg::SessionLogoffMarker, &[&id, &format_display(end, tz), &format_duration(d)]),
+                _ => tr(Msg::SessionOpenMarker, &[&id, &self.state.text()]),
+            },
+            4634 | 4647 => match (self.start, self.duration()) {
+                (Some(start), Some(d)) => tr(Msg::SessionLogonMarker, &[&id, &format_display(start, tz), &format_duration(d)]),
+                _ => tr(Msg::SessionLogonUnknown, &[&id]),
+            },
+            _ => tr(Msg::SessionProcessMarker, &[&self.account, &id]),
+        }
+    }
+}
+
+/// ログオン・ログオフ・プロセス作成をログオンIDで組み合わせてセッションを付与する関数（eventsは発生日時順）
+pub fn annotate(events: &mut [EventRecord]) {
+    let boots = BootSessions::detect(events);
+    let mut sessions: Vec<(LogonSession, Vec<usize>)> = Vec::new(); // (セッション, 付与するイベント)
+    let mut open: HashMap<(u32, u64), usize> = HashMap::new(); // (起動番号, ログオンID)→ログオフしていないセッション
+    for (index, event) in events.iter().enumerate() {
+        let boot = boots.boot_of(index);
+        match (event.event_id, &event.logon, event.subject_logon_id) {
+            (4624, Some(logon), _) => {
+                // 新しいセッション（同じIDのセッションが残っていれば置き換え）
+                open.insert((boot, logon.target_logon_id), sessions.len());
+                let session = LogonSession {
+                    logon_id: logon.target_logon_id,
+                    boot,
+                    account: logon.account(),
+                    logon_type: event.logon_type,
+                    source: logon.source(),
+                    start: Some(event.timestamp),
+                    end: None,
+                    duration_ms: None,
+                    logoff_event_id: None,
+                    state: SessionState::Active,
+                    process_count: 0,
+                    start_record_id: Some(event.record_id),
+                    end_record_id: None,
+                };
+                sessions.push((session, vec![index]));
+            }
+            (4634 | 4647, Some(logon), _) => {
+                let slot = *open.entry((boot, logon.target_logon_id)).or_insert_with(|| {
+                    sessions.push((unknown_session(logon.target_logon_id, boot, &logon.account(), event.logon_type), Vec::new())); // ログオンが監査範囲外
+                    sessions.len() - 1
+                });
+                let (session, members) = &mut sessions[slot];
+                // 4647（ログオフの開始）の後に 4634（セッションの終了）が続く場合は 4634 を終了とする
+                session.end = Some(event.timestamp);
+                session.end_record_id = Some(event.record_id);
+                session.logoff_event_id = Some(event.event_id);
+                session.state = SessionState::LoggedOff;
+                session.duration_ms = session.start.map(|start| (event.timestamp - start).num_milliseconds());
+                members.push(index);
+                if event.event_id == 4634 {
+                    open.remove(&(boot, logon.target_logon_id)); // セッションの終了
+                }
+            }
+            (4688, _, Some(logon_id)) => {
+                let slot = *open.entry((boot, logon_id)).or_insert_with(|| {
+                    // ログオンが監査範囲外（SYSTEM などの既定のログオンIDを含む）
+                    let account = WELL_KNOWN_LOGON_IDS.iter().find(|(id, _)| *id == logon_id).map(|(_, name)| name.to_string());
+                    let account = account.unwrap_or_else(|| match event.data.get("SubjectDomainName").filter(|d| !d.is_empty()) {
+                        Some(domain) => format!("{}\\{}", domain, event.user),
+                        None => event.user.clone(),
+                    });
+                    sessions.push((unknown_session(logon_id, boot, &account, None), Vec::new()));
+                    sessions.len() - 1
+                });
+                sessions[slot].0.process_count += 1;
+                sessions[slot].1.push(index);
+            }
+            _ => {}
+        }
+    }
+    // ログオフイベントのないセッション: 最後の起動ならログオン中、それ以前の起動なら再起動で終了
+    for (session, _) in sessions.iter_mut().filter(|(s, _)| s.end.is_none()) {
+        session.state = if session.boot == boots.count() { SessionState::Active } else { SessionState::Rebooted };
+    }
+    for (session, members) in sessions {
+        for index in members {
+            events[index].session = Some(session.clone());
+        }
+    }
+}
+
+/// ログオンが監査範囲外のセッションを作る関数
+fn unknown_session(logon_id: u64, boot: u32, account: &str, logon_type: Option<LogonType>) -> LogonSession {
+    LogonSession {
+        logon_id,
+        boot,
+        account: account.to_string(),
+        logon_type,
+        source: String::new(),
+        start: None,
+        end: None,
+        duration_ms: None,
+        logoff_event_id: None,
+        state: SessionState::Active,
+        process_count: 0,
+        start_record_id: None,
+        end_record_id: None,
+    }
+}
+
+/// セッションの一覧をタブ区切りで書き込む関数（1セッション1行、最初に現れたイベントの順）
+pub fn write_sessions(events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", t(Msg::SessionColumns))?; // 列名
+    let mut seen: Vec<(u32, u64, Option<u64>)> = Vec::new(); // 出力済みのセッション（起動番号・ログオンID・ログオンの EventRecordID）
+    for session in events.iter().filter_map(|e| e.session.as_ref()) {
+        let key = (session.boot, session.logon_id, session.start_record_id);
+        if seen.contains(&key) {
+            continue; // 同じセッションのイベントが複数ある場合は1行のみ
+        }
+        seen.push(key);
+        let when = |dt: Option<DateTime<Utc>>, unknown: Msg| dt.map(|dt| format_display(dt, tz)).unwrap_or_else(|| t(unknown).to_string());
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            when(session.start, Msg::SessionBeforeWindow),
+            when(session.end, Msg::SessionNoLogoff),
+            session.duration().map(format_duration).unwrap_or_default(),
+            session.state.text(),
+            format_logon_id(session.logon_id),
+            session.account,
+            session.logon_type.map(|l| l.describe()).unwrap_or_default(),
+            session.source,
+            session.process_count
+        )?;
+    }
+    Ok(())
+}
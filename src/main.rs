@@ -10,40 +10,160 @@
 // mod eventlog: イベントログ解析・出力用
 // mod event_types: イベントレコード型定義用
 // mod event_query: wevtutil呼び出し・イベント取得用
+// mod event_evtx: EVTXバイナリファイル解析用
+// mod event_source: イベントログ取得元（wevtutil・保存済みファイル）の抽象化用
 // mod event_parse: XMLパース・イベント解析用
-// mod event_filter: 除外判定・フィルタ用
+// mod event_decode: ログオンタイプ・%%nnnn 形式のメッセージID・整合性レベルSIDの表記への変換用
+// mod event_filter: 除外判定・フィルタ用（既定の除外フィルタ設定＋--filter、--profile で選択）
 // mod event_util: 共通ユーティリティ関数用
+// mod svchost: svchost.exe のコマンドライン（-k / -p / -s）解析用
+// mod masquerade: システムバイナリのなりすまし（想定外の場所・酷似した名前）検出用
+// mod boot_session: 起動（ブート）ごとの区間（終わり方・稼働時間）の判定・起動の選択用
+// mod process_tree: 起動ごとのプロセスツリー（ツリー表示・DOT出力・子孫での絞り込み）用
+// mod process_lifetime: プロセス作成・終了の組み合わせによる実行期間用
+// mod logon_session: ログオン・ログオフ・プロセス作成のログオンIDによる組み合わせ（ログオンセッション）用
+// mod brute_force: ログオン失敗のアカウント・接続元ごとの集計によるブルートフォース検出用
+// mod integrity: ログの消去・EventRecordID の欠落・監査ポリシーと時刻の変更の検出用
+// mod command_runner: 外部コマンド実行の抽象化用
+// mod event_output: 出力形式（テキスト・JSON・JSON Lines・CSV・プロセスツリー）用
+// mod event_html: HTMLタイムラインレポート用
+// mod output_sink: 出力先（ファイル・標準出力）用
+// mod cli: コマンドライン引数解析・ヘルプ表示用
+// mod time_window: 監査範囲（--since / --until / --last / 起動基準）の決定用
+// mod messages: 利用者向けメッセージ（日本語・英語）のカタログ用
+// mod event_catalog: 対象イベント・表示内容のカタログ（既定＋--catalog）用
 // use std::env: コマンドライン引数取得用
-// use init::precheck_and_info: 監査範囲・JST・監査ポリシー取得関数
+// use init::precheck_and_info: 監査範囲・監査ポリシー取得関数
 // =============================
 
 mod init;      // 事前チェック・システム情報
 mod eventlog;  // イベントログ解析
 mod event_types; // イベントレコード型定義
 mod event_query; // wevtutil呼び出し
+mod event_evtx;  // EVTXファイル解析
+mod event_source; // イベントログ取得元
 mod event_parse; // XMLパース
+mod event_decode; // コード値の表記
 mod event_filter; // 除外判定
 mod event_util;   // 共通関数
-use init::precheck_and_info; // 監査範囲・JST・監査ポリシー取得
+mod svchost;      // svchostコマンドライン解析
+mod masquerade;   // なりすまし検出
+mod boot_session; // 起動ごとの区間
+mod process_tree; // プロセスツリー
+mod process_lifetime; // プロセスの実行期間
+mod logon_session; // ログオンセッション
+mod brute_force;  // ブルートフォース検出
+mod integrity;    // ログの整合性
+mod command_runner; // 外部コマンド実行
+mod event_output; // 出力形式
+mod event_html;   // HTMLレポート
+mod output_sink;  // 出力先
+mod cli;          // コマンドライン引数解析
+mod time_window;  // 監査範囲の決定
+mod messages;     // メッセージカタログ
+mod event_catalog; // イベントカタログ
+use init::precheck_and_info; // 監査範囲・監査ポリシー取得
+use event_source::{MemorySource, WevtutilSource}; // イベントログ取得元
+use event_output::OutputOptions; // 出力オプション
+use cli::CliCommand; // コマンドライン解析結果
+use output_sink::OutputSink; // 出力先
 use std::env; // コマンドライン引数取得用
 
 fn main() {
-    // --- svchost出力レベルのコマンドライン引数解析 ---
-    let args: Vec<String> = env::args().collect(); // コマンドライン引数をベクタに格納
-    let mut svchost_level: u8 = 0; // svchost出力レベル（デフォルト: 全出力）
-    for arg in &args[1..] { // 1番目以降の引数を走査
-        match arg.as_str() { // 文字列としてマッチ
-            "-s0" => svchost_level = 0, // 全出力
-            "-s1" => svchost_level = 1, // 除外リスト方式
-            "-s2" => svchost_level = 2, // svchost.exe全除外
-            _ => {}, // その他は無視
+    // --- コマンドライン引数解析 ---
+    let args: Vec<String> = env::args().skip(1).collect(); // プログラム名を除いた引数
+    messages::set_lang(cli::peek_lang(&args).unwrap_or_else(messages::Lang::from_env)); // 表示言語（--lang 優先、なければ環境変数）
+    let opts = match cli::parse_args(&args) {
+        Ok(CliCommand::Run(opts)) => *opts, // 解析実行
+        Ok(CliCommand::Help) => {
+            print!("{}", cli::help_text()); // 使い方を表示
+            return;
         }
+        Ok(CliCommand::Version) => {
+            println!("{}", cli::version_text()); // バージョンを表示
+            return;
+        }
+        Err(e) => {
+            eprintln!("🛑 {}", e); // 引数エラー
+            std::process::exit(2);
+        }
+    };
+    // --- イベントカタログ（既定のカタログに --catalog の内容を追加・上書き） ---
+    let catalog = match event_catalog::EventCatalog::load(&opts.catalogs).and_then(|c| c.check_event_ids(&opts.event_ids).map(|_| c)) {
+        Ok(c) => c,
+        Err(e) => {
+            eprintln!("🛑 {}", e); // カタログ・--event-id の指定エラー
+            std::process::exit(2);
+        }
+    };
+    // --- 除外フィルタ設定（既定の設定に --filter の内容を追加・上書きし、--profile のプロファイルを選択） ---
+    let filter = match event_filter::FilterConfig::load(&opts.filters).and_then(|f| f.profile(opts.profile.as_deref()))
+        .and_then(|p| p.with_subtrees(&opts.hide_subtrees, &opts.only_subtrees))
+    {
+        Ok(f) => f,
+        Err(e) => {
+            eprintln!("🛑 {}", e); // 除外フィルタ設定・--profile の指定エラー
+            std::process::exit(2);
+        }
+    };
+    let options = OutputOptions { format: opts.format, csv_bom: opts.csv_bom }; // 出力オプション
+    let sink_for = |host: &str| OutputSink::from_spec(opts.output.as_deref(), opts.format.extension(), host, chrono::Local::now()); // 出力先
+    // --- オフライン解析（保存済みXML/EVTX）: 事前チェックは行わない ---
+    if !opts.input_files.is_empty() {
+        // 監査ポリシーは確認できないため、コマンドラインが記録されている前提で扱う
+        let source = MemorySource::from_files(&opts.input_files); // 保存済みファイルを読み込み
+        let sink = sink_for(&source.host_name().unwrap_or_else(|| output_sink::UNKNOWN_HOST.to_string())); // {host} はログの収集元
+        let latest = opts.window.last.and(source.latest_timestamp()); // --last の基準（入力内の最新のイベント）
+        if let (Some(latest), None) = (latest, opts.window.until) {
+            eprintln!("{}", messages::tr(messages::Msg::OfflineLastAnchor, &[&event_util::format_display(latest, opts.tz)])); // 基準の案内
+        }
+        let time_range = opts.window.resolve_offline(latest); // --since / --until / --last 指定時のみ範囲で絞り込む
+        let settings = eventlog::AnalysisSettings {
+            time_range,                      // 監査範囲（未指定なら入力ファイル内の全イベント）
+            offline: true,                   // オフライン解析
+            tz: opts.tz,                     // 表示タイムゾーン
+            cmdline_audit_enabled: true,     // コマンドライン記録あり扱い
+            filter,                          // 除外プロファイル
+            show_excluded: opts.show_excluded, // 除外されたイベントも出力するか
+            environment: Vec::new(),         // 環境チェックなし
+            event_ids: opts.event_ids,       // 出力対象のイベントID
+            catalog,                         // イベントカタログ
+            brute_force: opts.brute_force,   // ブルートフォース検出の条件
+            boot_selection: opts.boot_selection, // 出力する起動
+            system_root: None,               // ログから求める
+        };
+        if let Err(e) = eventlog::analyze_and_output_events(&settings, &source, options, &sink) {
+            eprintln!("🛑 {}", e);
+            std::process::exit(1);
+        }
+        return;
     }
     // --- 事前チェック・情報出力 ---
-    let (start_time, end_time, jst, cmdline_audit_enabled) = match precheck_and_info() {
-        Some(t) => t, // 正常取得時は値を展開
-        None => return, // エラー時は即終了
+    let info = match precheck_and_info(&command_runner::SystemRunner, &opts.window, opts.tz) {
+        Ok(t) => t, // 正常取得時は値を展開
+        Err(_) => std::process::exit(1), // エラー時は即終了（通知文は表示済み）
     };
     // --- イベントログ解析・出力 ---
-    eventlog::analyze_and_output_events(start_time, end_time, jst, cmdline_audit_enabled, svchost_level); // イベントログ解析・出力関数を呼び出し
+    let channels = catalog.channels(); // カタログに定義されたログ
+    let logs: Vec<&str> = channels.iter().map(|(log, _)| log.as_str()).collect(); // 取得対象のログ名
+    let source = WevtutilSource::new(&command_runner::SystemRunner, &logs); // wevtutilによるライブ取得
+    let sink = sink_for(&output_sink::local_host_name()); // {host} は実行中のホスト
+    let settings = eventlog::AnalysisSettings {
+        time_range: Some((info.start_time, info.end_time)), // 監査範囲
+        offline: false,                                    // ライブ取得
+        tz: opts.tz,                                       // 表示タイムゾーン
+        cmdline_audit_enabled: info.cmdline_audit_enabled, // コマンドライン監査有効フラグ
+        filter,                                            // 除外プロファイル
+        show_excluded: opts.show_excluded,                 // 除外されたイベントも出力するか
+        environment: info.messages,                        // 環境チェックの通知文
+        event_ids: opts.event_ids,                         // 出力対象のイベントID
+        catalog,                                           // イベントカタログ
+        brute_force: opts.brute_force,                     // ブルートフォース検出の条件
+        boot_selection: opts.boot_selection,               // 出力する起動
+        system_root: std::env::var("SystemRoot").ok().and_then(|v| masquerade::SystemRoot::live(&v)), // 実行中のシステムの %SystemRoot%
+    };
+    if let Err(e) = eventlog::analyze_and_output_events(&settings, &source, options, &sink) { // イベントログ解析・出力関数を呼び出し
+        eprintln!("🛑 {}", e);
+        std::process::exit(1);
+    }
 }
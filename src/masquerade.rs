@@ -0,0 +1,411 @@
+// --- 必要なクレートの説明 ---
+// このファイルはシステムバイナリのなりすまし（想定外の場所からの実行・酷似した名前）を検出するモジュールです
+// 検出結果はイベントの検出事項（Finding）として付与し、除外フィルタに一致しても出力します
+// crate::event_types: イベントレコード型・検出事項
+// crate::event_util: プロセスのパスの正規化
+// システムルートは実行中のシステムの %SystemRoot%、またはログの System が起動した smss.exe の配置先から決定します（除外ルールの照合にも使用）
+
+use crate::event_types::{EventRecord, Finding, FindingKind}; // イベントレコード型・検出事項
+use crate::event_util::{file_name_of, normalize_image_path_in, DEFAULT_SYSTEM_ROOT}; // パスの正規化
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+/// 代表的なシステムバイナリと、正規の配置先（システムルートからの相対ディレクトリ、"" はシステムルート直下）
+const SYSTEM_BINARIES: &[(&str, &[&str])] = &[
+    ("svchost.exe", &["system32", "syswow64"]),
+    ("lsass.exe", &["system32"]),
+    ("lsm.exe", &["system32"]),
+    ("csrss.exe", &["system32"]),
+    ("smss.exe", &["system32"]),
+    ("services.exe", &["system32"]),
+    ("wininit.exe", &["system32"]),
+    ("winlogon.exe", &["system32"]),
+    ("userinit.exe", &["system32"]),
+    ("explorer.exe", &["", "syswow64"]),
+    ("spoolsv.exe", &["system32"]),
+    ("taskhost.exe", &["system32"]),
+    ("taskhostex.exe", &["system32"]),
+    ("taskhostw.exe", &["system32"]),
+    ("dwm.exe", &["system32"]),
+    ("conhost.exe", &["system32"]),
+    ("dllhost.exe", &["system32", "syswow64"]),
+    ("rundll32.exe", &["system32", "syswow64"]),
+    ("runtimebroker.exe", &["system32"]),
+    ("sihost.exe", &["system32"]),
+    ("ctfmon.exe", &["system32", "syswow64"]),
+    ("fontdrvhost.exe", &["system32"]),
+    ("audiodg.exe", &["system32"]),
+    ("searchindexer.exe", &["system32"]),
+];
+
+/// 酷似した名前の判定対象とする名前（拡張子を除く）の最小文字数（短い名前は誤検出が多いため）
+const NEAR_MISS_MIN_LEN: usize = 5;
+
+/// システムルートの候補とするプロセス（System32 から実行されるため、配置先の親ディレクトリがシステムルートを示す）
+const ROOT_MARKERS: &[&str] = &["smss.exe", "services.exe"];
+
+/// システムルートの決定方法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RootSource {
+    Live,    // 実行中のシステムの %SystemRoot%（ライブ取得）
+    Log,     // ログに記録された、System が起動した smss.exe の配置先
+    Default, // 確実な手掛かりがないため既定値（C:\Windows）
+}
+
+/// なりすまし検出・除外ルールの照合に使うシステムルート
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemRoot {
+    pub path: String,           // システムルート（正規化済み、例: c:\windows）
+    pub source: RootSource,     // 決定方法
+    pub conflicts: Vec<String>, // 既定値を使った場合の、ログ内の別の候補（正規化済み、出現順）
+}
+
+impl Default for SystemRoot {
+    fn default() -> Self {
+        SystemRoot { path: DEFAULT_SYSTEM_ROOT.to_string(), source: RootSource::Default, conflicts: Vec::new() }
+    }
+}
+
+impl SystemRoot {
+    /// 実行中のシステムの %SystemRoot% の値から生成する（空ならNone）
+    pub fn live(value: &str) -> Option<SystemRoot> {
+        let path = normalize_image_path_in(value, DEFAULT_SYSTEM_ROOT).trim_end_matches('\\').to_string();
+        (!path.is_empty()).then_some(SystemRoot { path, source: RootSource::Live, conflicts: Vec::new() })
+    }
+
+    /// ログからシステムルートを求める
+    /// 攻撃者が任意の場所（C:\Temp\System32\services.exe など）から実行したプロセスで基準がずれないよう、
+    /// System（PID 4）が起動した smss.exe の配置先のみを手掛かりとする。手掛かりがない・複数ある場合は既定値とし、
+    /// smss.exe / services.exe の配置先が示す既定値以外の候補を conflicts に記録する（該当イベントに検出事項を付与）
+    pub fn from_events(events: &[EventRecord]) -> SystemRoot {
+        let mut trusted: Vec<String> = Vec::new(); // System が起動した smss.exe が示すシステムルート
+        let mut candidates: Vec<String> = Vec::new(); // smss.exe / services.exe の配置先が示すシステムルート
+        for event in events {
+            for (path, by_system) in [(event.image(), started_by_system(event)), (event.parent_image(), false)] {
+                let Some(root) = marker_root(path) else {
+                    continue;
+                };
+                if by_system && file_name_of(&normalize_image_path_in(path, "")) == "smss.exe" && !trusted.contains(&root) {
+                    trusted.push(root.clone());
+                }
+                if !candidates.contains(&root) {
+                    candidates.push(root);
+                }
+            }
+        }
+        if let [root] = trusted.as_slice() {
+            return SystemRoot { path: root.clone(), source: RootSource::Log, conflicts: Vec::new() };
+        }
+        SystemRoot { conflicts: candidates.into_iter().filter(|r| r != DEFAULT_SYSTEM_ROOT).collect(), ..SystemRoot::default() }
+    }
+
+    /// 表示用の文字列（例: D:\Winnt（ログの System が起動した smss.exe））
+    pub fn describe(&self) -> String {
+        let source = match self.source {
+            RootSource::Live => t(Msg::RootSourceLive),
+            RootSource::Log => t(Msg::RootSourceLog),
+            RootSource::Default => t(Msg::RootSourceDefault),
+        };
+        tr(Msg::SystemRootValue, &[&display_dir(&self.path), &source])
+    }
+}
+
+/// プロセス作成イベント（4688）で、作成元が System（PID 4）か
+fn started_by_system(event: &EventRecord) -> bool {
+    if event.new_process_id.is_none() {
+        return false; // プロセス作成以外
+    }
+    let parent = normalize_image_path_in(event.parent_image(), "");
+    match file_name_of(&parent) {
+        "" => event.parent_process_id == Some(4), // 作成元のパスが記録されていない場合はPIDで判定
+        name => name == "system",
+    }
+}
+
+/// smss.exe / services.exe の ...\system32 の親ディレクトリ（正規化済み、それ以外のパスはNone）
+/// \SystemRoot\ などの表記・デバイスパス（\Device\HarddiskVolume2\...）はドライブを示さないため対象外
+fn marker_root(path: &str) -> Option<String> {
+    let normalized = normalize_image_path_in(path, "");
+    if !ROOT_MARKERS.contains(&file_name_of(&normalized)) {
+        return None;
+    }
+    let (dir, _) = normalized.rsplit_once('\\')?;
+    let root = dir.strip_suffix("\\system32")?;
+    let drive = root.as_bytes();
+    (drive.len() > 3 && drive[0].is_ascii_alphabetic() && drive[1..3] == *b":\\").then(|| root.to_string())
+}
+
+/// イベントのプロセス・親プロセスについてなりすましの疑いを検出する関数
+pub fn check_event(event: &EventRecord, system_root: &SystemRoot) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    for (role, path) in [(Msg::RoleProcess, event.image()), (Msg::RoleParentProcess, event.parent_image())] {
+        if let Some(finding) = check_image(path, t(role), &system_root.path) {
+            findings.push(finding);
+        }
+        // 既定値を使っている場合、別のシステムルートを示すプロセスを報告
+        if let Some(root) = marker_root(path).filter(|r| system_root.conflicts.contains(r)) {
+            findings.push(Finding {
+                kind: FindingKind::SystemRootConflict,
+                message: tr(Msg::SystemRootConflict, &[&t(role), &path.trim(), &display_dir(&root), &display_dir(&system_root.path)]),
+            });
+        }
+    }
+    findings
+}
+
+/// 1つのプロセスのパスを検査する関数（問題がなければNone）
+fn check_image(path: &str, role: &str, system_root: &str) -> Option<Finding> {
+    if path.trim().is_empty() {
+        return None;
+    }
+    let normalized = normalize_image_path_in(path, system_root);
+    let name = file_name_of(&normalized);
+    if let Some((known, dirs)) = SYSTEM_BINARIES.iter().find(|(known, _)| *known == name) {
+        // システムバイナリと同じ名前: 配置先を確認（ディレクトリのないパスは判定しない）
+        let dir = normalized.strip_suffix(name).map(|d| d.trim_end_matches('\\')).unwrap_or("");
+        let dirs: Vec<String> = dirs.iter().map(|d| system_dir(system_root, d)).collect();
+        if dir.is_empty() || dirs.iter().any(|d| d == dir) {
+            return None;
+        }
+        let expected: Vec<String> = dirs.iter().map(|d| display_dir(d)).collect();
+        return Some(Finding {
+            kind: FindingKind::MasqueradePath,
+            message: tr(Msg::MasqueradePath, &[&role, known, &path.trim(), &expected.join(", ")]),
+        });
+    }
+    // システムバイナリに酷似した名前（svch0st.exe, scvhost.exe など）
+    let stem = name.rsplit_once('.').map(|(stem, _)| stem).unwrap_or(name); // 拡張子を除く（svchost.com なども対象）
+    let folded = fold_homoglyphs(stem);
+    let similar = SYSTEM_BINARIES.iter().map(|(known, _)| *known).find(|known| {
+        let known_stem = known.strip_suffix(".exe").unwrap_or(known);
+        known_stem.chars().count() >= NEAR_MISS_MIN_LEN && (folded == known_stem || edit_distance(&folded, known_stem) <= 1)
+    })?;
+    Some(Finding {
+        kind: FindingKind::MasqueradeName,
+        message: tr(Msg::MasqueradeName, &[&role, &path.trim(), &similar]),
+    })
+}
+
+/// システムルートと相対ディレクトリを連結する関数（"" はシステムルートそのもの）
+fn system_dir(system_root: &str, relative: &str) -> String {
+    if relative.is_empty() { system_root.to_string() } else { format!("{}\\{}", system_root, relative) }
+}
+
+/// 正規化済みのディレクトリを表示用に整形する関数（c:\windows\system32 → C:\Windows\System32）
+fn display_dir(dir: &str) -> String {
+    dir.split('\\')
+        .map(|part| match part {
+            "syswow64" => "SysWOW64".to_string(),
+            _ => {
+                let mut chars = part.chars();
+                chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\\")
+}
+
+/// 見た目の似た文字を置き換える関数（0→o, 1→l, 5→s, rn→m, キリル文字のа→a など）
+fn fold_homoglyphs(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        out.push(match c {
+            '0' | 'о' => 'o',
+            '1' | '|' | 'і' => 'l',
+            '3' | 'е' => 'e',
+            '5' | '$' | 'ѕ' => 's',
+            '@' | 'а' => 'a',
+            'с' => 'c',
+            'р' => 'p',
+            'х' => 'x',
+            _ => c,
+        });
+    }
+    out.replace("rn", "m").replace("vv", "w")
+}
+
+/// 2つの文字列の編集距離（挿入・削除・置換・隣接文字の入れ替えを1回と数える）
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let mut d = vec![vec![0usize; b.len() + 1]; a.len() + 1];
+    for (i, row) in d.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    d[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let cost = usize::from(a[i - 1] != b[j - 1]);
+            d[i][j] = (d[i - 1][j] + 1).min(d[i][j - 1] + 1).min(d[i - 1][j - 1] + cost);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                d[i][j] = d[i][j].min(d[i - 2][j - 2] + 1); // 隣接文字の入れ替え（scvhost など）
+            }
+        }
+    }
+    d[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kind_of(path: &str, system_root: &str) -> Option<FindingKind> {
+        check_image(path, "process", system_root).map(|f| f.kind)
+    }
+
+    #[test]
+    fn system_binaries_in_place_are_not_flagged() {
+        for path in [
+            r"C:\Windows\System32\svchost.exe",
+            r"c:\windows\syswow64\svchost.exe",
+            r"\SystemRoot\System32\smss.exe",
+            r"%windir%\explorer.exe",
+            r"\??\C:\Windows\system32\csrss.exe",
+            r#""C:\Windows\System32\lsass.exe""#,
+            r"C:\Windows\System32\..\System32\services.exe",
+            "svchost.exe", // ディレクトリのないパスは判定しない
+            "",
+        ] {
+            assert_eq!(kind_of(path, DEFAULT_SYSTEM_ROOT), None, "{}", path);
+        }
+    }
+
+    #[test]
+    fn system_binaries_elsewhere_are_flagged() {
+        for path in [
+            r"C:\Temp\svchost.exe",
+            r"C:\Users\alice\AppData\Local\Temp\lsass.exe",
+            r"C:\Windows\svchost.exe",
+            r"C:\Windows\System32\explorer.exe",
+            r"C:\Windows\System32\..\..\Temp\svchost.exe",
+            r"C:\Windows\System32\..\svchost.exe",
+            r"C:\Windows\System32\.\drivers\..\..\Temp\services.exe",
+            r"D:\Windows\System32\svchost.exe",
+        ] {
+            assert_eq!(kind_of(path, DEFAULT_SYSTEM_ROOT), Some(FindingKind::MasqueradePath), "{}", path);
+        }
+    }
+
+    #[test]
+    fn path_finding_names_the_expected_directories() {
+        let finding = check_image(r"C:\Temp\svchost.exe", "process", DEFAULT_SYSTEM_ROOT).unwrap();
+        assert_eq!(
+            finding.message,
+            tr(Msg::MasqueradePath, &[&"process", &"svchost.exe", &r"C:\Temp\svchost.exe", &r"C:\Windows\System32, C:\Windows\SysWOW64"])
+        );
+    }
+
+    #[test]
+    fn near_miss_names_are_flagged() {
+        for (path, similar) in [
+            (r"C:\Windows\System32\svch0st.exe", "svchost.exe"),
+            (r"C:\Windows\System32\scvhost.exe", "svchost.exe"),
+            (r"C:\Temp\svhost.exe", "svchost.exe"),
+            (r"C:\Temp\svchosts.exe", "svchost.exe"),
+            (r"C:\Temp\svchost.com", "svchost.exe"),
+            (r"C:\Temp\lsas.exe", "lsass.exe"),
+            (r"C:\Temp\expl0rer.exe", "explorer.exe"),
+            (r"C:\Temp\winIogon.exe", "winlogon.exe"),
+            ("C:\\Temp\\svсhost.exe", "svchost.exe"), // キリル文字の с
+        ] {
+            let finding = check_image(path, "process", DEFAULT_SYSTEM_ROOT);
+            assert_eq!(
+                finding.map(|f| (f.kind, f.message)),
+                Some((FindingKind::MasqueradeName, tr(Msg::MasqueradeName, &[&"process", &path, &similar]))),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn unrelated_and_short_names_are_not_flagged() {
+        for path in [
+            r"C:\Windows\System32\cmd.exe",
+            r"C:\Windows\System32\notepad.exe",
+            r"C:\Program Files\App\svc.exe",
+            r"C:\Windows\System32\dwn.exe", // 短い名前（dwm）は対象外
+            r"C:\Windows\System32\svchost.exe.bak",
+        ] {
+            assert_eq!(kind_of(path, DEFAULT_SYSTEM_ROOT), None, "{}", path);
+        }
+    }
+
+    /// プロセス作成イベント（4688）
+    fn created(record_id: u32, image: &str, parent: &str, parent_pid: &str) -> String {
+        format!(
+            "<Event xmlns='http://schemas.microsoft.com/win/2004/08/events/event'><System><Provider Name='Microsoft-Windows-Security-Auditing'/>\
+             <EventID>4688</EventID><TimeCreated SystemTime='2025-07-10T06:00:{:02}.000Z'/><EventRecordID>{}</EventRecordID>\
+             <Channel>Security</Channel><Computer>HOST1</Computer></System><EventData>\
+             <Data Name='NewProcessId'>0x{:x}</Data><Data Name='NewProcessName'>{}</Data>\
+             <Data Name='ProcessId'>{}</Data><Data Name='ParentProcessName'>{}</Data></EventData></Event>\r\n",
+            record_id, record_id, 0x100 + record_id, image, parent_pid, parent
+        )
+    }
+
+    fn events(xml: &[String]) -> Vec<EventRecord> {
+        crate::event_parse::parse_events(&xml.concat(), &[4688]).events
+    }
+
+    #[test]
+    fn system_root_is_taken_from_smss_started_by_system() {
+        let log = events(&[
+            created(1, r"D:\WINNT\System32\smss.exe", "", "0x4"),
+            created(2, r"D:\WINNT\System32\services.exe", r"D:\WINNT\System32\wininit.exe", "0x1f0"),
+        ]);
+        let root = SystemRoot::from_events(&log);
+        assert_eq!((root.path.as_str(), root.source), (r"d:\winnt", RootSource::Log));
+        assert!(root.conflicts.is_empty());
+        assert!(log.iter().all(|e| check_event(e, &root).is_empty()));
+        // 作成元が System と記録されている場合も同様
+        let log = events(&[created(1, r"\Device\HarddiskVolume2\WINNT\System32\smss.exe", "System", "0x4")]);
+        assert_eq!(SystemRoot::from_events(&log).source, RootSource::Default); // デバイスパスはシステムルートを示さない
+        let log = events(&[created(1, r"E:\Windows\System32\smss.exe", "System", "0x8")]);
+        assert_eq!(SystemRoot::from_events(&log).path, r"e:\windows");
+    }
+
+    #[test]
+    fn processes_run_from_elsewhere_do_not_move_the_system_root() {
+        let log = events(&[
+            created(1, r"C:\Temp\System32\services.exe", r"C:\Temp\dropper.exe", "0x1f0"),
+            created(2, r"C:\Temp\System32\services.exe", r"C:\Temp\dropper.exe", "0x1f0"),
+            created(3, r"C:\Temp\System32\smss.exe", r"C:\Temp\dropper.exe", "0x1f0"), // System 以外が起動した smss.exe
+            created(4, r"C:\Windows\System32\svchost.exe", r"C:\Windows\System32\services.exe", "0x2a0"),
+        ]);
+        let root = SystemRoot::from_events(&log);
+        assert_eq!(root, SystemRoot { conflicts: vec![r"c:\temp".to_string()], ..SystemRoot::default() });
+        let findings = check_event(&log[0], &root);
+        assert_eq!(findings.iter().map(|f| f.kind).collect::<Vec<_>>(), [FindingKind::MasqueradePath, FindingKind::SystemRootConflict]);
+        assert_eq!(
+            findings[1].message,
+            tr(Msg::SystemRootConflict, &[&t(Msg::RoleProcess), &r"C:\Temp\System32\services.exe", &r"C:\Temp", &r"C:\Windows"])
+        );
+        assert!(check_event(&log[3], &root).is_empty());
+    }
+
+    #[test]
+    fn conflicting_trusted_roots_fall_back_to_the_default() {
+        let log = events(&[created(1, r"E:\Windows\System32\smss.exe", "", "0x4"), created(2, r"D:\WINNT\System32\smss.exe", "", "0x4")]);
+        let root = SystemRoot::from_events(&log);
+        assert_eq!((root.path.as_str(), root.source), (DEFAULT_SYSTEM_ROOT, RootSource::Default));
+        assert_eq!(root.conflicts, [r"e:\windows", r"d:\winnt"]);
+        assert_eq!(SystemRoot::from_events(&[]), SystemRoot::default());
+    }
+
+    #[test]
+    fn live_system_root_is_normalized() {
+        let root = SystemRoot::live(r"D:\WINNT\").unwrap();
+        assert_eq!((root.path.as_str(), root.source), (r"d:\winnt", RootSource::Live));
+        assert_eq!(SystemRoot::live(" "), None);
+    }
+
+    #[test]
+    fn checks_follow_the_detected_system_root() {
+        let root = r"d:\winnt";
+        assert_eq!(kind_of(r"D:\WINNT\System32\svchost.exe", root), None);
+        assert_eq!(kind_of(r"D:\WINNT\explorer.exe", root), None);
+        assert_eq!(kind_of(r"%SystemRoot%\System32\lsass.exe", root), None);
+        assert_eq!(kind_of(r"C:\Windows\System32\svchost.exe", root), Some(FindingKind::MasqueradePath));
+        let finding = check_image(r"C:\Windows\System32\lsass.exe", "process", root).unwrap();
+        assert_eq!(finding.message, tr(Msg::MasqueradePath, &[&"process", &"lsass.exe", &r"C:\Windows\System32\lsass.exe", &r"D:\Winnt\System32"]));
+    }
+}
@@ -0,0 +1,880 @@
+// --- 必要なクレートの説明 ---
+// このファイルは利用者向けメッセージ（日本語・英語）のカタログをまとめるモジュールです
+// 表示言語は --lang または環境変数（WINBOOTEVENTCHECK_LANG / LC_ALL / LC_MESSAGES / LANG）で選択します
+// すべてのメッセージは Msg の網羅的な match で定義するため、どちらかの言語が欠けるとコンパイルエラーになります
+// std::sync::OnceLock: 実行中の表示言語の保持用
+
+use std::sync::OnceLock; // 表示言語（プロセス全体で1回だけ設定）
+
+/// 表示言語
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Lang {
+    #[default]
+    Ja, // 日本語（既定）
+    En, // 英語
+}
+
+impl Lang {
+    /// 言語コード（ja / en、ja_JP.UTF-8 や en-US などの地域付き表記も可）から表示言語を決定する
+    pub fn parse(s: &str) -> Option<Lang> {
+        let code = s.trim().to_ascii_lowercase(); // 小文字化
+        let primary = code.split(['_', '-', '.']).next().unwrap_or(""); // 言語部分
+        match primary {
+            "ja" | "jp" | "japanese" => Some(Lang::Ja),
+            "en" | "english" | "c" | "posix" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// 環境変数から表示言語を決定する（未設定・不明な場合は日本語）
+    pub fn from_env() -> Lang {
+        ["WINBOOTEVENTCHECK_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|k| std::env::var(k).ok())
+            .find(|v| !v.is_empty())
+            .and_then(|v| Lang::parse(&v))
+            .unwrap_or_default()
+    }
+
+    /// 言語コード（HTMLのlang属性などに使用）
+    pub fn code(&self) -> &'static str {
+        match self {
+            Lang::Ja => "ja",
+            Lang::En => "en",
+        }
+    }
+}
+
+/// 実行中の表示言語
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+/// 表示言語を設定する関数（起動時に1回だけ呼ぶ。2回目以降は無視）
+pub fn set_lang(lang: Lang) {
+    let _ = LANG.set(lang);
+}
+
+/// 現在の表示言語を返す関数（未設定なら環境変数から決定）
+pub fn lang() -> Lang {
+    *LANG.get_or_init(Lang::from_env)
+}
+
+/// メッセージキー
+/// 文中の {} は tr() に渡した引数で先頭から順に置き換える
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Msg {
+    // --- 環境チェック（init.rs） ---
+    AdminRequired,
+    LogEnabled,
+    LogDisabled,
+    EnableLogHint,
+    AppStarted,
+    Uptime,
+    UptimeFailed,
+    UptimeParseFailed,
+    AuditRangeNotice,
+    RangeExplicit,
+    RangeBootRelative,
+    SecurityAuditDisabled,
+    SecurityAuditEnabled,
+    SecurityAuditUnknown,
+    CmdlineAuditOff,
+    // --- 監査範囲（time_window.rs） ---
+    BootTimeUnavailable,
+    RangeInverted,
+    // --- 解析・出力（eventlog.rs） ---
+    SkipUnsupportedLog,
+    FetchFailed,
+    RangeFormat,
+    RangeAllTime,
+    OfflineScopeRange,
+    OfflineScopeAll,
+    OfflineRange,
+    OfflineNoEvents,
+    OfflineLastAnchor,
+    WriteFailed,
+    WroteOutput,
+    WroteMetadata,
+    // --- レポートヘッダ（event_output.rs / event_html.rs） ---
+    HeaderAuditRange,
+    HeaderCmdlineAudit,
+    HeaderTimeZone,
+    HeaderFilterProfile,
+    HeaderShowExcluded,
+    ExcludedMarker,
+    FindingMarker,
+    DecodedMarker,
+    ServiceMarker,
+    TreeProcessLabel,
+    TreeBootHeading,
+    TreeBeforeFirstBoot,
+    TreeOutOfRange,
+    TreeOutOfRangeShort,
+    LifetimeStartMarker,
+    LifetimeStartUnknown,
+    LifetimeEndMarker,
+    LifetimeColumns,
+    LifetimeBeforeWindow,
+    LifetimeNoEnd,
+    LifetimeExited,
+    LifetimeRunning,
+    LifetimeRebooted,
+    LifetimePidReused,
+    LogonSourceMarker,
+    SessionLogoffMarker,
+    SessionOpenMarker,
+    SessionLogonMarker,
+    SessionLogonUnknown,
+    SessionProcessMarker,
+    SessionColumns,
+    SessionBeforeWindow,
+    SessionNoLogoff,
+    SessionLoggedOff,
+    SessionActive,
+    SessionRebooted,
+    BootMark,
+    BootMarkSince,
+    BootEndClean,
+    BootEndDirty,
+    BootEndRunning,
+    BootInitiator,
+    BootColumns,
+    BootNoEnd,
+    BootSelectionOne,
+    BootSelectionRange,
+    BootSelectionFirst,
+    BootSelectionLast,
+    HeaderBootSelection,
+    BootSelectionInvalid,
+    BootSelectionConflict,
+    BootSelectionLiveRange,
+    CmdlineAuditStateOn,
+    CmdlineAuditStateOff,
+    HtmlTitle,
+    HtmlHeading,
+    HtmlAuditRange,
+    HtmlTzAndCount,
+    HtmlCmdlineWarn,
+    HtmlFilterIds,
+    HtmlFilterUser,
+    HtmlSvchostGroup,
+    ColDateTime,
+    ColLog,
+    ColUser,
+    ColEventId,
+    ColAction,
+    ColDescription,
+    ColService,
+    ColDetail,
+    ColBoot,
+    CatBoot,
+    CatShutdown,
+    CatUnexpected,
+    CatLogon,
+    CatLogonFailure,
+    CatProcess,
+    CatCleared,
+    CatAuditChange,
+    CatOther,
+    // --- 出力先・入力ファイル（output_sink.rs / event_query.rs / event_evtx.rs / command_runner.rs） ---
+    Stdout,
+    CreateDirFailed,
+    CreateFileFailed,
+    CommandFailed,
+    CommandSpawnFailed,
+    FileReadFailed,
+    DirReadFailed,
+    EvtxParseFailed,
+    EvtxBadHeader,
+    EvtxUnsupportedVersion,
+    EvtxOutOfBounds,
+    EvtxEndOfData,
+    EvtxTooDeep,
+    EvtxTooLarge,
+    EvtxUnexpectedToken,
+    EvtxBadElementEnd,
+    EvtxUnsupportedValueType,
+    EvtxUnexpectedContent,
+    EventMalformed,
+    EventFieldInvalid,
+    XmlSyntaxError,
+    // --- イベントカタログ（event_catalog.rs） ---
+    CatalogBuiltin,
+    CatalogReadFailed,
+    CatalogInvalid,
+    CatalogEntryInvalid,
+    CatalogEmpty,
+    CatalogChannelEmpty,
+    CatalogCategoryInvalid,
+    CatalogTextMissing,
+    CatalogDuplicate,
+    CatalogTemplateUnclosed,
+    CatalogTemplateEmptyField,
+    CatalogTemplateStrayBrace,
+    // --- なりすまし検出（masquerade.rs） ---
+    RoleProcess,
+    RoleParentProcess,
+    MasqueradePath,
+    MasqueradeName,
+    SystemRootConflict,
+    RootSourceLive,
+    RootSourceLog,
+    RootSourceDefault,
+    SystemRootValue,
+    HeaderSystemRoot,
+    BruteForceAccount,
+    BruteForceSource,
+    BruteForceThenSuccess,
+    BruteForceNoSuccess,
+    BruteForceSuccessAccount,
+    BruteForceSuccessSource,
+    BruteForceMore,
+    BruteForceThresholdInvalid,
+    IntegrityHigh,
+    IntegrityMedium,
+    IntegrityLogCleared,
+    IntegrityServiceStopped,
+    IntegrityAuditPolicy,
+    IntegrityTimeChange,
+    IntegrityTimeBackward,
+    IntegrityTimeForward,
+    IntegrityRecordGap,
+    IntegrityRecordReset,
+    IntegrityRecordResetCleared,
+    // --- 除外フィルタ設定（event_filter.rs） ---
+    FilterBuiltin,
+    FilterReadFailed,
+    FilterInvalid,
+    FilterRuleInvalid,
+    FilterProfileNameEmpty,
+    FilterProfileDuplicate,
+    FilterProfileUnknown,
+    FilterProfileCycle,
+    FilterRuleNameEmpty,
+    FilterRuleNoCondition,
+    FilterActionInvalid,
+    FilterRegexInvalid,
+    // --- コマンドライン（cli.rs） ---
+    HelpText,
+    ValueRequired,
+    FlagTakesNoValue,
+    SvchostLevelInvalid,
+    ProfileConflict,
+    FormatInvalid,
+    FormatConflict,
+    OutputRequired,
+    OutputConflict,
+    TimestampInvalid,
+    DurationInvalid,
+    TzInvalid,
+    LangInvalid,
+    EventIdUnsupported,
+    EventIdInvalid,
+    InputFileSpecInvalid,
+    UnknownOption,
+    UnknownArgument,
+    InputDirEmpty,
+    BomRequiresCsv,
+    SinceLastConflict,
+    BootFlagsConflict,
+    BootFlagsOffline,
+    SinceAfterUntil,
+}
+
+impl Msg {
+    /// メッセージ本文（日本語, 英語）
+    fn texts(self) -> (&'static str, &'static str) {
+        match self {
+            // --- 環境チェック ---
+            Msg::AdminRequired => (
+                "🛑 このアプリは管理者権限で実行してください。",
+                "🛑 Please run this tool with administrator privileges.",
+            ),
+            Msg::LogEnabled => ("✅ {} ログは記録有効です。", "✅ The {} log is enabled."),
+            Msg::LogDisabled => ("⚠️ {} ログは記録が無効です。", "⚠️ The {} log is disabled."),
+            Msg::EnableLogHint => (
+                "⚠️ 記録が無効なログはイベントビューアーでプロパティから「ログの有効化」にチェックを入れてください。",
+                "⚠️ To enable a disabled log, open its Properties in Event Viewer and check \"Enable logging\".",
+            ),
+            Msg::AppStarted => ("ℹ️ アプリ起動日時: {} [{}]", "ℹ️ Started at: {} [{}]"),
+            Msg::Uptime => ("ℹ️ システム稼働時間: {}日 {}時間 {}分 {}秒", "ℹ️ System uptime: {}d {}h {}m {}s"),
+            Msg::UptimeFailed => ("⚠️ システム稼働時間: 取得失敗", "⚠️ System uptime: unavailable"),
+            Msg::UptimeParseFailed => ("⚠️ システム稼働時間: 取得失敗 (生データ: {})", "⚠️ System uptime: unavailable (raw value: {})"),
+            Msg::AuditRangeNotice => ("ℹ️ イベント監査対象日時: {} ～ {}（{}）", "ℹ️ Audit window: {} - {} ({})"),
+            Msg::RangeExplicit => ("指定範囲", "user-specified range"),
+            Msg::RangeBootRelative => ("最終起動の{}前 ～ 起動後{}", "from {} before to {} after the last boot"),
+            Msg::SecurityAuditDisabled => (
+                "⚠️ Security監査が無効です。ログ改ざん検知が機能しません！",
+                "⚠️ Security auditing is disabled. Log tampering cannot be detected!",
+            ),
+            Msg::SecurityAuditEnabled => (
+                "✅ Security監査は有効です。ログを分析可能です！",
+                "✅ Security auditing is enabled. The logs can be analysed.",
+            ),
+            Msg::SecurityAuditUnknown => (
+                "⚠️ Security監査の状態を取得できませんでした。",
+                "⚠️ Could not determine the Security audit status.",
+            ),
+            Msg::CmdlineAuditOff => (
+                "⚠️ プロセス作成イベントにコマンドラインを含める監査ポリシーが未構成または無効です。svchost.exeの挙動詳細は出力できません。",
+                "⚠️ The audit policy that includes command lines in process creation events is not configured or disabled. svchost.exe details cannot be reported.",
+            ),
+            // --- 監査範囲 ---
+            Msg::BootTimeUnavailable => (
+                "最終起動時刻を取得できないため監査範囲を決定できません。--since または --last で範囲を指定してください",
+                "Cannot determine the audit window because the last boot time is unavailable. Specify a range with --since or --last",
+            ),
+            Msg::RangeInverted => (
+                "監査開始時刻が終了時刻より後になっています。--since / --until / --last の指定を確認してください",
+                "The audit start is later than the audit end. Check --since / --until / --last",
+            ),
+            // --- 解析・出力 ---
+            Msg::SkipUnsupportedLog => ("⚠️ 対象外のログのためスキップします: {}", "⚠️ Skipping unsupported log: {}"),
+            Msg::FetchFailed => ("⚠️ {} ログの取得に失敗しました: {}", "⚠️ Failed to read the {} log: {}"),
+            Msg::RangeFormat => ("{} ～ {}", "{} - {}"),
+            Msg::RangeAllTime => ("全期間", "all time"),
+            Msg::OfflineScopeRange => ("指定範囲内のイベント", "events within the specified range"),
+            Msg::OfflineScopeAll => ("入力ファイル内の全イベント", "all events in the input files"),
+            Msg::OfflineRange => ("{} ～ {}（オフライン解析: {}）", "{} - {} (offline analysis: {})"),
+            Msg::OfflineNoEvents => ("該当イベントなし（オフライン解析）", "no matching events (offline analysis)"),
+            Msg::OfflineLastAnchor => (
+                "ℹ️ --last は入力ファイル内の最新のイベント（{}）から遡ります",
+                "ℹ️ --last counts back from the newest event in the input ({})",
+            ),
+            Msg::WriteFailed => ("書き込み失敗: {}: {}", "Write failed: {}: {}"),
+            Msg::WroteOutput => ("ℹ️ {} に出力しました", "ℹ️ Report written to {}"),
+            Msg::WroteMetadata => ("ℹ️ {} にメタ情報を出力しました", "ℹ️ Metadata written to {}"),
+            // --- レポートヘッダ ---
+            Msg::HeaderAuditRange => (">>> イベント監査対象日時: {}", ">>> Audit window: {}"),
+            Msg::HeaderCmdlineAudit => (">>> コマンドライン監査: {}", ">>> Command-line auditing: {}"),
+            Msg::HeaderTimeZone => (">>> 表示タイムゾーン: {}", ">>> Display time zone: {}"),
+            Msg::HeaderBootSelection => (">>> 対象の起動: {}（該当 {} 件）", ">>> Selected boots: {} ({} matched)"),
+            Msg::HeaderFilterProfile => (">>> 除外プロファイル: {}（{}）", ">>> Filter profile: {} ({})"),
+            Msg::HeaderShowExcluded => (
+                ">>> 除外されたイベントも [除外: ルール名] を付けて出力しています（--show-excluded）",
+                ">>> Excluded events are included and marked [excluded: rule] (--show-excluded)",
+            ),
+            Msg::ExcludedMarker => ("[除外: {}]", "[excluded: {}]"),
+            Msg::FindingMarker => ("[⚠ {}]", "[⚠ {}]"),
+            Msg::DecodedMarker => ("[値: {}]", "[decoded: {}]"),
+            Msg::ServiceMarker => ("[サービス: {}]", "[Service: {}]"),
+            Msg::TreeProcessLabel => ("{} (PID {})", "{} (PID {})"),
+            Msg::TreeBootHeading => ("=== 起動 #{}（{}） ===", "=== boot #{} ({}) ==="),
+            Msg::TreeBeforeFirstBoot => ("=== 最初の起動イベントより前 ===", "=== before the first boot event ==="),
+            Msg::TreeOutOfRange => ("{}（作成イベントは出力対象外）", "{} (creation not in output)"),
+            Msg::TreeOutOfRangeShort => ("作成イベントは出力対象外", "creation not in output"),
+            Msg::LifetimeStartMarker => ("[開始: {} 実行時間: {}]", "[started: {} ran for: {}]"),
+            Msg::LifetimeStartUnknown => ("[開始: 監査範囲外]", "[started: before the audit window]"),
+            Msg::LifetimeEndMarker => ("[終了: {} 実行時間: {} 終了コード: {}]", "[exited: {} ran for: {} exit status: {}]"),
+            Msg::LifetimeColumns => (
+                "開始\t終了\t実行時間\t終了コード\t状態\tPID\tプロセス",
+                "start\tend\tduration\texit status\tstate\tPID\tprocess",
+            ),
+            Msg::LifetimeBeforeWindow => ("（監査範囲外）", "(before the window)"),
+            Msg::LifetimeNoEnd => ("-", "-"),
+            Msg::LifetimeExited => ("終了", "exited"),
+            Msg::LifetimeRunning => ("監査範囲の終了時点で実行中", "still running at the end of the window"),
+            Msg::LifetimeRebooted => ("終了イベントなし（再起動）", "no exit event (rebooted)"),
+            Msg::LifetimePidReused => ("終了イベントなし（プロセスIDが再利用）", "no exit event (PID reused)"),
+            Msg::LogonSourceMarker => ("[接続元: {}]", "[source: {}]"),
+            Msg::SessionLogoffMarker => ("[ログオンID: {} ログオフ: {} 継続時間: {}]", "[logon ID: {} logged off: {} duration: {}]"),
+            Msg::SessionOpenMarker => ("[ログオンID: {} {}]", "[logon ID: {} {}]"),
+            Msg::SessionLogonMarker => ("[ログオンID: {} ログオン: {} 継続時間: {}]", "[logon ID: {} logged on: {} duration: {}]"),
+            Msg::SessionLogonUnknown => ("[ログオンID: {} ログオン: 監査範囲外]", "[logon ID: {} logged on: before the audit window]"),
+            Msg::SessionProcessMarker => ("[セッション: {} ログオンID: {}]", "[session: {} logon ID: {}]"),
+            Msg::SessionColumns => (
+                "ログオン\tログオフ\t継続時間\t状態\tログオンID\tアカウント\tログオンタイプ\t接続元\tプロセス数",
+                "logon\tlogoff\tduration\tstate\tlogon ID\taccount\tlogon type\tsource\tprocesses",
+            ),
+            Msg::SessionBeforeWindow => ("（監査範囲外）", "(before the window)"),
+            Msg::SessionNoLogoff => ("-", "-"),
+            Msg::SessionLoggedOff => ("ログオフ", "logged off"),
+            Msg::SessionActive => ("監査範囲の終了時点でログオン中", "still logged on at the end of the window"),
+            Msg::SessionRebooted => ("ログオフイベントなし（再起動）", "no logoff event (rebooted)"),
+            // --- 起動ごとの区間 ---
+            Msg::BootMark => ("boot #{}", "boot #{}"),
+            Msg::BootMarkSince => ("boot #{} T+{}s", "boot #{} T+{}s"),
+            Msg::BootEndClean => ("正常終了", "clean"),
+            Msg::BootEndDirty => ("予期しない終了", "dirty"),
+            Msg::BootEndRunning => ("稼働中", "running"),
+            Msg::BootInitiator => ("{}（{}、{}）", "{} ({}, {})"),
+            Msg::BootColumns => (
+                "起動\t起動日時\t終了日時\t終了の状態\t稼働時間\tシャットダウンの実行者\tイベント数",
+                "boot\tstart\tend\tend state\tuptime\tshutdown initiator\tevents",
+            ),
+            Msg::BootNoEnd => ("（監査範囲の終了時点で稼働中）", "(running at the end of the window)"),
+            Msg::BootSelectionOne => ("起動 #{}", "boot #{}"),
+            Msg::BootSelectionRange => ("起動 #{} ～ #{}", "boots #{} - #{}"),
+            Msg::BootSelectionFirst => ("最初の {} 回の起動", "the first {} boots"),
+            Msg::BootSelectionLast => ("直近 {} 回の起動", "the last {} boots"),
+            Msg::CmdlineAuditStateOn => ("有効", "enabled"),
+            Msg::CmdlineAuditStateOff => ("無効または未構成", "disabled or not configured"),
+            Msg::HtmlTitle => ("WinBootEventCheck レポート", "WinBootEventCheck report"),
+            Msg::HtmlHeading => ("WinBootEventCheck 起動イベントレポート", "WinBootEventCheck boot event report"),
+            Msg::HtmlAuditRange => ("イベント監査対象日時: {}", "Audit window: {}"),
+            Msg::HtmlTzAndCount => ("表示タイムゾーン: {} ／ 出力イベント数: {}", "Display time zone: {} / Events: {}"),
+            Msg::HtmlCmdlineWarn => (
+                "プロセス作成イベントのコマンドライン監査が無効または未構成です。",
+                "Command-line auditing for process creation events is disabled or not configured.",
+            ),
+            Msg::HtmlFilterIds => ("イベントID（カンマ区切り）", "Event IDs (comma separated)"),
+            Msg::HtmlFilterUser => ("ユーザー", "User"),
+            Msg::HtmlSvchostGroup => (
+                "▶ svchost.exe 関連イベント {} 件（クリックで展開/折りたたみ）",
+                "▶ {} svchost.exe events (click to expand/collapse)",
+            ),
+            Msg::ColDateTime => ("日時", "Date/time"),
+            Msg::ColLog => ("ログ", "Log"),
+            Msg::ColUser => ("ユーザー", "User"),
+            Msg::ColEventId => ("イベントID", "Event ID"),
+            Msg::ColAction => ("アクション", "Action"),
+            Msg::ColDescription => ("説明", "Description"),
+            Msg::ColService => ("サービス", "Service"),
+            Msg::ColDetail => ("プロセス/詳細", "Process / details"),
+            Msg::ColBoot => ("起動", "Boot"),
+            Msg::CatBoot => ("起動", "Boot"),
+            Msg::CatShutdown => ("シャットダウン", "Shutdown"),
+            Msg::CatUnexpected => ("予期しないシャットダウン", "Unexpected shutdown"),
+            Msg::CatLogon => ("ログオン/ログオフ", "Logon/logoff"),
+            Msg::CatLogonFailure => ("ログオン失敗", "Logon failure"),
+            Msg::CatProcess => ("プロセス・アプリ", "Processes/apps"),
+            Msg::CatCleared => ("ログ消去・停止", "Log cleared/stopped"),
+            Msg::CatAuditChange => ("監査ポリシー・時刻の変更", "Audit policy/time change"),
+            Msg::CatOther => ("その他", "Other"),
+            // --- 出力先・入力ファイル ---
+            Msg::Stdout => ("標準出力", "standard output"),
+            Msg::CreateDirFailed => ("出力先ディレクトリ作成失敗: {}: {}", "Failed to create output directory: {}: {}"),
+            Msg::CreateFileFailed => ("ファイル作成失敗: {}: {}", "Failed to create file: {}: {}"),
+            Msg::CommandFailed => ("コマンドが異常終了しました: {}: {}", "Command exited with an error: {}: {}"),
+            Msg::CommandSpawnFailed => ("コマンド実行失敗: {}: {}", "Command failed: {}: {}"),
+            Msg::FileReadFailed => ("ファイル読み込み失敗: {}: {}", "Failed to read file: {}: {}"),
+            Msg::DirReadFailed => ("ディレクトリ読み込み失敗: {}: {}", "Failed to read directory: {}: {}"),
+            Msg::EvtxParseFailed => ("EVTX解析失敗: {}: {}", "Failed to parse EVTX: {}: {}"),
+            Msg::EvtxBadHeader => ("EVTXファイルヘッダが不正です", "Invalid EVTX file header"),
+            Msg::EvtxUnsupportedVersion => ("未対応のEVTXバージョンです: {}", "Unsupported EVTX version: {}"),
+            Msg::EvtxOutOfBounds => ("データ終端を超えました (offset={})", "Read past end of data (offset={})"),
+            Msg::EvtxEndOfData => ("データ終端です", "End of data"),
+            Msg::EvtxTooDeep => ("BinXMLの入れ子が{}段を超えています（オフセット {}）", "BinXML nesting exceeds {} levels (offset {})"),
+            Msg::EvtxTooLarge => (
+                "1レコードの要素・テンプレートの展開が{}個を超えています（オフセット {}）",
+                "a record expands to more than {} elements and templates (offset {})",
+            ),
+            Msg::EvtxUnexpectedToken => ("想定外のトークンです: {} (offset={})", "Unexpected token: {} (offset={})"),
+            Msg::EvtxBadElementEnd => ("開始タグの終了トークンが不正です: {}", "Invalid start-element end token: {}"),
+            Msg::EvtxUnsupportedValueType => ("未対応の値トークン型です: {}", "Unsupported value token type: {}"),
+            Msg::EvtxUnexpectedContent => ("想定外の内容トークンです: {} (offset={})", "Unexpected content token: {} (offset={})"),
+            Msg::EventMalformed => ("解釈できないイベントを読み飛ばしました: {}", "Skipped malformed event: {}"),
+            Msg::EventFieldInvalid => (
+                "イベント {}（EventRecordID {}）の解釈できない項目を値なしとして扱いました: {}",
+                "Treated unparsable fields of event {} (EventRecordID {}) as empty: {}",
+            ),
+            Msg::XmlSyntaxError => (
+                "XMLの構文エラー（{} バイト目）: {}。次の <Event> から解析を再開します",
+                "XML syntax error at byte {}: {}; resuming at the next <Event>",
+            ),
+            // --- イベントカタログ ---
+            Msg::CatalogBuiltin => ("既定のカタログ", "built-in catalog"),
+            Msg::CatalogReadFailed => ("イベントカタログ読み込み失敗: {}: {}", "Failed to read event catalog: {}: {}"),
+            Msg::CatalogInvalid => ("イベントカタログが不正です: {}: {}", "Invalid event catalog: {}: {}"),
+            Msg::CatalogEntryInvalid => (
+                "イベントカタログが不正です: {}: {} 番目の [[event]]（id = {}）: {}",
+                "Invalid event catalog: {}: [[event]] #{} (id = {}): {}",
+            ),
+            Msg::CatalogEmpty => ("イベントカタログに有効なエントリがありません", "The event catalog has no enabled entries"),
+            Msg::CatalogChannelEmpty => ("channel を指定してください", "channel must not be empty"),
+            Msg::CatalogCategoryInvalid => (
+                "不明な category です: {}（boot / shutdown / unexpected / logon / logon_failure / process / cleared / audit_change / other）",
+                "unknown category: {} (boot / shutdown / unexpected / logon / logon_failure / process / cleared / audit_change / other)",
+            ),
+            Msg::CatalogTextMissing => ("{} を指定してください（文字列、または ja / en）", "{} is required (a string, or ja / en)"),
+            Msg::CatalogDuplicate => ("同じキーのエントリが重複しています（{}）", "duplicate entry for the same key ({})"),
+            Msg::CatalogTemplateUnclosed => ("テンプレートの { が閉じていません: {}", "unclosed { in template: {}"),
+            Msg::CatalogTemplateEmptyField => ("テンプレートに項目名のない波括弧があります: {}", "empty braces in template: {}"),
+            Msg::CatalogTemplateStrayBrace => ("テンプレートに対応する { のない } があります: {}", "unmatched } in template: {}"),
+            // --- なりすまし検出 ---
+            Msg::RoleProcess => ("プロセス", "process"),
+            Msg::RoleParentProcess => ("親プロセス", "parent process"),
+            Msg::MasqueradePath => (
+                "{}: システムバイナリ名 {} が想定外の場所から実行されています（{}、想定: {}）",
+                "{}: system binary name {} running from an unexpected location ({}, expected: {})",
+            ),
+            Msg::MasqueradeName => ("{}: システムバイナリに酷似した名前です（{}、類似: {}）", "{}: name closely resembles a system binary ({}, similar to: {})"),
+            Msg::SystemRootConflict => (
+                "{}: {} はシステムルート {} を示していますが、確実な手掛かり（System が起動した smss.exe）がないため {} として判定しています",
+                "{}: {} implies system root {}, but without trusted evidence (smss.exe started by System) {} is assumed",
+            ),
+            Msg::RootSourceLive => ("実行中のシステムの %SystemRoot%", "%SystemRoot% of the running system"),
+            Msg::RootSourceLog => ("ログの System が起動した smss.exe", "smss.exe started by System in the log"),
+            Msg::RootSourceDefault => ("既定値、ログに確実な手掛かりなし", "default; no trusted evidence in the log"),
+            Msg::SystemRootValue => ("{}（{}）", "{} ({})"),
+            Msg::HeaderSystemRoot => (">>> システムルート: {}", ">>> System root: {}"),
+            Msg::BruteForceAccount => (
+                "ブルートフォースの疑い: アカウント {} へのログオン失敗 {}回（{} ～ {}、{}以内に最大 {}回、閾値 {}回、接続元: {}）{}",
+                "possible brute force against account {}: {} failed logons ({} - {}; peak within {}: {}, threshold {}; sources: {}){}",
+            ),
+            Msg::BruteForceSource => (
+                "パスワードスプレーの疑い: 接続元 {} から複数アカウントへのログオン失敗 {}回（{} ～ {}、{}以内に最大 {}回、閾値 {}回、アカウント: {}）{}",
+                "possible password spraying from {}: {} failed logons against multiple accounts ({} - {}; peak within {}: {}, threshold {}; accounts: {}){}",
+            ),
+            Msg::BruteForceThenSuccess => ("、その後 {} にログオン成功", "; followed by a successful logon at {}"),
+            Msg::BruteForceNoSuccess => ("、その後のログオン成功なし", "; no successful logon followed"),
+            Msg::BruteForceSuccessAccount => (
+                "ブルートフォースの後のログオン成功（アカウント {} へのログオン失敗 {}回）",
+                "successful logon after brute force (account {}: {} failed logons)",
+            ),
+            Msg::BruteForceSuccessSource => (
+                "パスワードスプレーの後のログオン成功（接続元 {} からのログオン失敗 {}回）",
+                "successful logon after password spraying (source {}: {} failed logons)",
+            ),
+            Msg::BruteForceMore => ("{} ほか {}件", "{} and {} more"),
+            Msg::BruteForceThresholdInvalid => (
+                "--brute-force-threshold の値が正しくありません: {}（1以上の整数を指定してください）",
+                "invalid value for --brute-force-threshold: {} (expected an integer of 1 or more)",
+            ),
+            // --- ログの整合性 ---
+            Msg::IntegrityHigh => ("🛑 [ログの整合性・重大] {}", "🛑 [Log integrity, HIGH] {}"),
+            Msg::IntegrityMedium => ("⚠️ [ログの整合性・注意] {}", "⚠️ [Log integrity, MEDIUM] {}"),
+            Msg::IntegrityLogCleared => ("{} ログが消去されました（{}、実行者: {}）", "the {} log was cleared ({}, by {})"),
+            Msg::IntegrityServiceStopped => (
+                "イベントログサービスが停止しました（{}、前後 {} 以内にシャットダウン・起動の記録なし）",
+                "the event logging service was shut down ({}; no shutdown or boot recorded within {})",
+            ),
+            Msg::IntegrityAuditPolicy => (
+                "監査ポリシーが変更されました（{}、{}: {}、実行者: {}）",
+                "the audit policy was changed ({}; {}: {}; by {})",
+            ),
+            Msg::IntegrityTimeChange => (
+                "システム時刻が変更されました（{} → {}、{}の{}、プロセス: {}、実行者: {}）",
+                "the system time was changed ({} -> {}; {} {}; process: {}; by {})",
+            ),
+            Msg::IntegrityTimeBackward => ("巻き戻し", "backwards"),
+            Msg::IntegrityTimeForward => ("進める変更", "forwards"),
+            Msg::IntegrityRecordGap => (
+                "{} ログの EventRecordID が連続していません: {} の次が {}（{}件欠落、{} ～ {}）",
+                "EventRecordID gap in the {} log: {} is followed by {} ({} records missing, {} - {})",
+            ),
+            Msg::IntegrityRecordReset => (
+                "{} ログの EventRecordID が巻き戻っています: {} の次が {}（{}、ログの消去・再作成の可能性）",
+                "EventRecordID reset in the {} log: {} is followed by {} ({}; the log may have been cleared or recreated)",
+            ),
+            Msg::IntegrityRecordResetCleared => (
+                "{} ログの EventRecordID が巻き戻っています: {} の次が {}（{}、直後にログの消去を記録）",
+                "EventRecordID reset in the {} log: {} is followed by {} ({}; the log clear is recorded right after)",
+            ),
+            // --- 除外フィルタ設定 ---
+            Msg::FilterBuiltin => ("既定の除外フィルタ設定", "built-in filter config"),
+            Msg::FilterReadFailed => ("除外フィルタ設定の読み込み失敗: {}: {}", "Failed to read filter config: {}: {}"),
+            Msg::FilterInvalid => ("除外フィルタ設定が不正です: {}: {}", "Invalid filter config: {}: {}"),
+            Msg::FilterRuleInvalid => (
+                "除外フィルタ設定が不正です: {}: プロファイル {} の {} 番目の [[profile.rule]]（name = {}）: {}",
+                "Invalid filter config: {}: profile {}, [[profile.rule]] #{} (name = {}): {}",
+            ),
+            Msg::FilterProfileNameEmpty => ("[[profile]] の name を指定してください", "[[profile]] name must not be empty"),
+            Msg::FilterProfileDuplicate => ("同じ名前のプロファイルが重複しています（{}）", "duplicate profile name ({})"),
+            Msg::FilterProfileUnknown => ("不明な除外プロファイルです: {}（定義済み: {}）", "Unknown filter profile: {} (defined: {})"),
+            Msg::FilterProfileCycle => ("除外プロファイルの extends が循環しています（{}）", "Filter profile extends form a cycle ({})"),
+            Msg::FilterRuleNameEmpty => ("name を指定してください", "name must not be empty"),
+            Msg::FilterRuleNoCondition => (
+                "条件が指定されていません（image / file_name / parent / ancestor / service / service_group / user / event_id / command_line / cmdline_audit のいずれか）",
+                "no condition given (one of image / file_name / parent / ancestor / service / service_group / user / event_id / command_line / cmdline_audit)",
+            ),
+            Msg::FilterActionInvalid => ("不明な action です: {}（exclude / include）", "unknown action: {} (exclude / include)"),
+            Msg::FilterRegexInvalid => ("パターンを解釈できません: {}: {}", "invalid pattern: {}: {}"),
+            // --- コマンドライン ---
+            Msg::HelpText => (HELP_JA, HELP_EN),
+            Msg::ValueRequired => ("{} には値を指定してください", "{} requires a value"),
+            Msg::FlagTakesNoValue => ("{} は値を取りません: {}", "{} does not take a value: {}"),
+            Msg::SvchostLevelInvalid => (
+                "--svchost-level には 0 / 1 / 2 のいずれかを指定してください（指定値: {}）",
+                "--svchost-level must be 0, 1 or 2 (got: {})",
+            ),
+            Msg::ProfileConflict => (
+                "除外プロファイルが矛盾しています（{} と {}）",
+                "Conflicting filter profiles ({} and {})",
+            ),
+            Msg::FormatInvalid => (
+                "--format には text / json / jsonl / csv / html / tree / dot / lifetime / sessions / boots のいずれかを指定してください（指定値: {}）",
+                "--format must be one of text / json / jsonl / csv / html / tree / dot / lifetime / sessions / boots (got: {})",
+            ),
+            Msg::BootSelectionInvalid => (
+                "{} の値が正しくありません: {}（N / N-M / first:N / last:N の形式で指定してください）",
+                "invalid value for {}: {} (expected N / N-M / first:N / last:N)",
+            ),
+            Msg::BootSelectionConflict => ("--boot / --boots が複数指定されています（{} と {}）", "--boot / --boots given more than once ({} and {})"),
+            Msg::BootSelectionLiveRange => (
+                "ライブ取得で --boot / --boots を使う場合は、--since または --last で複数の起動を含む監査範囲を指定してください（例: --last 30d）",
+                "--boot / --boots with live collection needs an audit window spanning several boots; use --since or --last (e.g. --last 30d)",
+            ),
+            Msg::FormatConflict => ("--format が複数指定されています（{} と {}）", "--format given more than once ({} and {})"),
+            Msg::OutputRequired => ("{} には出力ファイル名または - を指定してください", "{} requires an output file name or -"),
+            Msg::OutputConflict => ("出力先が複数指定されています（{} と {}）", "Output given more than once ({} and {})"),
+            Msg::TimestampInvalid => (
+                "{} の日時を解釈できません: {}（例: 2025-01-01T09:00:00+09:00）",
+                "Cannot parse the {} timestamp: {} (e.g. 2025-01-01T09:00:00+09:00)",
+            ),
+            Msg::DurationInvalid => (
+                "{} の期間を解釈できません: {}（例: 45s, 90m, 1h30m, 2d）",
+                "Cannot parse the {} duration: {} (e.g. 45s, 90m, 1h30m, 2d)",
+            ),
+            Msg::TzInvalid => (
+                "--tz のタイムゾーンを解釈できません: {}（例: Asia/Tokyo, America/New_York, UTC, local, +09:00）",
+                "Unknown --tz time zone: {} (e.g. Asia/Tokyo, America/New_York, UTC, local, +09:00)",
+            ),
+            Msg::LangInvalid => ("--lang には ja / en のいずれかを指定してください（指定値: {}）", "--lang must be ja or en (got: {})"),
+            Msg::EventIdUnsupported => ("--event-id にイベントカタログにないイベントIDが指定されました: {}", "Event ID not in the event catalog for --event-id: {}"),
+            Msg::EventIdInvalid => ("--event-id には数値のイベントIDを指定してください（指定値: {}）", "--event-id must be numeric event IDs (got: {})"),
+            Msg::InputFileSpecInvalid => (
+                "{} は ログ名=ファイルパス の形式で指定してください（例: System=System.xml）",
+                "{} must be given as LOG=PATH (e.g. System=System.xml)",
+            ),
+            Msg::UnknownOption => ("不明なオプションです: {}（--help で使い方を表示）", "Unknown option: {} (see --help)"),
+            Msg::UnknownArgument => ("不明な引数です: {}（--help で使い方を表示）", "Unexpected argument: {} (see --help)"),
+            Msg::InputDirEmpty => ("{} に *.{} ファイルがありません（{}）", "{} has no *.{} files ({})"),
+            Msg::BomRequiresCsv => ("--bom は --format csv と併用してください", "--bom can only be used with --format csv"),
+            Msg::SinceLastConflict => ("--since と --last は同時に指定できません", "--since and --last cannot be used together"),
+            Msg::BootFlagsConflict => (
+                "--boot-offset / --boot-window は --since / --last と同時に指定できません",
+                "--boot-offset / --boot-window cannot be combined with --since / --last",
+            ),
+            Msg::BootFlagsOffline => (
+                "--boot-offset / --boot-window はオフライン解析では使用できません（--since / --until / --last を使用してください）",
+                "--boot-offset / --boot-window are not available for offline analysis (use --since / --until / --last)",
+            ),
+            Msg::SinceAfterUntil => ("--since には --until より前の日時を指定してください", "--since must be earlier than --until"),
+        }
+    }
+}
+
+/// 現在の表示言語でメッセージ本文を返す関数
+pub fn t(msg: Msg) -> &'static str {
+    text_in(lang(), msg)
+}
+
+/// 指定言語でメッセージ本文を返す関数
+pub fn text_in(lang: Lang, msg: Msg) -> &'static str {
+    let (ja, en) = msg.texts();
+    match lang {
+        Lang::Ja => ja,
+        Lang::En => en,
+    }
+}
+
+/// 現在の表示言語でメッセージの {} を引数で置き換えた文字列を返す関数
+pub fn tr(msg: Msg, args: &[&dyn std::fmt::Display]) -> String {
+    let mut out = String::new();
+    let mut args = args.iter(); // 置き換える引数
+    let mut parts = t(msg).split("{}").peekable(); // {} で分割した本文
+    while let Some(part) = parts.next() {
+        out.push_str(part);
+        if parts.peek().is_some() {
+            if let Some(arg) = args.next() {
+                out.push_str(&arg.to_string()); // 引数で置き換え
+            }
+        }
+    }
+    out
+}
+
+/// 使い方（日本語）: {} はプログラム名, バージョン, プログラム名
+const HELP_JA: &str = "{} {}
+Windowsイベントログ解析ツール - 起動前後のイベント（起動・シャットダウン・ログオン・プロセス生成等）を抽出・整形出力
+
+使い方:
+  {} [オプション]
+
+除外フィルタ:
+      --profile <名前>          除外プロファイル（既定: all）
+                                all: 常駐プロセスのみ除外 / services: 代表的なサービスのsvchost.exeも除外
+                                no-svchost: svchost.exeを全て除外
+  -s0, -s1, -s2                 --profile all / services / no-svchost の別名
+      --svchost-level <0|1|2>   上記と同じ（数値で指定）
+      --filter <FILE>           除外フィルタ設定（TOML）でプロファイルを追加・上書き（複数指定可）
+      --show-excluded           除外されたイベントも、除外したルール名を付けて出力
+      --hide-subtree <PROCESS>  指定プロセスとその子孫のプロセス作成を除外（複数指定可）
+      --only-subtree <PROCESS>  指定プロセスとその子孫のプロセス作成のみ出力（複数指定可）
+                                \\ を含む場合はフルパス、含まない場合はファイル名（* ? 使用可）
+
+監査範囲:
+      --since <日時>            監査開始時刻（RFC 3339、例: 2025-01-01T09:00:00+09:00）
+                                オフセットなし（2025-01-01 09:00:00）は表示タイムゾーンの日時
+      --until <日時>            監査終了時刻（既定: 現在時刻）
+      --last <期間>             終了時刻から遡る期間（例: 2h, 1h30m, 3d）
+      --boot-offset <期間>      最終起動時刻から遡る期間（既定: 30s）
+      --boot-window <期間>      最終起動時刻以降の監査時間（既定: 90m）
+                                未指定時はライブ取得なら最終起動時刻の前後、オフライン解析なら全期間
+                                期間の単位: s / m / h / d / w（例: 45s, 90m, 1h30m, 2d）
+
+イベントの選択:
+      --event-id <ID[,ID...]>   出力するイベントIDを限定（複数指定可、例: --event-id 4688,4624）
+      --catalog <FILE>          イベントカタログ（TOML）で対象イベント・表示内容を追加・上書き（複数指定可）
+      --boot <N>                起動 #N のイベントのみ出力（監査範囲内の最初の起動が #1、#0 は最初の起動より前）
+      --boots <指定>            出力する起動を選択（last:5 直近5回 / first:3 最初の3回 / 2-4 範囲）
+                                ライブ取得では --since / --last と併用
+
+ブルートフォース検出（ログオン失敗 4625 / 4771 / 4776）:
+      --brute-force-threshold <N>  アカウント・接続元ごとのウィンドウ内の失敗回数の閾値（既定: 5）
+      --brute-force-window <期間>  失敗回数を数えるウィンドウの長さ（既定: 5m）
+
+出力:
+      --tz <タイムゾーン>       表示タイムゾーン（既定: Asia/Tokyo）
+                                IANA名（America/New_York など、夏時間対応） / UTC / local / +09:00
+      --lang <ja|en>            表示言語（既定: 環境変数 WINBOOTEVENTCHECK_LANG / LANG、未設定時は ja）
+      --format <形式>           text（既定） / json / jsonl / csv / html
+                                / tree（プロセスツリー） / dot（Graphviz） / lifetime（実行期間の一覧）
+                                / sessions（ログオンセッションの一覧） / boots（起動の一覧）
+      --bom                     CSVの先頭にUTF-8 BOMを付与（--format csv のみ）
+  -o, --output <パス|->         出力先（既定: output.<拡張子>、- で標準出力）
+                                {host} {timestamp} {date} {ext} を展開
+
+入力（指定時はオフライン解析、事前チェックは行いません）:
+      --xml-dir <DIR>           ディレクトリ内の *.xml を解析（ファイル名=ログ名）
+      --evtx-dir <DIR>          ディレクトリ内の *.evtx を解析（ファイル名=ログ名）
+      --xml-file <ログ名=パス>   XMLファイルを個別に指定（複数指定可）
+      --evtx-file <ログ名=パス>  EVTXファイルを個別に指定（複数指定可）
+
+その他:
+  -h, --help                    この使い方を表示
+  -V, --version                 バージョンを表示
+";
+
+/// 使い方（英語）: {} はプログラム名, バージョン, プログラム名
+const HELP_EN: &str = "{} {}
+Windows event log analyser - extracts and formats events around boot (startup, shutdown, logon, process creation, ...)
+
+Usage:
+  {} [OPTIONS]
+
+Exclusion filters:
+      --profile <NAME>          filter profile (default: all)
+                                all: exclude resident processes only / services: also exclude svchost.exe
+                                of common services / no-svchost: exclude all svchost.exe
+  -s0, -s1, -s2                 aliases for --profile all / services / no-svchost
+      --svchost-level <0|1|2>   same as above, as a number
+      --filter <FILE>           add or override profiles with a TOML filter config (repeatable)
+      --show-excluded           also output excluded events, marked with the rule that excluded them
+      --hide-subtree <PROCESS>  exclude process creations of a process and its descendants (repeatable)
+      --only-subtree <PROCESS>  only output process creations of a process and its descendants (repeatable)
+                                full path if it contains \\, otherwise a file name (* ? allowed)
+
+Audit window:
+      --since <TIME>            audit start (RFC 3339, e.g. 2025-01-01T09:00:00+09:00)
+                                times without an offset (2025-01-01 09:00:00) use the display time zone
+      --until <TIME>            audit end (default: now)
+      --last <DURATION>         period before the audit end (e.g. 2h, 1h30m, 3d)
+      --boot-offset <DURATION>  how far before the last boot to start (default: 30s)
+      --boot-window <DURATION>  how long after the last boot to audit (default: 90m)
+                                default: around the last boot for live collection, everything for offline analysis
+                                duration units: s / m / h / d / w (e.g. 45s, 90m, 1h30m, 2d)
+
+Event selection:
+      --event-id <ID[,ID...]>   only output these event IDs (repeatable, e.g. --event-id 4688,4624)
+      --catalog <FILE>          add or override events and their texts with a TOML event catalog (repeatable)
+      --boot <N>                only output events of boot #N (#1 is the first boot in the window, #0 precedes it)
+      --boots <SPEC>            select boots to output (last:5 / first:3 / 2-4)
+                                with live collection, combine with --since / --last
+
+Brute-force detection (failed logons 4625 / 4771 / 4776):
+      --brute-force-threshold <N>  failures per account / source within the window to report (default: 5)
+      --brute-force-window <DURATION>  length of the sliding window (default: 5m)
+
+Output:
+      --tz <ZONE>               display time zone (default: Asia/Tokyo)
+                                IANA name (America/New_York etc., DST-aware) / UTC / local / +09:00
+      --lang <ja|en>            display language (default: WINBOOTEVENTCHECK_LANG / LANG, otherwise ja)
+      --format <FORMAT>         text (default) / json / jsonl / csv / html
+                                / tree (process tree) / dot (Graphviz) / lifetime (process lifetimes)
+                                / sessions (logon sessions) / boots (boot sessions)
+      --bom                     prepend a UTF-8 BOM to CSV output (--format csv only)
+  -o, --output <PATH|->         output path (default: output.<ext>, - for stdout)
+                                expands {host} {timestamp} {date} {ext}
+
+Input (offline analysis; environment checks are skipped):
+      --xml-dir <DIR>           analyse *.xml in a directory (file name = log name)
+      --evtx-dir <DIR>          analyse *.evtx in a directory (file name = log name)
+      --xml-file <LOG=PATH>     add an XML file (repeatable)
+      --evtx-file <LOG=PATH>    add an EVTX file (repeatable)
+
+Other:
+  -h, --help                    show this help
+  -V, --version                 show the version
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 全てのメッセージキー（enum Msg の宣言順）
+    const ALL: &[Msg] = &[
+        Msg::AdminRequired, Msg::LogEnabled, Msg::LogDisabled, Msg::EnableLogHint, Msg::AppStarted, Msg::Uptime,
+        Msg::UptimeFailed, Msg::UptimeParseFailed, Msg::AuditRangeNotice, Msg::RangeExplicit, Msg::RangeBootRelative,
+        Msg::SecurityAuditDisabled, Msg::SecurityAuditEnabled, Msg::SecurityAuditUnknown, Msg::CmdlineAuditOff,
+        Msg::BootTimeUnavailable, Msg::RangeInverted, Msg::SkipUnsupportedLog, Msg::FetchFailed, Msg::RangeFormat,
+        Msg::RangeAllTime, Msg::OfflineScopeRange, Msg::OfflineScopeAll, Msg::OfflineRange, Msg::OfflineNoEvents,
+        Msg::OfflineLastAnchor,
+        Msg::WriteFailed, Msg::WroteOutput, Msg::WroteMetadata, Msg::HeaderAuditRange, Msg::HeaderCmdlineAudit,
+        Msg::HeaderTimeZone, Msg::HeaderFilterProfile, Msg::HeaderShowExcluded, Msg::ExcludedMarker, Msg::FindingMarker,
+        Msg::DecodedMarker, Msg::ServiceMarker, Msg::TreeProcessLabel, Msg::TreeBootHeading, Msg::TreeBeforeFirstBoot,
+        Msg::TreeOutOfRange, Msg::TreeOutOfRangeShort, Msg::LifetimeStartMarker, Msg::LifetimeStartUnknown,
+        Msg::LifetimeEndMarker, Msg::LifetimeColumns, Msg::LifetimeBeforeWindow, Msg::LifetimeNoEnd,
+        Msg::LifetimeExited, Msg::LifetimeRunning, Msg::LifetimeRebooted, Msg::LifetimePidReused,
+        Msg::LogonSourceMarker, Msg::SessionLogoffMarker, Msg::SessionOpenMarker, Msg::SessionLogonMarker,
+        Msg::SessionLogonUnknown, Msg::SessionProcessMarker, Msg::SessionColumns, Msg::SessionBeforeWindow,
+        Msg::SessionNoLogoff, Msg::SessionLoggedOff, Msg::SessionActive, Msg::SessionRebooted, Msg::BootMark,
+        Msg::BootMarkSince, Msg::BootEndClean, Msg::BootEndDirty, Msg::BootEndRunning, Msg::BootInitiator,
+        Msg::BootColumns, Msg::BootNoEnd, Msg::BootSelectionOne, Msg::BootSelectionRange, Msg::BootSelectionFirst,
+        Msg::BootSelectionLast, Msg::HeaderBootSelection, Msg::BootSelectionInvalid, Msg::BootSelectionConflict,
+        Msg::BootSelectionLiveRange, Msg::CmdlineAuditStateOn, Msg::CmdlineAuditStateOff, Msg::HtmlTitle,
+        Msg::HtmlHeading, Msg::HtmlAuditRange, Msg::HtmlTzAndCount, Msg::HtmlCmdlineWarn, Msg::HtmlFilterIds,
+        Msg::HtmlFilterUser, Msg::HtmlSvchostGroup, Msg::ColDateTime, Msg::ColLog, Msg::ColUser, Msg::ColEventId,
+        Msg::ColAction, Msg::ColDescription, Msg::ColService, Msg::ColDetail, Msg::ColBoot, Msg::CatBoot,
+        Msg::CatShutdown, Msg::CatUnexpected, Msg::CatLogon, Msg::CatLogonFailure, Msg::CatProcess, Msg::CatCleared,
+        Msg::CatAuditChange, Msg::CatOther, Msg::Stdout, Msg::CreateDirFailed, Msg::CreateFileFailed,
+        Msg::CommandFailed, Msg::CommandSpawnFailed, Msg::FileReadFailed, Msg::DirReadFailed, Msg::EvtxParseFailed,
+        Msg::EvtxBadHeader, Msg::EvtxUnsupportedVersion, Msg::EvtxOutOfBounds, Msg::EvtxEndOfData, Msg::EvtxTooDeep, Msg::EvtxTooLarge,
+        Msg::EvtxUnexpectedToken, Msg::EvtxBadElementEnd, Msg::EvtxUnsupportedValueType, Msg::EvtxUnexpectedContent,
+        Msg::EventMalformed, Msg::EventFieldInvalid, Msg::XmlSyntaxError, Msg::CatalogBuiltin, Msg::CatalogReadFailed, Msg::CatalogInvalid, Msg::CatalogEntryInvalid,
+        Msg::CatalogEmpty, Msg::CatalogChannelEmpty, Msg::CatalogCategoryInvalid, Msg::CatalogTextMissing,
+        Msg::CatalogDuplicate, Msg::CatalogTemplateUnclosed, Msg::CatalogTemplateEmptyField,
+        Msg::CatalogTemplateStrayBrace, Msg::RoleProcess, Msg::RoleParentProcess, Msg::MasqueradePath,
+        Msg::MasqueradeName, Msg::SystemRootConflict, Msg::RootSourceLive, Msg::RootSourceLog, Msg::RootSourceDefault,
+        Msg::SystemRootValue, Msg::HeaderSystemRoot, Msg::BruteForceAccount, Msg::BruteForceSource, Msg::BruteForceThenSuccess,
+        Msg::BruteForceNoSuccess, Msg::BruteForceSuccessAccount, Msg::BruteForceSuccessSource, Msg::BruteForceMore,
+        Msg::BruteForceThresholdInvalid, Msg::IntegrityHigh, Msg::IntegrityMedium, Msg::IntegrityLogCleared,
+        Msg::IntegrityServiceStopped, Msg::IntegrityAuditPolicy, Msg::IntegrityTimeChange, Msg::IntegrityTimeBackward,
+        Msg::IntegrityTimeForward, Msg::IntegrityRecordGap, Msg::IntegrityRecordReset, Msg::IntegrityRecordResetCleared,
+        Msg::FilterBuiltin, Msg::FilterReadFailed, Msg::FilterInvalid, Msg::FilterRuleInvalid,
+        Msg::FilterProfileNameEmpty, Msg::FilterProfileDuplicate, Msg::FilterProfileUnknown, Msg::FilterProfileCycle,
+        Msg::FilterRuleNameEmpty, Msg::FilterRuleNoCondition, Msg::FilterActionInvalid, Msg::FilterRegexInvalid,
+        Msg::HelpText, Msg::ValueRequired, Msg::FlagTakesNoValue, Msg::SvchostLevelInvalid, Msg::ProfileConflict,
+        Msg::FormatInvalid, Msg::FormatConflict, Msg::OutputRequired, Msg::OutputConflict, Msg::TimestampInvalid,
+        Msg::DurationInvalid, Msg::TzInvalid, Msg::LangInvalid, Msg::EventIdUnsupported, Msg::EventIdInvalid,
+        Msg::InputFileSpecInvalid, Msg::UnknownOption, Msg::UnknownArgument, Msg::InputDirEmpty, Msg::BomRequiresCsv,
+        Msg::SinceLastConflict, Msg::BootFlagsConflict, Msg::BootFlagsOffline, Msg::SinceAfterUntil,
+    ];
+
+    #[test]
+    fn all_lists_every_key() {
+        let source = include_str!("messages.rs");
+        let body = &source[source.find("pub enum Msg {").unwrap()..];
+        let body = &body[..body.find("\n}").unwrap()];
+        let declared: Vec<&str> = body
+            .lines()
+            .filter_map(|line| line.trim().split_once(',').map(|(name, _)| name))
+            .filter(|name| name.chars().all(|c| c.is_ascii_alphanumeric()) && !name.is_empty())
+            .collect();
+        let listed: Vec<String> = ALL.iter().map(|m| format!("{:?}", m)).collect();
+        assert_eq!(listed, declared, "ALL と enum Msg の宣言が一致しません");
+    }
+
+    #[test]
+    fn every_key_has_ja_and_en_text() {
+        for &msg in ALL {
+            let (ja, en) = msg.texts();
+            assert!(!ja.trim().is_empty(), "{:?}: 日本語のメッセージがありません", msg);
+            assert!(!en.trim().is_empty(), "{:?}: 英語のメッセージがありません", msg);
+            assert_eq!(ja.matches("{}").count(), en.matches("{}").count(), "{:?}: 日本語と英語で {{}} の数が異なります", msg);
+            let japanese = |c: char| matches!(c, '\u{3040}'..='\u{30ff}' | '\u{4e00}'..='\u{9fff}');
+            assert!(!en.chars().any(japanese), "{:?}: 英語のメッセージに日本語が含まれています: {}", msg, en);
+        }
+    }
+}
@@ -0,0 +1,85 @@
+// --- 必要なクレートの説明 ---
+// このファイルはレポートの出力先（ファイル・標準出力）を抽象化するモジュールです
+// std::fs / std::io: ファイル作成・標準出力への書き込み用
+// chrono: ファイル名テンプレートの {timestamp} / {date} 展開用
+
+use std::io::Write;     // 書き込み用
+use std::path::PathBuf; // ファイルパス
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+/// レポートの出力先
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OutputSink {
+    File(PathBuf), // ファイルに出力
+    Stdout,        // 標準出力に出力（パイプ連携用）
+}
+
+impl OutputSink {
+    /// --output の指定値から出力先を決定する
+    /// 未指定時は output.<拡張子>、"-" は標準出力
+    /// ファイル名には {host}（ホスト名、オフライン解析ではログを記録したコンピュータ名）、{timestamp}（yyyyMMdd_HHmmss）、{date}（yyyyMMdd）、{ext}（拡張子）を使用可能
+    pub fn from_spec(spec: Option<&str>, ext: &str, host: &str, now: chrono::DateTime<chrono::Local>) -> OutputSink {
+        match spec {
+            Some("-") => OutputSink::Stdout,
+            Some(template) => OutputSink::File(PathBuf::from(expand_template(template, ext, host, now))),
+            None => OutputSink::File(PathBuf::from(format!("output.{}", ext))),
+        }
+    }
+
+    /// 出力先を開き、書き込み用のライターを返す
+    pub fn open(&self) -> Result<Box<dyn Write>, String> {
+        match self {
+            OutputSink::Stdout => Ok(Box::new(std::io::stdout().lock())),
+            OutputSink::File(path) => {
+                if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+                    std::fs::create_dir_all(dir).map_err(|e| tr(Msg::CreateDirFailed, &[&dir.display(), &e]))?; // 出力先ディレクトリを作成
+                }
+                let file = std::fs::File::create(path).map_err(|e| tr(Msg::CreateFileFailed, &[&path.display(), &e]))?; // ファイル作成
+                Ok(Box::new(std::io::BufWriter::new(file)))
+            }
+        }
+    }
+
+    /// メタ情報（CSVのヘッダ情報）の出力先を返す
+    /// ファイル出力時は同じ場所の <ファイル名>.meta.txt、標準出力時は標準エラー出力（None）
+    pub fn metadata_sink(&self) -> Option<OutputSink> {
+        match self {
+            OutputSink::Stdout => None,
+            OutputSink::File(path) => {
+                let stem = path.file_stem().map(|s| s.to_string_lossy().to_string()).unwrap_or_default(); // 拡張子なしのファイル名
+                Some(OutputSink::File(path.with_file_name(format!("{}.meta.txt", stem))))
+            }
+        }
+    }
+
+    /// 案内表示用の出力先名
+    pub fn display_name(&self) -> String {
+        match self {
+            OutputSink::Stdout => t(Msg::Stdout).to_string(),
+            OutputSink::File(path) => path.display().to_string(),
+        }
+    }
+}
+
+/// ログにコンピュータ名が記録されていない場合の {host}（オフライン解析）
+pub const UNKNOWN_HOST: &str = "unknown-host";
+
+/// 実行中のホスト名を取得する関数（環境変数 COMPUTERNAME / HOSTNAME、取得できなければ "localhost"）
+pub fn local_host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "localhost".to_string())
+}
+
+/// ファイル名テンプレートのプレースホルダを展開する関数
+fn expand_template(template: &str, ext: &str, host: &str, now: chrono::DateTime<chrono::Local>) -> String {
+    // ホスト名はファイル名に使えない文字を置換
+    let safe_host: String = host.chars().map(|c| if c.is_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' }).collect();
+    template
+        .replace("{host}", &safe_host)
+        .replace("{timestamp}", &now.format("%Y%m%d_%H%M%S").to_string())
+        .replace("{date}", &now.format("%Y%m%d").to_string())
+        .replace("{ext}", ext)
+}
@@ -0,0 +1,198 @@
+// --- 必要なクレートの説明 ---
+// このファイルはプロセス作成（4688）とプロセス終了（4689）を組み合わせて、プロセスごとの実行期間を求めるモジュールです
+// 終了イベントは、同じ起動の中で同じプロセスIDの直近の作成イベント（プロセスのパスが一致するもの）と組み合わせます
+// 作成が監査範囲外の終了（開始不明）や、監査範囲の終了時点で実行中のプロセスも実行期間として扱います
+// 実行期間の一覧（--format lifetime）もこのモジュールで出力します
+// chrono: 開始・終了日時と実行時間の計算用
+// serde: JSON出力用シリアライズ
+
+use std::collections::HashMap; // 起動番号・プロセスID→実行期間
+use std::io::Write; // 書き込み用
+use chrono::{DateTime, Duration, Utc}; // 日時
+use serde::Serialize; // シリアライズ
+use crate::boot_session::BootSessions; // 起動ごとの区間
+use crate::event_types::{serialize_opt_utc, EventRecord}; // イベントレコード型
+use crate::event_util::{format_display, normalize_image_path, DisplayTz}; // パスの正規化・日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+use crate::time_window::format_duration; // 期間の表示
+
+/// 実行期間の終わり方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LifetimeState {
+    Exited,       // 終了イベントあり
+    Running,      // 監査範囲の終了時点で実行中（最後の起動で終了イベントなし）
+    Rebooted,     // 終了イベントがないまま次の起動を迎えた
+    PidReused,    // 終了イベントがないまま同じプロセスIDが再利用された
+}
+
+impl LifetimeState {
+    /// 出力用の名前（JSONの値と同じ）
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LifetimeState::Exited => "exited",
+            LifetimeState::Running => "running",
+            LifetimeState::Rebooted => "rebooted",
+            LifetimeState::PidReused => "pid_reused",
+        }
+    }
+}
+
+/// プロセスの実行期間（作成・終了イベントの両方に付与）
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessLifetime {
+    pub pid: u32,                             // プロセスID
+    pub image: String,                        // プロセスのパス
+    #[serde(serialize_with = "serialize_opt_utc")]
+    pub start: Option<DateTime<Utc>>,         // 開始日時（作成が監査範囲外ならNone）
+    #[serde(serialize_with = "serialize_opt_utc")]
+    pub end: Option<DateTime<Utc>>,           // 終了日時（終了イベントがなければNone）
+    pub duration_ms: Option<i64>,             // 実行時間（ミリ秒、開始・終了の両方がある場合のみ）
+    pub exit_status: Option<u32>,             // 終了コード（4689の Status）
+    pub state: LifetimeState,                 // 終わり方
+    pub start_record_id: Option<u64>,         // 作成イベントの EventRecordID
+    pub end_record_id: Option<u64>,           // 終了イベントの EventRecordID
+}
+
+impl ProcessLifetime {
+    /// 実行時間
+    fn duration(&self) -> Option<Duration> {
+        self.duration_ms.map(Duration::milliseconds)
+    }
+
+    /// イベントの詳細に付ける表示（作成イベントには終了、終了イベントには開始の情報）
+    pub fn marker(&self, event: &EventRecord, tz: DisplayTz) -> String {
+        if event.exit_process_id.is_some() {
+            return match (self.start, self.duration()) {
+                (Some(start), Some(d)) => tr(Msg::LifetimeStartMarker, &[&format_display(start, tz), &display_duration(d)]),
+                _ => t(Msg::LifetimeStartUnknown).to_string(), // 作成は監査範囲外
+            };
+        }
+        match (self.state, self.end, self.duration()) {
+            (LifetimeState::Exited, Some(end), Some(d)) => tr(
+                Msg::LifetimeEndMarker,
+                &[&format_display(end, tz), &display_duration(d), &display_status(self.exit_status)],
+            ),
+            (state, _, _) => format!("[{}]", state_text(state)),
+        }
+    }
+}
+
+/// 作成・終了イベントを組み合わせて実行期間を付与する関数（eventsは発生日時順）
+/// 戻り値は終了イベントの添字→対応する作成イベントの添字
+pub fn annotate(events: &mut [EventRecord]) -> HashMap<usize, usize> {
+    let boots = BootSessions::detect(events);
+    let mut lifetimes: Vec<(ProcessLifetime, Option<usize>, Option<usize>)> = Vec::new(); // (実行期間, 作成イベント, 終了イベント)
+    let mut live: HashMap<(u32, u32), usize> = HashMap::new(); // (起動番号, プロセスID)→終了していない実行期間
+    for (index, event) in events.iter().enumerate() {
+        let boot = boots.boot_of(index);
+        if let Some(pid) = event.new_process_id {
+            if let Some(previous) = live.insert((boot, pid), lifetimes.len()) {
+                lifetimes[previous].0.state = LifetimeState::PidReused; // 終了イベントのないままIDが再利用された
+            }
+            lifetimes.push((new_lifetime(pid, event.image(), Some(event)), Some(index), None));
+        } else if let Some(pid) = event.exit_process_id {
+            // 同じ起動・同じIDで、プロセスのパスが一致する作成イベントと組み合わせる
+            let same_image = |l: &ProcessLifetime| {
+                l.image.is_empty() || event.image().is_empty() || normalize_image_path(&l.image) == normalize_image_path(event.image())
+            };
+            let paired = live.get(&(boot, pid)).copied().filter(|&i| same_image(&lifetimes[i].0));
+            let slot = match paired {
+                Some(i) => {
+                    live.remove(&(boot, pid));
+                    i
+                }
+                None => {
+                    lifetimes.push((new_lifetime(pid, event.image(), None), None, None)); // 作成が監査範囲外の終了
+                    lifetimes.len() - 1
+                }
+            };
+            let (lifetime, _, exit) = &mut lifetimes[slot];
+            lifetime.end = Some(event.timestamp);
+            lifetime.end_record_id = Some(event.record_id);
+            lifetime.exit_status = event.exit_status;
+            lifetime.state = LifetimeState::Exited;
+            lifetime.duration_ms = lifetime.start.map(|start| (event.timestamp - start).num_milliseconds());
+            *exit = Some(index);
+        }
+    }
+    // 終了イベントのないプロセス: 最後の起動なら実行中、それ以前の起動なら再起動で終了
+    for ((boot, _), slot) in live {
+        lifetimes[slot].0.state = if boot == boots.count() { LifetimeState::Running } else { LifetimeState::Rebooted };
+    }
+    let mut exits = HashMap::new();
+    for (lifetime, create, exit) in lifetimes {
+        if let (Some(create), Some(exit)) = (create, exit) {
+            exits.insert(exit, create);
+        }
+        for index in [create, exit].into_iter().flatten() {
+            events[index].lifetime = Some(lifetime.clone());
+        }
+    }
+    exits
+}
+
+/// 実行中として実行期間を作る関数（作成イベントがなければ開始不明）
+fn new_lifetime(pid: u32, image: &str, created: Option<&EventRecord>) -> ProcessLifetime {
+    ProcessLifetime {
+        pid,
+        image: image.to_string(),
+        start: created.map(|e| e.timestamp),
+        end: None,
+        duration_ms: None,
+        exit_status: None,
+        state: LifetimeState::Running,
+        start_record_id: created.map(|e| e.record_id),
+        end_record_id: None,
+    }
+}
+
+/// 実行期間の一覧をタブ区切りで書き込む関数（1プロセス1行、最初に現れたイベントの順）
+pub fn write_lifetimes(events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    writeln!(out, "{}", t(Msg::LifetimeColumns))?; // 列名
+    let mut seen: Vec<(Option<u64>, Option<u64>)> = Vec::new(); // 出力済みの実行期間（作成・終了の EventRecordID）
+    for lifetime in events.iter().filter_map(|e| e.lifetime.as_ref()) {
+        let key = (lifetime.start_record_id, lifetime.end_record_id);
+        if seen.contains(&key) {
+            continue; // 作成・終了の両方が出力対象の場合は1行のみ
+        }
+        seen.push(key);
+        let when = |dt: Option<DateTime<Utc>>, unknown: Msg| dt.map(|dt| format_display(dt, tz)).unwrap_or_else(|| t(unknown).to_string());
+        writeln!(
+            out,
+            "{}\t{}\t{}\t{}\t{}\t{}\t{}",
+            when(lifetime.start, Msg::LifetimeBeforeWindow),
+            when(lifetime.end, Msg::LifetimeNoEnd),
+            lifetime.duration().map(display_duration).unwrap_or_default(),
+            if lifetime.state == LifetimeState::Exited { display_status(lifetime.exit_status) } else { String::new() },
+            state_text(lifetime.state),
+            lifetime.pid,
+            lifetime.image
+        )?;
+    }
+    Ok(())
+}
+
+/// 終わり方の表示
+fn state_text(state: LifetimeState) -> &'static str {
+    match state {
+        LifetimeState::Exited => t(Msg::LifetimeExited),
+        LifetimeState::Running => t(Msg::LifetimeRunning),
+        LifetimeState::Rebooted => t(Msg::LifetimeRebooted),
+        LifetimeState::PidReused => t(Msg::LifetimePidReused),
+    }
+}
+
+/// 実行時間の表示（1秒未満はミリ秒、それ以上は 1h2m3s 形式）
+fn display_duration(d: Duration) -> String {
+    if d.num_seconds() == 0 {
+        format!("{}ms", d.num_milliseconds())
+    } else {
+        format_duration(d)
+    }
+}
+
+/// 終了コードの表示（ログと同じ 0x 形式）
+fn display_status(status: Option<u32>) -> String {
+    status.map(|s| format!("0x{:x}", s)).unwrap_or_else(|| "-".to_string())
+}
@@ -0,0 +1,240 @@
+// --- 必要なクレートの説明 ---
+// このファイルはプロセス作成イベント（4688）の NewProcessId / ProcessId（親）から、起動ごとのプロセスツリーを組み立てるモジュールです
+// プロセスIDは再利用されるため、同じ起動の中で子の作成より前に作成された同じIDのプロセスのうち最も新しいものを親とします
+// 親の作成が監査範囲外の場合は、ParentProcessName とIDから親の仮ノード（範囲外）を作ります
+// ツリー表示（--format tree）とDOT（Graphviz）出力（--format dot）もこのモジュールで行います
+// std::collections::HashMap: 起動番号・プロセスIDからノードへの対応
+// std::io::Write: 出力先への書き込み用
+
+use std::collections::HashMap; // 起動番号・プロセスID→ノード
+use std::io::Write; // 書き込み用
+use crate::event_types::EventRecord; // イベントレコード型
+use crate::boot_session::BootSessions; // 起動ごとの区間
+use crate::event_decode::decoded_marker; // コードで記録された項目の表記
+use crate::event_util::{file_name_of, format_display, normalize_image_path, DisplayTz}; // パスの正規化・日時変換
+use crate::messages::{t, tr, Msg}; // メッセージカタログ
+
+/// プロセスツリーのノード
+#[derive(Debug, Clone)]
+pub struct ProcessNode {
+    pub event: Option<usize>,   // プロセス作成イベントの添字（親の仮ノードはNone）
+    pub pid: Option<u32>,       // プロセスID
+    pub image: String,          // プロセスのパス
+    pub boot: u32,              // 起動番号（0は最初の起動イベントより前）
+    pub parent: Option<usize>,  // 親ノード
+    pub children: Vec<usize>,   // 子ノード（作成順）
+}
+
+/// 起動ごとのプロセスツリー
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTree {
+    pub nodes: Vec<ProcessNode>,       // 全ノード（作成順、親の仮ノードを含む）
+    of_event: HashMap<usize, usize>,   // イベントの添字→ノード
+    pub boots: BootSessions,           // 起動ごとの区間
+}
+
+impl ProcessTree {
+    /// 発生日時順のイベントからプロセスツリーを組み立てる
+    pub fn build(events: &[EventRecord]) -> ProcessTree {
+        let mut tree = ProcessTree { boots: BootSessions::detect(events), ..Default::default() };
+        let mut live: HashMap<(u32, u32), usize> = HashMap::new(); // (起動番号, プロセスID)→最も新しく作成されたノード
+        let mut placeholders: HashMap<(u32, u32, String), usize> = HashMap::new(); // 範囲外の親の仮ノード
+        for (index, event) in events.iter().enumerate() {
+            let Some(pid) = event.new_process_id else {
+                continue; // プロセス作成イベント以外
+            };
+            let boot = tree.boots.boot_of(index);
+            let parent_image = event.parent_image();
+            // 親: 同じ起動で同じIDの直近のプロセス（親のパスが記録されていれば一致するもののみ、IDの再利用対策）
+            let parent = event.parent_process_id.and_then(|ppid| {
+                live.get(&(boot, ppid)).copied().filter(|&node| {
+                    parent_image.is_empty() || normalize_image_path(&tree.nodes[node].image) == normalize_image_path(parent_image)
+                })
+            });
+            let parent = match (parent, event.parent_process_id) {
+                (Some(node), _) => Some(node),
+                (None, Some(ppid)) => {
+                    // 親の作成が監査範囲外: 仮ノードを作る（同じ親の子は同じ仮ノードにまとめる）
+                    let key = (boot, ppid, normalize_image_path(parent_image));
+                    let node = *placeholders.entry(key).or_insert_with(|| {
+                        tree.nodes.push(ProcessNode {
+                            event: None,
+                            pid: Some(ppid),
+                            image: parent_image.to_string(),
+                            boot,
+                            parent: None,
+                            children: Vec::new(),
+                        });
+                        tree.nodes.len() - 1
+                    });
+                    Some(node)
+                }
+                (None, None) => None,
+            };
+            tree.nodes.push(ProcessNode {
+                event: Some(index),
+                pid: Some(pid),
+                image: event.image().to_string(),
+                boot,
+                parent,
+                children: Vec::new(),
+            });
+            let node = tree.nodes.len() - 1;
+            if let Some(parent) = parent {
+                tree.nodes[parent].children.push(node);
+            }
+            tree.of_event.insert(index, node);
+            live.insert((boot, pid), node); // 同じIDの以前のプロセスを置き換え
+        }
+        tree
+    }
+
+    /// イベントに対応するノード
+    pub fn node_of_event(&self, index: usize) -> Option<usize> {
+        self.of_event.get(&index).copied()
+    }
+
+    /// ノードの祖先（親から順、仮ノードを含む）
+    pub fn ancestors(&self, node: usize) -> impl Iterator<Item = &ProcessNode> {
+        std::iter::successors(self.nodes[node].parent, move |&n| self.nodes[n].parent).map(move |n| &self.nodes[n])
+    }
+
+    /// 親のないノード（起動番号・作成順）
+    fn roots(&self) -> Vec<usize> {
+        let mut roots: Vec<usize> = (0..self.nodes.len()).filter(|&n| self.nodes[n].parent.is_none()).collect();
+        roots.sort_by_key(|&n| self.nodes[n].boot); // 同じ起動の中は作成順のまま
+        roots
+    }
+
+    /// ノードの表示名（例: "cmd.exe (PID 420)"）
+    fn label(&self, node: usize) -> String {
+        let n = &self.nodes[node];
+        let name = if n.image.is_empty() { "?" } else { file_name_of(&n.image) };
+        match n.pid {
+            Some(pid) => tr(Msg::TreeProcessLabel, &[&name, &pid]),
+            None => name.to_string(),
+        }
+    }
+
+    /// 起動の見出し（例: "=== 起動 #1 (2025/07/10 15:03:45) ==="）
+    fn boot_heading(&self, boot: u32, tz: DisplayTz) -> String {
+        match self.boots.start_of(boot) {
+            Some(start) => tr(Msg::TreeBootHeading, &[&boot, &format_display(start, tz)]),
+            None => t(Msg::TreeBeforeFirstBoot).to_string(),
+        }
+    }
+}
+
+/// 1行分のプロセスの詳細（日時・コマンドライン・ユーザー・項目の表記・実行期間・セッション・除外理由・検出事項）
+fn node_detail(tree: &ProcessTree, node: usize, events: &[EventRecord], tz: DisplayTz) -> String {
+    let Some(event) = tree.nodes[node].event.map(|i| &events[i]) else {
+        return tr(Msg::TreeOutOfRange, &[&tree.label(node)]); // 親の仮ノード
+    };
+    let mut detail = format!("{} {}", format_display(event.timestamp, tz), tree.label(node));
+    if !event.command_line.is_empty() {
+        detail.push_str(&format!(" {}", event.command_line));
+    }
+    if !event.user.is_empty() {
+        detail.push_str(&format!(" [{}]", event.user));
+    }
+    if let Some(decoded) = decoded_marker(&event.data, &event.decoded) {
+        detail.push_str(&format!(" {}", tr(Msg::DecodedMarker, &[&decoded])));
+    }
+    if let Some(lifetime) = &event.lifetime {
+        detail.push_str(&format!(" {}", lifetime.marker(event, tz)));
+    }
+    if let Some(session) = &event.session {
+        detail.push_str(&format!(" {}", session.marker(event, tz)));
+    }
+    if let Some(rule) = &event.excluded_by {
+        detail.push_str(&format!(" {}", tr(Msg::ExcludedMarker, &[rule])));
+    }
+    for finding in &event.findings {
+        detail.push_str(&format!(" {}", tr(Msg::FindingMarker, &[&finding.message])));
+    }
+    detail
+}
+
+/// インデント付きのツリー表示で書き込む関数（起動ごとに見出し）
+pub fn write_tree(events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    let tree = ProcessTree::build(events);
+    let mut current_boot: Option<u32> = None;
+    for root in tree.roots() {
+        let boot = tree.nodes[root].boot;
+        if current_boot != Some(boot) {
+            writeln!(out, "{}", tree.boot_heading(boot, tz))?; // 起動ごとの見出し
+            current_boot = Some(boot);
+        }
+        writeln!(out, "{}", node_detail(&tree, root, events, tz))?;
+        write_children(&tree, root, "", events, tz, out)?;
+    }
+    Ok(())
+}
+
+/// 子ノードを罫線付きで再帰的に書き込む関数
+fn write_children(tree: &ProcessTree, node: usize, prefix: &str, events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    let children = &tree.nodes[node].children;
+    for (i, &child) in children.iter().enumerate() {
+        let last = i + 1 == children.len();
+        writeln!(out, "{}{}{}", prefix, if last { "└─ " } else { "├─ " }, node_detail(tree, child, events, tz))?;
+        let next_prefix = format!("{}{}", prefix, if last { "   " } else { "│  " });
+        write_children(tree, child, &next_prefix, events, tz, out)?;
+    }
+    Ok(())
+}
+
+/// DOT（Graphviz）形式で書き込む関数（起動ごとにクラスタ、範囲外の親は破線、除外は灰色、検出事項ありは赤）
+pub fn write_dot(header_lines: &[String], events: &[EventRecord], tz: DisplayTz, out: &mut dyn Write) -> std::io::Result<()> {
+    let tree = ProcessTree::build(events);
+    for line in header_lines {
+        writeln!(out, "// {}", line)?; // レポートヘッダはコメントとして出力
+    }
+    writeln!(out, "digraph process_tree {{")?;
+    writeln!(out, "  rankdir=LR;")?;
+    writeln!(out, "  node [shape=box, fontname=\"Consolas\"];")?;
+    let mut boots: Vec<u32> = tree.nodes.iter().map(|n| n.boot).collect();
+    boots.sort_unstable();
+    boots.dedup();
+    for boot in boots {
+        writeln!(out, "  subgraph cluster_boot{} {{", boot)?;
+        writeln!(out, "    label=\"{}\";", dot_escape(&tree.boot_heading(boot, tz)))?;
+        for (i, node) in tree.nodes.iter().enumerate().filter(|(_, n)| n.boot == boot) {
+            let mut label = tree.label(i);
+            let mut attrs = Vec::new();
+            match node.event.map(|e| &events[e]) {
+                Some(event) => {
+                    label.push_str(&format!("\n{}", format_display(event.timestamp, tz)));
+                    if !event.user.is_empty() {
+                        label.push_str(&format!("\n{}", event.user));
+                    }
+                    if let Some(rule) = &event.excluded_by {
+                        label.push_str(&format!("\n{}", tr(Msg::ExcludedMarker, &[rule])));
+                        attrs.push("color=gray, fontcolor=gray".to_string());
+                    }
+                    if !event.findings.is_empty() {
+                        attrs.push("color=red, penwidth=2".to_string());
+                    }
+                    attrs.push(format!("tooltip=\"{}\"", dot_escape(&event.command_line)));
+                }
+                None => {
+                    label.push_str(&format!("\n{}", t(Msg::TreeOutOfRangeShort)));
+                    attrs.push("style=dashed".to_string());
+                }
+            }
+            attrs.insert(0, format!("label=\"{}\"", dot_escape(&label)));
+            writeln!(out, "    n{} [{}];", i, attrs.join(", "))?;
+        }
+        writeln!(out, "  }}")?;
+    }
+    for (i, node) in tree.nodes.iter().enumerate() {
+        for child in &node.children {
+            writeln!(out, "  n{} -> n{};", i, child)?;
+        }
+    }
+    writeln!(out, "}}")
+}
+
+/// DOTの文字列リテラル用にエスケープする関数（改行は \n）
+fn dot_escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n")
+}
@@ -0,0 +1,170 @@
+// --- 必要なクレートの説明 ---
+// このファイルは svchost.exe のコマンドライン（-k サービスグループ / -p / -s サービス名）を解析するモジュールです
+// 例: C:\Windows\system32\svchost.exe -k netsvcs -p -s Schedule
+// regex: スイッチ（-k / /k、-p / /p、-s / /s、大文字小文字を区別しない）の判定用
+// serde: JSON出力用シリアライズ
+
+use std::sync::OnceLock; // 正規表現の初回のみコンパイル
+use regex::Regex; // 正規表現
+use serde::Serialize; // シリアライズ
+use crate::event_util::{file_name_of, normalize_image_path}; // プロセスのパスの正規化
+
+/// svchost.exe のコマンドラインの解析結果
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SvchostCommand {
+    pub group: Option<String>,   // サービスグループ（-k の値）
+    pub split: bool,             // -p（サービスごとのプロセス分離・ポリシー適用）の指定有無
+    pub service: Option<String>, // サービス名（-s の値）
+}
+
+impl SvchostCommand {
+    /// 表示用の文字列（例: "Schedule (-k netsvcs -p)"、サービス名がない場合は "(-k netsvcs)"）
+    pub fn describe(&self) -> String {
+        let mut opts = Vec::new();
+        if let Some(group) = &self.group {
+            opts.push(format!("-k {}", group));
+        }
+        if self.split {
+            opts.push("-p".to_string());
+        }
+        let opts = if opts.is_empty() { String::new() } else { format!("({})", opts.join(" ")) };
+        match &self.service {
+            Some(service) if opts.is_empty() => service.clone(),
+            Some(service) => format!("{} {}", service, opts),
+            None => opts,
+        }
+    }
+}
+
+/// svchost.exe のスイッチ（-k / -p / -s、/ 始まりも可）
+fn switch_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(r"(?i)^[-/]([kps])$").expect("svchostスイッチの正規表現"))
+}
+
+/// コマンドラインが svchost.exe の起動であれば解析する関数（svchost.exe 以外はNone）
+pub fn parse_svchost(command_line: &str) -> Option<SvchostCommand> {
+    let args = split_command_line(command_line);
+    let program = args.first()?;
+    if file_name_of(&normalize_image_path(program)) != "svchost.exe" {
+        return None;
+    }
+    let mut cmd = SvchostCommand::default();
+    let mut iter = args[1..].iter().peekable();
+    while let Some(arg) = iter.next() {
+        let Some(caps) = switch_regex().captures(arg) else {
+            continue; // 未知の引数は無視
+        };
+        // 値を取るスイッチは次の引数（スイッチでないもの）を値とする
+        let mut value = || iter.next_if(|next| !switch_regex().is_match(next)).cloned();
+        match caps[1].to_ascii_lowercase().as_str() {
+            "k" => cmd.group = value(),
+            "s" => cmd.service = value(),
+            _ => cmd.split = true, // -p
+        }
+    }
+    Some(cmd)
+}
+
+/// Windowsのコマンドラインを引数に分割する関数（CommandLineToArgvW と同じ引用符・バックスラッシュの規則）
+fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new(); // 解析中の引数
+    let mut in_arg = false;          // 引数の途中か（空の引用符 "" も1つの引数）
+    let mut in_quotes = false;       // 引用符の内側か
+    let mut backslashes = 0usize;    // 連続するバックスラッシュの数
+    for c in command_line.chars() {
+        match c {
+            '\\' => {
+                backslashes += 1;
+                in_arg = true;
+            }
+            '"' => {
+                // 2n個のバックスラッシュ＋" は n個のバックスラッシュと引用符の切り替え、2n+1個は n個と " そのもの
+                current.extend(std::iter::repeat('\\').take(backslashes / 2));
+                if backslashes % 2 == 1 {
+                    current.push('"');
+                } else {
+                    in_quotes = !in_quotes;
+                }
+                backslashes = 0;
+                in_arg = true;
+            }
+            ' ' | '\t' if !in_quotes => {
+                current.extend(std::iter::repeat('\\').take(backslashes));
+                backslashes = 0;
+                if in_arg {
+                    args.push(std::mem::take(&mut current)); // 引数の区切り
+                    in_arg = false;
+                }
+            }
+            _ => {
+                current.extend(std::iter::repeat('\\').take(backslashes));
+                backslashes = 0;
+                current.push(c);
+                in_arg = true;
+            }
+        }
+    }
+    current.extend(std::iter::repeat('\\').take(backslashes));
+    if in_arg {
+        args.push(current);
+    }
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn svc(group: Option<&str>, split: bool, service: Option<&str>) -> Option<SvchostCommand> {
+        Some(SvchostCommand {
+            group: group.map(str::to_string),
+            split,
+            service: service.map(str::to_string),
+        })
+    }
+
+    #[test]
+    fn parse_svchost_table() {
+        let cases = [
+            (r"C:\Windows\system32\svchost.exe -k netsvcs -p -s Schedule", svc(Some("netsvcs"), true, Some("Schedule"))),
+            (r"C:\Windows\system32\svchost.exe -k LocalService", svc(Some("LocalService"), false, None)),
+            (r"C:\Windows\System32\svchost.exe /k netsvcs /p /s Schedule", svc(Some("netsvcs"), true, Some("Schedule"))),
+            (r"svchost.exe -K DcomLaunch -P -S PlugPlay", svc(Some("DcomLaunch"), true, Some("PlugPlay"))),
+            (r#""C:\Windows\System32\svchost.exe" -k netsvcs -s Schedule"#, svc(Some("netsvcs"), false, Some("Schedule"))),
+            (r#""C:\Program Files\x\svchost.exe" -k netsvcs"#, svc(Some("netsvcs"), false, None)),
+            // 値の欠落: 次のスイッチは値として取らない
+            (r"C:\Windows\system32\svchost.exe -s -p", svc(None, true, None)),
+            (r"C:\Windows\system32\svchost.exe -k -s Schedule", svc(None, false, Some("Schedule"))),
+            (r"C:\Windows\system32\svchost.exe -k", svc(None, false, None)),
+            (r"C:\Windows\system32\svchost.exe", svc(None, false, None)),
+            (r"C:\Windows\system32\svchost.exe -k netsvcs -x unknown", svc(Some("netsvcs"), false, None)),
+            // svchost.exe 以外
+            (r"C:\Windows\system32\cmd.exe /c svchost.exe -k netsvcs", None),
+            (r#""C:\Windows\System32\svchost.exe.bak" -k netsvcs"#, None),
+            ("", None),
+        ];
+        for (command_line, expected) in cases {
+            assert_eq!(parse_svchost(command_line), expected, "{}", command_line);
+        }
+    }
+
+    #[test]
+    fn split_command_line_table() {
+        let cases: [(&str, &[&str]); 9] = [
+            (r"a b  c", &["a", "b", "c"]),
+            ("\ta\t b ", &["a", "b"]),
+            (r#""C:\Program Files\svchost.exe" -k netsvcs"#, &[r"C:\Program Files\svchost.exe", "-k", "netsvcs"]),
+            (r#"a "" b"#, &["a", "", "b"]),
+            (r#"a"b c"d"#, &["ab cd"]),
+            (r#"a\\\"b"#, &[r#"a\"b"#]),
+            (r#""a\\" b"#, &[r"a\", "b"]),
+            (r"C:\dir\ x\\", &[r"C:\dir\", r"x\\"]),
+            ("", &[]),
+        ];
+        for (command_line, expected) in cases {
+            assert_eq!(split_command_line(command_line), expected, "{}", command_line);
+        }
+    }
+}
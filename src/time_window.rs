@@ -0,0 +1,183 @@
+// --- 必要なクレートの説明 ---
+// このファイルは監査範囲（開始・終了時刻）の指定と決定をまとめるモジュールです
+// --since / --until / --last / --boot-offset / --boot-window の値の解釈と、
+// 最終起動時刻を基準にした既定の監査範囲の計算を行います
+// chrono: 日時・期間の計算用
+
+use chrono::{DateTime, Duration, Utc}; // 日時・期間
+use crate::event_source::TimeRange; // 監査範囲
+use crate::event_util::DisplayTz; // 表示タイムゾーン
+use crate::messages::{t, Msg}; // メッセージカタログ
+
+/// 起動前に遡る既定の秒数（シャットダウン直後・起動直前のイベントを含めるため）
+pub const DEFAULT_BOOT_OFFSET_SECS: i64 = 30;
+/// 起動後の既定の監査時間（分）
+pub const DEFAULT_BOOT_WINDOW_MINS: i64 = 90;
+
+/// 監査範囲の指定内容
+#[derive(Debug, Clone)]
+pub struct WindowOptions {
+    pub since: Option<DateTime<Utc>>, // --since: 監査開始時刻
+    pub until: Option<DateTime<Utc>>, // --until: 監査終了時刻
+    pub last: Option<Duration>,       // --last: 終了時刻から遡る期間
+    pub boot_offset: Duration,        // --boot-offset: 最終起動時刻から遡る期間
+    pub boot_window: Duration,        // --boot-window: 最終起動時刻以降の監査時間
+}
+
+impl Default for WindowOptions {
+    fn default() -> Self {
+        WindowOptions {
+            since: None,
+            until: None,
+            last: None,
+            boot_offset: Duration::seconds(DEFAULT_BOOT_OFFSET_SECS), // 起動30秒前から
+            boot_window: Duration::minutes(DEFAULT_BOOT_WINDOW_MINS), // 起動後90分まで
+        }
+    }
+}
+
+impl WindowOptions {
+    /// 最終起動時刻を使わずに範囲が決まる指定か（--since または --last）
+    pub fn is_explicit(&self) -> bool {
+        self.since.is_some() || self.last.is_some()
+    }
+
+    /// ライブ取得時の監査範囲を決定する
+    /// - --last: 終了時刻（--until、未指定なら現在時刻）から指定期間を遡る
+    /// - --since: 指定時刻から終了時刻（--until、未指定なら現在時刻）まで
+    /// - それ以外: 最終起動時刻 - boot_offset から、最終起動時刻 + boot_window と終了時刻の早い方まで
+    pub fn resolve(&self, now: DateTime<Utc>, boot_time: Option<DateTime<Utc>>) -> Result<TimeRange, String> {
+        let until = self.until.unwrap_or(now); // 終了時刻
+        if let Some(last) = self.last {
+            return Ok((until - last, until));
+        }
+        if let Some(since) = self.since {
+            return Ok((since, until));
+        }
+        let Some(boot) = boot_time else {
+            return Err(t(Msg::BootTimeUnavailable).to_string());
+        };
+        let end = until.min(boot + self.boot_window); // 起動後の監査時間で打ち切り
+        Ok((boot - self.boot_offset, end))
+    }
+
+    /// ライブ取得時の監査範囲を決定し、開始が終了より後になる指定はErrとする
+    pub fn resolve_checked(&self, now: DateTime<Utc>, boot_time: Option<DateTime<Utc>>) -> Result<TimeRange, String> {
+        let (start, end) = self.resolve(now, boot_time)?;
+        if start > end {
+            return Err(t(Msg::RangeInverted).to_string());
+        }
+        Ok((start, end))
+    }
+
+    /// オフライン解析時の監査範囲を決定する（指定なしならNone＝全期間、片側のみの指定は反対側を無制限とする）
+    /// --last は --until（未指定なら入力内の最新のイベント latest）から遡る（収集から時間が経ったログも対象にするため）
+    pub fn resolve_offline(&self, latest: Option<DateTime<Utc>>) -> Option<TimeRange> {
+        if let Some(last) = self.last {
+            let until = self.until.or(latest)?; // イベントがなければ全期間（該当なし）
+            return Some((until - last, until));
+        }
+        match (self.since, self.until) {
+            (None, None) => None,
+            (since, until) => Some((since.unwrap_or(DateTime::<Utc>::MIN_UTC), until.unwrap_or(DateTime::<Utc>::MAX_UTC))),
+        }
+    }
+}
+
+/// 日時文字列を解釈する関数
+/// RFC 3339（例: 2025-01-01T09:00:00+09:00）に対応し、
+/// タイムゾーン指定のない "YYYY-MM-DD HH:MM:SS" / "YYYY-MM-DD"（0時）は表示タイムゾーンの日時として扱う
+pub fn parse_timestamp(s: &str, tz: DisplayTz) -> Option<DateTime<Utc>> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    let naive = chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S"))
+        .ok()
+        .or_else(|| chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d").ok().and_then(|d| d.and_hms_opt(0, 0, 0)))?;
+    tz.local_to_utc(naive)
+}
+
+/// 人が読みやすい期間表記を解釈する関数
+/// 数値＋単位の並び（例: 90m, 1h30m, 2d, 45s, 1w, "2 hours"）に対応。単位は s/m/h/d/w とその英語表記
+pub fn parse_duration(s: &str) -> Option<Duration> {
+    let compact: String = s.chars().filter(|c| !c.is_whitespace()).collect::<String>().to_ascii_lowercase(); // 空白除去・小文字化
+    if compact.is_empty() {
+        return None;
+    }
+    if compact == "0" {
+        return Some(Duration::zero()); // 単位なしの0のみ許可
+    }
+    let mut total = Duration::zero(); // 合計期間
+    let mut rest = compact.as_str(); // 未解釈の残り
+    while !rest.is_empty() {
+        let digits = rest.find(|c: char| !c.is_ascii_digit()).unwrap_or(rest.len()); // 数値部分の長さ
+        if digits == 0 {
+            return None; // 数値で始まらない
+        }
+        let value: i64 = rest[..digits].parse().ok()?; // 数値
+        rest = &rest[digits..];
+        let unit_len = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len()); // 単位部分の長さ
+        let unit = match &rest[..unit_len] {
+            "s" | "sec" | "secs" | "second" | "seconds" => Duration::try_seconds(value)?,
+            "m" | "min" | "mins" | "minute" | "minutes" => Duration::try_minutes(value)?,
+            "h" | "hr" | "hrs" | "hour" | "hours" => Duration::try_hours(value)?,
+            "d" | "day" | "days" => Duration::try_days(value)?,
+            "w" | "week" | "weeks" => Duration::try_weeks(value)?,
+            _ => return None, // 単位なし・不明な単位
+        };
+        total = total.checked_add(&unit)?;
+        rest = &rest[unit_len..];
+    }
+    Some(total)
+}
+
+/// 期間を表示用の文字列（例: 1h30m）に変換する関数
+pub fn format_duration(d: Duration) -> String {
+    let mut secs = d.num_seconds(); // 残りの秒数
+    if secs == 0 {
+        return "0s".to_string();
+    }
+    let mut out = String::new();
+    if secs < 0 {
+        out.push('-');
+        secs = -secs;
+    }
+    for (unit_secs, unit) in [(86400, "d"), (3600, "h"), (60, "m"), (1, "s")] {
+        if secs >= unit_secs {
+            out.push_str(&format!("{}{}", secs / unit_secs, unit)); // 単位ごとの値
+            secs %= unit_secs;
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(h: u32, m: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 7, 10, h, m, 0).unwrap()
+    }
+
+    #[test]
+    fn offline_last_counts_back_from_the_newest_event() {
+        let window = WindowOptions { last: Some(Duration::hours(2)), ..WindowOptions::default() };
+        assert_eq!(window.resolve_offline(Some(at(16, 30))), Some((at(14, 30), at(16, 30))));
+        assert_eq!(window.resolve_offline(None), None);
+    }
+
+    #[test]
+    fn offline_last_counts_back_from_until_when_given() {
+        let window = WindowOptions { last: Some(Duration::minutes(30)), until: Some(at(12, 0)), ..WindowOptions::default() };
+        assert_eq!(window.resolve_offline(Some(at(16, 30))), Some((at(11, 30), at(12, 0))));
+    }
+
+    #[test]
+    fn offline_since_until_are_open_ended() {
+        assert_eq!(WindowOptions::default().resolve_offline(Some(at(16, 30))), None);
+        let window = WindowOptions { since: Some(at(9, 0)), ..WindowOptions::default() };
+        assert_eq!(window.resolve_offline(Some(at(16, 30))), Some((at(9, 0), DateTime::<Utc>::MAX_UTC)));
+    }
+}
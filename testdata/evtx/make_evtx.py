@@ -0,0 +1,112 @@
+# testdata/evtx/*.evtx（event_evtx.rs の単体テスト用の合成EVTX）を生成するスクリプト
+# 実行: python3 testdata/evtx/make_evtx.py testdata/evtx
+# 1チャンクに、インライン配置のテンプレート定義を持つ最初のレコードと、その定義を参照する後続のレコードを格納する
+# （EventData は置換値として埋め込まれたBinXML、Qualifiers は省略可能置換）
+import os, struct, sys
+
+def u8(v): return struct.pack('<B', v)
+def u16(v): return struct.pack('<H', v)
+def u32(v): return struct.pack('<I', v)
+def u64(v): return struct.pack('<Q', v)
+def utf16(s): return s.encode('utf-16-le')
+
+class W:
+    """チャンク先頭からのオフセットを把握して BinXML を書き込む"""
+    def __init__(self, base): self.base = base; self.b = bytearray()
+    def pos(self): return self.base + len(self.b)
+    def add(self, x): self.b += x
+    def name(self, n):  # 名前構造をインライン配置
+        self.add(u32(self.pos() + 4))
+        self.add(u32(0) + u16(0) + u16(len(n)) + utf16(n) + u16(0))
+    def start(self, n, attrs=False, dep=True):
+        self.add(u8(0x41 if attrs else 0x01))
+        if dep: self.add(u16(0xffff))
+        self.add(u32(0)); self.name(n)
+        if attrs: self.add(u32(0))
+    def attr(self, n): self.add(u8(0x06)); self.name(n)
+    def val(self, t): self.add(u8(0x05) + u8(1) + u16(len(t)) + utf16(t))
+    def sub(self, i, t, opt=False): self.add(u8(0x0e if opt else 0x0d) + u16(i) + u8(t))
+
+def filetime(unix): return (unix + 11644473600) * 10000000
+
+def template_def(w):
+    w.add(b'\x0f\x01\x01\x00')
+    w.start('Event', True); w.attr('xmlns'); w.val('http://schemas.microsoft.com/win/2004/08/events/event'); w.add(u8(0x02))
+    w.start('System'); w.add(u8(0x02))
+    w.start('Provider', True); w.attr('Name'); w.sub(0, 0x01); w.add(u8(0x03))
+    w.start('EventID', True); w.attr('Qualifiers'); w.sub(6, 0x06, True); w.add(u8(0x02)); w.sub(1, 0x06); w.add(u8(0x04))
+    w.start('Level'); w.add(u8(0x02)); w.sub(7, 0x04); w.add(u8(0x04))
+    w.start('TimeCreated', True); w.attr('SystemTime'); w.sub(2, 0x11); w.add(u8(0x03))
+    w.start('EventRecordID'); w.add(u8(0x02)); w.sub(3, 0x0a); w.add(u8(0x04))
+    w.start('Execution', True); w.attr('ProcessID'); w.sub(8, 0x08); w.attr('ThreadID'); w.sub(9, 0x08); w.add(u8(0x03))
+    w.start('Channel'); w.add(u8(0x02)); w.sub(4, 0x01); w.add(u8(0x04))
+    w.start('Computer'); w.add(u8(0x02)); w.sub(10, 0x01); w.add(u8(0x04))
+    w.add(u8(0x04))  # </System>
+    w.sub(5, 0x21, True)  # EventData（埋め込みBinXML）
+    w.add(u8(0x04))  # </Event>
+    w.add(u8(0x00))
+
+def eventdata(base, pairs):
+    w = W(base)
+    w.add(b'\x0f\x01\x01\x00')
+    w.start('EventData', False, dep=False); w.add(u8(0x02))
+    for k, v in pairs:
+        w.start('Data', True, dep=False); w.attr('Name'); w.val(k); w.add(u8(0x02)); w.val(v); w.add(u8(0x04))
+    w.add(u8(0x04)); w.add(u8(0))
+    return bytes(w.b)
+
+def record(chunk_pos, event, tmpl_off):
+    prov, eid, unix, rid, chan, pairs, qual, level = event
+    vals = [
+        (0x01, utf16(prov)), (0x06, u16(eid)), (0x11, u64(filetime(unix))), (0x0a, u64(rid)), (0x01, utf16(chan)),
+        (0x21, lambda p: eventdata(p, pairs)),
+        (0x06, u16(qual)) if qual is not None else (0x00, b''),
+        (0x04, u8(level)), (0x08, u32(4)), (0x08, u32(88)), (0x01, utf16('HOST1')),
+    ]
+    w = W(chunk_pos + 24)
+    w.add(b'\x0f\x01\x01\x00')
+    w.add(u8(0x0c) + u8(1) + u32(1))
+    if tmpl_off is None:  # 最初のレコードはテンプレート定義をインライン配置
+        tmpl_off = w.pos() + 4
+        w.add(u32(tmpl_off))
+        d = W(tmpl_off + 24); template_def(d)
+        w.add(u32(0) + b'\x01' * 16 + u32(len(d.b))); w.add(d.b)
+    else:
+        w.add(u32(tmpl_off))
+    p = w.pos() + 4 + 4 * len(vals)  # 置換値本体の開始位置
+    blobs = []
+    for t, v in vals:
+        if t == 0x21: v = v(p)
+        blobs.append((t, v)); p += len(v)
+    w.add(u32(len(blobs)))
+    for t, v in blobs: w.add(u16(len(v)) + u8(t) + u8(0))
+    for t, v in blobs: w.add(v)
+    w.add(u8(0))
+    size = 24 + len(w.b) + 4
+    return b'**\x00\x00' + u32(size) + u64(rid) + u64(filetime(unix)) + bytes(w.b) + u32(size), tmpl_off
+
+def write_evtx(path, events):
+    chunk = bytearray(512)
+    chunk[0:8] = b'ElfChnk\x00'
+    tmpl_off = None
+    for event in events:
+        rec, tmpl_off = record(len(chunk), event, tmpl_off)
+        chunk += rec
+    struct.pack_into('<I', chunk, 48, len(chunk))  # 空き領域の開始位置
+    chunk += bytearray(65536 - len(chunk))
+    hdr = bytearray(4096); hdr[0:8] = b'ElfFile\x00'
+    struct.pack_into('<I', hdr, 32, 128); struct.pack_into('<H', hdr, 36, 1); struct.pack_into('<H', hdr, 38, 3); struct.pack_into('<H', hdr, 42, 1)
+    with open(path, 'wb') as f:
+        f.write(bytes(hdr) + bytes(chunk))
+
+SECURITY = 'Microsoft-Windows-Security-Auditing'
+out = sys.argv[1] if len(sys.argv) > 1 else os.path.dirname(os.path.abspath(__file__))
+write_evtx(os.path.join(out, 'System.evtx'), [
+    ('EventLog', 6005, 1752127425, 7001, 'System', [], 32768, 4),
+    ('Service Control Manager', 7036, 1752127430, 7002, 'System', [('param1', 'Windows Update'), ('param2', 'running')], 16384, 4),
+    ('EventLog', 6006, 1752130800, 7003, 'System', [], 32768, 4),
+])
+write_evtx(os.path.join(out, 'Security.evtx'), [
+    (SECURITY, 4688, 1752127426, 1001, 'Security', [('SubjectUserName', 'HOST1$'), ('NewProcessId', '0x1a4'), ('NewProcessName', 'C:\\Windows\\System32\\cmd.exe'), ('CommandLine', 'cmd.exe /c "a & b"'), ('ParentProcessId', '0x2f0')], None, 0),
+    (SECURITY, 4624, 1752127433, 1002, 'Security', [('SubjectUserName', '-'), ('TargetUserName', 'alice'), ('TargetDomainName', 'HOST1'), ('LogonType', '10'), ('IpAddress', '10.0.0.5')], None, 0),
+])